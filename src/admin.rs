@@ -0,0 +1,246 @@
+//! Typed results for server administration commands.
+use bson::{self, Bson};
+use common::{get_f64, get_i64};
+use cursor::Cursor;
+use Result;
+use std::vec;
+
+/// Connection counts reported by `serverStatus`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Connections {
+    pub current: i64,
+    pub available: i64,
+    pub total_created: i64,
+}
+
+/// Operation counts since the server started, as reported by `serverStatus`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Opcounters {
+    pub insert: i64,
+    pub query: i64,
+    pub update: i64,
+    pub delete: i64,
+    pub getmore: i64,
+    pub command: i64,
+}
+
+/// The state of a server, as reported by `serverStatus`.
+///
+/// Only commonly used metrics are extracted; the complete reply is available as `raw`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServerStatus {
+    pub host: String,
+    pub version: String,
+    /// Either `mongod` or `mongos`.
+    pub process: String,
+    pub uptime_secs: f64,
+    pub connections: Connections,
+    pub opcounters: Opcounters,
+    /// Resident memory in megabytes.
+    pub resident_mb: Option<i64>,
+    /// Virtual memory in megabytes.
+    pub virtual_mb: Option<i64>,
+    pub raw: bson::Document,
+}
+
+impl ServerStatus {
+    /// Extracts the server state from a `serverStatus` reply.
+    pub fn new(raw: bson::Document) -> ServerStatus {
+        let connections = match raw.get_document("connections") {
+            Ok(doc) => {
+                Connections {
+                    current: get_i64(doc, "current").unwrap_or(0),
+                    available: get_i64(doc, "available").unwrap_or(0),
+                    total_created: get_i64(doc, "totalCreated").unwrap_or(0),
+                }
+            }
+            Err(_) => Connections::default(),
+        };
+
+        let opcounters = match raw.get_document("opcounters") {
+            Ok(doc) => {
+                Opcounters {
+                    insert: get_i64(doc, "insert").unwrap_or(0),
+                    query: get_i64(doc, "query").unwrap_or(0),
+                    update: get_i64(doc, "update").unwrap_or(0),
+                    delete: get_i64(doc, "delete").unwrap_or(0),
+                    getmore: get_i64(doc, "getmore").unwrap_or(0),
+                    command: get_i64(doc, "command").unwrap_or(0),
+                }
+            }
+            Err(_) => Opcounters::default(),
+        };
+
+        let (resident_mb, virtual_mb) = match raw.get_document("mem") {
+            Ok(mem) => (get_i64(mem, "resident"), get_i64(mem, "virtual")),
+            Err(_) => (None, None),
+        };
+
+        ServerStatus {
+            host: raw.get_str("host").unwrap_or_default().to_owned(),
+            version: raw.get_str("version").unwrap_or_default().to_owned(),
+            process: raw.get_str("process").unwrap_or_default().to_owned(),
+            uptime_secs: get_f64(&raw, "uptime").unwrap_or(0.0),
+            connections: connections,
+            opcounters: opcounters,
+            resident_mb: resident_mb,
+            virtual_mb: virtual_mb,
+            raw: raw,
+        }
+    }
+}
+
+/// An operation in progress, as reported by `$currentOp`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Operation {
+    /// The operation id to pass to `kill_op`, which is a string of the form `shard:opid` on
+    /// `mongos`.
+    pub opid: Option<Bson>,
+    pub active: bool,
+    /// The operation type, such as `query`, `command` or `getmore`.
+    pub op: String,
+    pub ns: Option<String>,
+    pub command: Option<bson::Document>,
+    pub plan_summary: Option<String>,
+    pub secs_running: Option<i64>,
+    pub microsecs_running: Option<i64>,
+    /// The host and port of the client that issued the operation.
+    pub client: Option<String>,
+    /// A description of the connection or thread running the operation.
+    pub desc: Option<String>,
+    pub waiting_for_lock: bool,
+    pub raw: bson::Document,
+}
+
+impl Operation {
+    /// Extracts an operation from a `$currentOp` result document or `inprog` entry.
+    pub fn new(raw: bson::Document) -> Operation {
+        Operation {
+            opid: raw.get("opid").cloned(),
+            active: raw.get_bool("active").unwrap_or(false),
+            op: raw.get_str("op").unwrap_or_default().to_owned(),
+            ns: raw.get_str("ns").ok().map(String::from),
+            command: raw.get_document("command").ok().cloned(),
+            plan_summary: raw.get_str("planSummary").ok().map(String::from),
+            secs_running: get_i64(&raw, "secs_running"),
+            microsecs_running: get_i64(&raw, "microsecs_running"),
+            client: raw.get_str("client").ok().map(String::from),
+            desc: raw.get_str("desc").ok().map(String::from),
+            waiting_for_lock: raw.get_bool("waitingForLock").unwrap_or(false),
+            raw: raw,
+        }
+    }
+}
+
+/// Iterates over the operations returned by `Client::current_op`.
+pub struct Operations {
+    cursor: Option<Cursor>,
+    inprog: vec::IntoIter<bson::Document>,
+}
+
+impl Operations {
+    pub fn new(cursor: Cursor) -> Operations {
+        Operations {
+            cursor: Some(cursor),
+            inprog: Vec::new().into_iter(),
+        }
+    }
+
+    /// Wraps the `inprog` array of a `currentOp` command reply, for servers older than 3.6.
+    pub fn from_inprog(inprog: Vec<bson::Document>) -> Operations {
+        Operations {
+            cursor: None,
+            inprog: inprog.into_iter(),
+        }
+    }
+}
+
+impl Iterator for Operations {
+    type Item = Result<Operation>;
+
+    fn next(&mut self) -> Option<Result<Operation>> {
+        match self.cursor {
+            Some(ref mut cursor) => cursor.next().map(|result| result.map(Operation::new)),
+            None => self.inprog.next().map(|raw| Ok(Operation::new(raw))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bson::{bson, doc};
+    use super::*;
+
+    #[test]
+    fn parses_server_status() {
+        let status = ServerStatus::new(doc!{
+            "host": "db1:27017",
+            "version": "6.0.5",
+            "process": "mongod",
+            "uptime": 5321.0,
+            "connections": {"current": 12, "available": 838848, "totalCreated": 40i64},
+            "opcounters": {
+                "insert": 5i64,
+                "query": 9i64,
+                "update": 1i64,
+                "delete": 0i64,
+                "getmore": 2i64,
+                "command": 310i64,
+            },
+            "mem": {"bits": 64, "resident": 120, "virtual": 2700},
+            "ok": 1.0,
+        });
+
+        assert_eq!("db1:27017", status.host);
+        assert_eq!("mongod", status.process);
+        assert_eq!(5321.0, status.uptime_secs);
+        assert_eq!(12, status.connections.current);
+        assert_eq!(40, status.connections.total_created);
+        assert_eq!(9, status.opcounters.query);
+        assert_eq!(310, status.opcounters.command);
+        assert_eq!(Some(120), status.resident_mb);
+        assert_eq!(Some(2700), status.virtual_mb);
+    }
+
+    #[test]
+    fn parses_current_op_entries() {
+        let operation = Operation::new(doc!{
+            "type": "op",
+            "desc": "conn42",
+            "active": true,
+            "opid": 5001,
+            "secs_running": 12i64,
+            "microsecs_running": 12000431i64,
+            "op": "query",
+            "ns": "shop.orders",
+            "command": {"find": "orders", "filter": {"x": 1}},
+            "planSummary": "COLLSCAN",
+            "client": "10.0.0.5:51234",
+            "waitingForLock": false,
+        });
+
+        assert_eq!(Some(Bson::I32(5001)), operation.opid);
+        assert!(operation.active);
+        assert_eq!("query", operation.op);
+        assert_eq!(Some(String::from("shop.orders")), operation.ns);
+        assert_eq!(Some(String::from("COLLSCAN")), operation.plan_summary);
+        assert_eq!(Some(12), operation.secs_running);
+        assert_eq!(Some(String::from("conn42")), operation.desc);
+        assert!(!operation.waiting_for_lock);
+
+        let idle = Operation::new(doc!{"type": "idleSession", "active": false});
+        assert_eq!(None, idle.opid);
+        assert_eq!("", idle.op);
+    }
+
+    #[test]
+    fn iterates_legacy_inprog_entries() {
+        let operations = Operations::from_inprog(vec![
+            doc!{"opid": 7, "op": "query", "active": true},
+            doc!{"opid": 8, "op": "getmore", "active": false},
+        ]);
+
+        let ops: Vec<_> = operations.map(|op| op.unwrap().op).collect();
+        assert_eq!(vec!["query", "getmore"], ops);
+    }
+}
@@ -0,0 +1,804 @@
+//! Typed builders for aggregation pipelines.
+//!
+//! A `Pipeline` is built stage by stage and converts into the `Vec<bson::Document>` accepted by
+//! `Collection::aggregate`:
+//!
+//! ```ignore
+//! let pipeline = Pipeline::new()
+//!     .match_(doc! { "status": "A" })
+//!     .group(Group::new("$cust_id").field("total", Accumulator::Sum(Bson::from("$amount"))))
+//!     .sort(doc! { "total": -1 });
+//!
+//! let cursor = coll.aggregate(pipeline, None)?;
+//! ```
+//!
+//! Stages without a typed builder can be appended as raw documents with `Pipeline::stage`.
+use bson::{self, Bson, bson, doc};
+
+/// A group accumulator operator, applied to an expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Accumulator {
+    Sum(Bson),
+    Avg(Bson),
+    Min(Bson),
+    Max(Bson),
+    First(Bson),
+    Last(Bson),
+    Push(Bson),
+    AddToSet(Bson),
+    StdDevPop(Bson),
+    StdDevSamp(Bson),
+    /// Any other accumulator, given by its operator name including the leading `$`.
+    Custom(String, Bson),
+}
+
+impl Accumulator {
+    pub fn to_bson(&self) -> bson::Document {
+        let (operator, expression) = match *self {
+            Accumulator::Sum(ref expr) => ("$sum", expr),
+            Accumulator::Avg(ref expr) => ("$avg", expr),
+            Accumulator::Min(ref expr) => ("$min", expr),
+            Accumulator::Max(ref expr) => ("$max", expr),
+            Accumulator::First(ref expr) => ("$first", expr),
+            Accumulator::Last(ref expr) => ("$last", expr),
+            Accumulator::Push(ref expr) => ("$push", expr),
+            Accumulator::AddToSet(ref expr) => ("$addToSet", expr),
+            Accumulator::StdDevPop(ref expr) => ("$stdDevPop", expr),
+            Accumulator::StdDevSamp(ref expr) => ("$stdDevSamp", expr),
+            Accumulator::Custom(ref operator, ref expr) => (&operator[..], expr),
+        };
+
+        let mut document = bson::Document::new();
+        document.insert(operator, expression.clone());
+        document
+    }
+}
+
+// Encodes a list of named accumulators as the fields of a document.
+fn accumulator_fields(fields: &[(String, Accumulator)]) -> bson::Document {
+    let mut document = bson::Document::new();
+    for &(ref name, ref accumulator) in fields {
+        document.insert(name.to_owned(), accumulator.to_bson());
+    }
+    document
+}
+
+/// A `$group` stage, grouping documents by `id` and computing each accumulated field.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group {
+    pub id: Bson,
+    pub fields: Vec<(String, Accumulator)>,
+}
+
+impl Group {
+    /// Creates a group by the given expression; `Bson::Null` groups every document together.
+    pub fn new<T: Into<Bson>>(id: T) -> Group {
+        Group {
+            id: id.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Adds a field computed by an accumulator to each group.
+    pub fn field(mut self, name: &str, accumulator: Accumulator) -> Group {
+        self.fields.push((String::from(name), accumulator));
+        self
+    }
+
+    pub fn to_bson(&self) -> bson::Document {
+        let mut document = doc! { "_id": self.id.clone() };
+        for (name, value) in accumulator_fields(&self.fields) {
+            document.insert(name, value);
+        }
+        document
+    }
+}
+
+/// An `$unwind` stage, which outputs a document for each element of an array field.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Unwind {
+    /// The field path of the array, prefixed with `$`.
+    pub path: String,
+    /// The name of a field in which to store each element's array index.
+    pub include_array_index: Option<String>,
+    /// Whether to output documents whose array is missing, null, or empty.
+    pub preserve_null_and_empty_arrays: Option<bool>,
+}
+
+impl Unwind {
+    pub fn new(path: &str) -> Unwind {
+        Unwind {
+            path: String::from(path),
+            ..Default::default()
+        }
+    }
+
+    /// Encodes the stage argument, using the short string form when no options are set.
+    pub fn to_bson(&self) -> Bson {
+        if self.include_array_index.is_none() && self.preserve_null_and_empty_arrays.is_none() {
+            return Bson::String(self.path.clone());
+        }
+
+        let mut document = doc! { "path": self.path.clone() };
+
+        if let Some(ref include_array_index) = self.include_array_index {
+            document.insert("includeArrayIndex", include_array_index.clone());
+        }
+
+        if let Some(preserve) = self.preserve_null_and_empty_arrays {
+            document.insert("preserveNullAndEmptyArrays", preserve);
+        }
+
+        Bson::Document(document)
+    }
+}
+
+/// A `$lookup` stage, joining documents from another collection either by equality between
+/// fields or by running a pipeline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lookup {
+    pub from: String,
+    pub local_field: Option<String>,
+    pub foreign_field: Option<String>,
+    /// Variables that the pipeline can access with the `$$` prefix.
+    pub let_vars: Option<bson::Document>,
+    pub pipeline: Option<Pipeline>,
+    /// The name of the array field in which to store the joined documents.
+    pub as_field: String,
+}
+
+impl Lookup {
+    /// Creates an equality match between `local_field` and `foreign_field` of `from`.
+    pub fn new(from: &str, local_field: &str, foreign_field: &str, as_field: &str) -> Lookup {
+        Lookup {
+            from: String::from(from),
+            local_field: Some(String::from(local_field)),
+            foreign_field: Some(String::from(foreign_field)),
+            let_vars: None,
+            pipeline: None,
+            as_field: String::from(as_field),
+        }
+    }
+
+    /// Creates a join that runs `pipeline` against `from` (MongoDB 3.6+).
+    pub fn with_pipeline(
+        from: &str,
+        let_vars: Option<bson::Document>,
+        pipeline: Pipeline,
+        as_field: &str,
+    ) -> Lookup {
+        Lookup {
+            from: String::from(from),
+            local_field: None,
+            foreign_field: None,
+            let_vars: let_vars,
+            pipeline: Some(pipeline),
+            as_field: String::from(as_field),
+        }
+    }
+
+    pub fn to_bson(&self) -> bson::Document {
+        let mut document = doc! { "from": self.from.clone() };
+
+        if let Some(ref local_field) = self.local_field {
+            document.insert("localField", local_field.clone());
+        }
+
+        if let Some(ref foreign_field) = self.foreign_field {
+            document.insert("foreignField", foreign_field.clone());
+        }
+
+        if let Some(ref let_vars) = self.let_vars {
+            document.insert("let", let_vars.clone());
+        }
+
+        if let Some(ref pipeline) = self.pipeline {
+            document.insert("pipeline", pipeline.to_bson());
+        }
+
+        document.insert("as", self.as_field.clone());
+        document
+    }
+}
+
+/// A `$facet` stage, which runs several sub-pipelines over the same input documents.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Facet {
+    pub pipelines: Vec<(String, Pipeline)>,
+}
+
+impl Facet {
+    pub fn new() -> Facet {
+        Default::default()
+    }
+
+    /// Adds a sub-pipeline whose results are stored in the field `name`.
+    pub fn pipeline(mut self, name: &str, pipeline: Pipeline) -> Facet {
+        self.pipelines.push((String::from(name), pipeline));
+        self
+    }
+
+    pub fn to_bson(&self) -> bson::Document {
+        let mut document = bson::Document::new();
+        for &(ref name, ref pipeline) in &self.pipelines {
+            document.insert(name.to_owned(), pipeline.to_bson());
+        }
+        document
+    }
+}
+
+/// A `$bucket` stage, which groups documents into ranges of an expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bucket {
+    pub group_by: Bson,
+    /// The inclusive lower bounds of each bucket, followed by the exclusive upper bound of the
+    /// last bucket.
+    pub boundaries: Vec<Bson>,
+    /// The bucket for documents outside the boundaries.
+    pub default: Option<Bson>,
+    /// The fields computed for each bucket; the server counts documents by default.
+    pub output: Option<Vec<(String, Accumulator)>>,
+}
+
+impl Bucket {
+    pub fn new<T: Into<Bson>>(group_by: T, boundaries: Vec<Bson>) -> Bucket {
+        Bucket {
+            group_by: group_by.into(),
+            boundaries: boundaries,
+            default: None,
+            output: None,
+        }
+    }
+
+    pub fn to_bson(&self) -> bson::Document {
+        let mut document = doc! {
+            "groupBy": self.group_by.clone(),
+            "boundaries": self.boundaries.clone(),
+        };
+
+        if let Some(ref default) = self.default {
+            document.insert("default", default.clone());
+        }
+
+        if let Some(ref output) = self.output {
+            document.insert("output", accumulator_fields(output));
+        }
+
+        document
+    }
+}
+
+/// A `$graphLookup` stage, which performs a recursive search on a collection.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphLookup {
+    pub from: String,
+    pub start_with: Bson,
+    pub connect_from_field: String,
+    pub connect_to_field: String,
+    pub as_field: String,
+    pub max_depth: Option<i64>,
+    pub depth_field: Option<String>,
+    pub restrict_search_with_match: Option<bson::Document>,
+}
+
+impl GraphLookup {
+    pub fn new<T: Into<Bson>>(
+        from: &str,
+        start_with: T,
+        connect_from_field: &str,
+        connect_to_field: &str,
+        as_field: &str,
+    ) -> GraphLookup {
+        GraphLookup {
+            from: String::from(from),
+            start_with: start_with.into(),
+            connect_from_field: String::from(connect_from_field),
+            connect_to_field: String::from(connect_to_field),
+            as_field: String::from(as_field),
+            max_depth: None,
+            depth_field: None,
+            restrict_search_with_match: None,
+        }
+    }
+
+    pub fn to_bson(&self) -> bson::Document {
+        let mut document = doc! {
+            "from": self.from.clone(),
+            "startWith": self.start_with.clone(),
+            "connectFromField": self.connect_from_field.clone(),
+            "connectToField": self.connect_to_field.clone(),
+            "as": self.as_field.clone(),
+        };
+
+        if let Some(max_depth) = self.max_depth {
+            document.insert("maxDepth", max_depth);
+        }
+
+        if let Some(ref depth_field) = self.depth_field {
+            document.insert("depthField", depth_field.clone());
+        }
+
+        if let Some(ref restrict_search_with_match) = self.restrict_search_with_match {
+            document.insert("restrictSearchWithMatch", restrict_search_with_match.clone());
+        }
+
+        document
+    }
+}
+
+/// An `$out` stage, which replaces a collection with the pipeline's results.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Out {
+    /// The output database; defaults to the database of the aggregation (MongoDB 4.4+).
+    pub db: Option<String>,
+    pub coll: String,
+}
+
+impl Out {
+    pub fn new(coll: &str) -> Out {
+        Out {
+            db: None,
+            coll: String::from(coll),
+        }
+    }
+
+    pub fn to_bson(&self) -> Bson {
+        match self.db {
+            Some(ref db) => Bson::Document(doc! { "db": db.clone(), "coll": self.coll.clone() }),
+            None => Bson::String(self.coll.clone()),
+        }
+    }
+}
+
+/// The action a `$merge` stage takes when a result matches an existing document.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MergeWhenMatched {
+    Replace,
+    KeepExisting,
+    Merge,
+    Fail,
+    /// Updates the existing document with an update pipeline.
+    Pipeline(Vec<bson::Document>),
+}
+
+impl MergeWhenMatched {
+    pub fn to_bson(&self) -> Bson {
+        match *self {
+            MergeWhenMatched::Replace => Bson::String(String::from("replace")),
+            MergeWhenMatched::KeepExisting => Bson::String(String::from("keepExisting")),
+            MergeWhenMatched::Merge => Bson::String(String::from("merge")),
+            MergeWhenMatched::Fail => Bson::String(String::from("fail")),
+            MergeWhenMatched::Pipeline(ref stages) => {
+                Bson::Array(stages.iter().cloned().map(Bson::Document).collect())
+            }
+        }
+    }
+}
+
+/// The action a `$merge` stage takes when a result does not match an existing document.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MergeWhenNotMatched {
+    Insert,
+    Discard,
+    Fail,
+}
+
+impl MergeWhenNotMatched {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            MergeWhenNotMatched::Insert => "insert",
+            MergeWhenNotMatched::Discard => "discard",
+            MergeWhenNotMatched::Fail => "fail",
+        }
+    }
+}
+
+/// A `$merge` stage, which merges the pipeline's results into a collection (MongoDB 4.2+).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Merge {
+    /// The output database; defaults to the database of the aggregation.
+    pub into_db: Option<String>,
+    pub into: String,
+    /// The fields that identify a matching document; `_id` by default.
+    pub on: Option<Vec<String>>,
+    /// Variables that a `when_matched` pipeline can access with the `$$` prefix.
+    pub let_vars: Option<bson::Document>,
+    pub when_matched: Option<MergeWhenMatched>,
+    pub when_not_matched: Option<MergeWhenNotMatched>,
+}
+
+impl Merge {
+    pub fn new(into: &str) -> Merge {
+        Merge {
+            into: String::from(into),
+            ..Default::default()
+        }
+    }
+
+    pub fn to_bson(&self) -> bson::Document {
+        let into = match self.into_db {
+            Some(ref db) => Bson::Document(doc! { "db": db.clone(), "coll": self.into.clone() }),
+            None => Bson::String(self.into.clone()),
+        };
+
+        let mut document = doc! { "into": into };
+
+        if let Some(ref on) = self.on {
+            if on.len() == 1 {
+                document.insert("on", on[0].clone());
+            } else {
+                let fields = on.iter().cloned().map(Bson::String).collect::<Vec<_>>();
+                document.insert("on", fields);
+            }
+        }
+
+        if let Some(ref let_vars) = self.let_vars {
+            document.insert("let", let_vars.clone());
+        }
+
+        if let Some(ref when_matched) = self.when_matched {
+            document.insert("whenMatched", when_matched.to_bson());
+        }
+
+        if let Some(when_not_matched) = self.when_not_matched {
+            document.insert("whenNotMatched", when_not_matched.as_str());
+        }
+
+        document
+    }
+}
+
+/// A single aggregation pipeline stage.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Stage {
+    Match(bson::Document),
+    Project(bson::Document),
+    Group(Group),
+    Sort(bson::Document),
+    Limit(i64),
+    Skip(i64),
+    Unwind(Unwind),
+    Lookup(Lookup),
+    Facet(Facet),
+    Bucket(Bucket),
+    GraphLookup(GraphLookup),
+    AddFields(bson::Document),
+    ReplaceRoot(Bson),
+    Sample(i64),
+    Out(Out),
+    Merge(Merge),
+    /// A stage given as a raw document, such as `{ "$count": "total" }`.
+    Raw(bson::Document),
+}
+
+impl Stage {
+    pub fn to_bson(&self) -> bson::Document {
+        let (name, argument) = match *self {
+            Stage::Match(ref filter) => ("$match", Bson::Document(filter.clone())),
+            Stage::Project(ref projection) => ("$project", Bson::Document(projection.clone())),
+            Stage::Group(ref group) => ("$group", Bson::Document(group.to_bson())),
+            Stage::Sort(ref sort) => ("$sort", Bson::Document(sort.clone())),
+            Stage::Limit(limit) => ("$limit", Bson::I64(limit)),
+            Stage::Skip(skip) => ("$skip", Bson::I64(skip)),
+            Stage::Unwind(ref unwind) => ("$unwind", unwind.to_bson()),
+            Stage::Lookup(ref lookup) => ("$lookup", Bson::Document(lookup.to_bson())),
+            Stage::Facet(ref facet) => ("$facet", Bson::Document(facet.to_bson())),
+            Stage::Bucket(ref bucket) => ("$bucket", Bson::Document(bucket.to_bson())),
+            Stage::GraphLookup(ref graph_lookup) => {
+                ("$graphLookup", Bson::Document(graph_lookup.to_bson()))
+            }
+            Stage::AddFields(ref fields) => ("$addFields", Bson::Document(fields.clone())),
+            Stage::ReplaceRoot(ref new_root) => {
+                ("$replaceRoot", Bson::Document(doc! { "newRoot": new_root.clone() }))
+            }
+            Stage::Sample(size) => ("$sample", Bson::Document(doc! { "size": size })),
+            Stage::Out(ref out) => ("$out", out.to_bson()),
+            Stage::Merge(ref merge) => ("$merge", Bson::Document(merge.to_bson())),
+            Stage::Raw(ref document) => return document.clone(),
+        };
+
+        let mut document = bson::Document::new();
+        document.insert(name, argument);
+        document
+    }
+}
+
+/// A fluent builder for an aggregation pipeline.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Pipeline {
+    pub stages: Vec<Stage>,
+}
+
+impl Pipeline {
+    pub fn new() -> Pipeline {
+        Default::default()
+    }
+
+    /// Appends a stage to the pipeline.
+    pub fn push(mut self, stage: Stage) -> Pipeline {
+        self.stages.push(stage);
+        self
+    }
+
+    /// Appends a stage given as a raw document.
+    pub fn stage(self, stage: bson::Document) -> Pipeline {
+        self.push(Stage::Raw(stage))
+    }
+
+    /// Filters documents with a query. The trailing underscore avoids the `match` keyword.
+    pub fn match_(self, filter: bson::Document) -> Pipeline {
+        self.push(Stage::Match(filter))
+    }
+
+    pub fn project(self, projection: bson::Document) -> Pipeline {
+        self.push(Stage::Project(projection))
+    }
+
+    pub fn group(self, group: Group) -> Pipeline {
+        self.push(Stage::Group(group))
+    }
+
+    pub fn sort(self, sort: bson::Document) -> Pipeline {
+        self.push(Stage::Sort(sort))
+    }
+
+    pub fn limit(self, limit: i64) -> Pipeline {
+        self.push(Stage::Limit(limit))
+    }
+
+    pub fn skip(self, skip: i64) -> Pipeline {
+        self.push(Stage::Skip(skip))
+    }
+
+    pub fn unwind(self, unwind: Unwind) -> Pipeline {
+        self.push(Stage::Unwind(unwind))
+    }
+
+    pub fn lookup(self, lookup: Lookup) -> Pipeline {
+        self.push(Stage::Lookup(lookup))
+    }
+
+    pub fn facet(self, facet: Facet) -> Pipeline {
+        self.push(Stage::Facet(facet))
+    }
+
+    pub fn bucket(self, bucket: Bucket) -> Pipeline {
+        self.push(Stage::Bucket(bucket))
+    }
+
+    pub fn graph_lookup(self, graph_lookup: GraphLookup) -> Pipeline {
+        self.push(Stage::GraphLookup(graph_lookup))
+    }
+
+    pub fn add_fields(self, fields: bson::Document) -> Pipeline {
+        self.push(Stage::AddFields(fields))
+    }
+
+    pub fn replace_root<T: Into<Bson>>(self, new_root: T) -> Pipeline {
+        self.push(Stage::ReplaceRoot(new_root.into()))
+    }
+
+    pub fn sample(self, size: i64) -> Pipeline {
+        self.push(Stage::Sample(size))
+    }
+
+    pub fn out(self, out: Out) -> Pipeline {
+        self.push(Stage::Out(out))
+    }
+
+    pub fn merge(self, merge: Merge) -> Pipeline {
+        self.push(Stage::Merge(merge))
+    }
+
+    /// Encodes the pipeline as an array of stage documents.
+    pub fn to_bson(&self) -> Bson {
+        Bson::Array(self.stages.iter().map(|stage| Bson::Document(stage.to_bson())).collect())
+    }
+}
+
+impl From<Pipeline> for Vec<bson::Document> {
+    fn from(pipeline: Pipeline) -> Self {
+        pipeline.stages.iter().map(Stage::to_bson).collect()
+    }
+}
+
+impl From<Vec<bson::Document>> for Pipeline {
+    fn from(stages: Vec<bson::Document>) -> Self {
+        Pipeline { stages: stages.into_iter().map(Stage::Raw).collect() }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn basic_stages() {
+        let pipeline = Pipeline::new()
+            .match_(doc!{"status": "A"})
+            .project(doc!{"status": 1})
+            .add_fields(doc!{"total": {"$add": ["$a", "$b"]}})
+            .sort(doc!{"total": -1})
+            .skip(5)
+            .limit(10)
+            .sample(3)
+            .replace_root("$inner")
+            .stage(doc!{"$count": "n"});
+
+        let expected = vec![
+            doc!{"$match": {"status": "A"}},
+            doc!{"$project": {"status": 1}},
+            doc!{"$addFields": {"total": {"$add": ["$a", "$b"]}}},
+            doc!{"$sort": {"total": -1}},
+            doc!{"$skip": 5_i64},
+            doc!{"$limit": 10_i64},
+            doc!{"$sample": {"size": 3_i64}},
+            doc!{"$replaceRoot": {"newRoot": "$inner"}},
+            doc!{"$count": "n"},
+        ];
+
+        assert_eq!(expected, Vec::<bson::Document>::from(pipeline));
+    }
+
+    #[test]
+    fn group_with_accumulators() {
+        let group = Group::new("$cust_id")
+            .field("total", Accumulator::Sum(Bson::from("$amount")))
+            .field("count", Accumulator::Sum(Bson::I32(1)))
+            .field("items", Accumulator::AddToSet(Bson::from("$item")))
+            .field("merged", Accumulator::Custom(String::from("$mergeObjects"), Bson::from("$m")));
+
+        assert_eq!(
+            doc!{
+                "$group": {
+                    "_id": "$cust_id",
+                    "total": {"$sum": "$amount"},
+                    "count": {"$sum": 1},
+                    "items": {"$addToSet": "$item"},
+                    "merged": {"$mergeObjects": "$m"},
+                }
+            },
+            Stage::Group(group).to_bson()
+        );
+    }
+
+    #[test]
+    fn unwind_forms() {
+        assert_eq!(doc!{"$unwind": "$tags"}, Stage::Unwind(Unwind::new("$tags")).to_bson());
+
+        let mut unwind = Unwind::new("$tags");
+        unwind.include_array_index = Some(String::from("index"));
+        unwind.preserve_null_and_empty_arrays = Some(true);
+        assert_eq!(
+            doc!{
+                "$unwind": {
+                    "path": "$tags",
+                    "includeArrayIndex": "index",
+                    "preserveNullAndEmptyArrays": true,
+                }
+            },
+            Stage::Unwind(unwind).to_bson()
+        );
+    }
+
+    #[test]
+    fn lookup_forms() {
+        let lookup = Lookup::new("inventory", "item", "sku", "docs");
+        assert_eq!(
+            doc!{
+                "$lookup": {
+                    "from": "inventory",
+                    "localField": "item",
+                    "foreignField": "sku",
+                    "as": "docs",
+                }
+            },
+            Stage::Lookup(lookup).to_bson()
+        );
+
+        let pipeline = Pipeline::new().match_(doc!{"$expr": {"$eq": ["$sku", "$$item"]}});
+        let let_vars = Some(doc!{"item": "$item"});
+        let lookup = Lookup::with_pipeline("inventory", let_vars, pipeline, "docs");
+        assert_eq!(
+            doc!{
+                "$lookup": {
+                    "from": "inventory",
+                    "let": {"item": "$item"},
+                    "pipeline": [{"$match": {"$expr": {"$eq": ["$sku", "$$item"]}}}],
+                    "as": "docs",
+                }
+            },
+            Stage::Lookup(lookup).to_bson()
+        );
+    }
+
+    #[test]
+    fn facet_bucket_and_graph_lookup() {
+        let facet = Facet::new()
+            .pipeline("byTag", Pipeline::new().unwind(Unwind::new("$tags")))
+            .pipeline("first", Pipeline::new().limit(1));
+        assert_eq!(
+            doc!{
+                "$facet": {
+                    "byTag": [{"$unwind": "$tags"}],
+                    "first": [{"$limit": 1_i64}],
+                }
+            },
+            Stage::Facet(facet).to_bson()
+        );
+
+        let mut bucket = Bucket::new("$price", vec![Bson::I32(0), Bson::I32(100)]);
+        bucket.default = Some(Bson::from("Other"));
+        bucket.output = Some(vec![(String::from("n"), Accumulator::Sum(Bson::I32(1)))]);
+        assert_eq!(
+            doc!{
+                "$bucket": {
+                    "groupBy": "$price",
+                    "boundaries": [0, 100],
+                    "default": "Other",
+                    "output": {"n": {"$sum": 1}},
+                }
+            },
+            Stage::Bucket(bucket).to_bson()
+        );
+
+        let mut graph_lookup =
+            GraphLookup::new("employees", "$reportsTo", "reportsTo", "name", "chain");
+        graph_lookup.max_depth = Some(2);
+        graph_lookup.depth_field = Some(String::from("depth"));
+        assert_eq!(
+            doc!{
+                "$graphLookup": {
+                    "from": "employees",
+                    "startWith": "$reportsTo",
+                    "connectFromField": "reportsTo",
+                    "connectToField": "name",
+                    "as": "chain",
+                    "maxDepth": 2_i64,
+                    "depthField": "depth",
+                }
+            },
+            Stage::GraphLookup(graph_lookup).to_bson()
+        );
+    }
+
+    #[test]
+    fn out_and_merge() {
+        assert_eq!(doc!{"$out": "results"}, Stage::Out(Out::new("results")).to_bson());
+
+        let mut out = Out::new("results");
+        out.db = Some(String::from("reporting"));
+        assert_eq!(
+            doc!{"$out": {"db": "reporting", "coll": "results"}},
+            Stage::Out(out).to_bson()
+        );
+
+        assert_eq!(
+            doc!{"$merge": {"into": "results"}},
+            Stage::Merge(Merge::new("results")).to_bson()
+        );
+
+        let mut merge = Merge::new("results");
+        merge.into_db = Some(String::from("reporting"));
+        merge.on = Some(vec![String::from("a"), String::from("b")]);
+        merge.when_matched = Some(MergeWhenMatched::Pipeline(vec![doc!{"$set": {"x": 1}}]));
+        merge.when_not_matched = Some(MergeWhenNotMatched::Discard);
+        assert_eq!(
+            doc!{
+                "$merge": {
+                    "into": {"db": "reporting", "coll": "results"},
+                    "on": ["a", "b"],
+                    "whenMatched": [{"$set": {"x": 1}}],
+                    "whenNotMatched": "discard",
+                }
+            },
+            Stage::Merge(merge).to_bson()
+        );
+    }
+
+    #[test]
+    fn raw_documents_round_trip() {
+        let stages = vec![doc!{"$match": {"x": 1}}, doc!{"$count": "n"}];
+        assert_eq!(stages.clone(), Vec::<bson::Document>::from(Pipeline::from(stages)));
+    }
+}
@@ -1,20 +1,192 @@
 //! Models for collection-level batch operations.
-use super::options::WriteModel;
+use super::options::{Collation, Hint, UpdateModifications, UpdateOptions, WriteModel};
 
-use bson::{Bson, Document, bson, doc};
+use bson::{self, Bson, Document, bson, doc};
+use topology::monitor::{DEFAULT_MAX_BSON_OBJECT_SIZE, DEFAULT_MAX_MESSAGE_SIZE_BYTES,
+                        DEFAULT_MAX_WRITE_BATCH_SIZE};
+use topology::server::ServerDescription;
+use wire_protocol::flags::{OpDeleteFlags, OpUpdateFlags};
+use wire_protocol::operations::Message;
+
+use Error::ArgumentError;
+use Result;
+
+use std::cmp;
 use std::convert::From;
+use std::ops::Range;
+
+// Space reserved in each write command for everything other than its statements.
+const COMMAND_OVERHEAD_BYTES: usize = 16 * 1024;
+
+// The space taken in a BSON array by an element's type byte, index key, and null terminator.
+const ARRAY_ELEMENT_OVERHEAD_BYTES: usize = 8;
+
+/// The limits a server places on write commands, which determine how large writes are split.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchLimits {
+    pub max_bson_object_size: usize,
+    pub max_message_size_bytes: usize,
+    pub max_write_batch_size: usize,
+}
+
+impl Default for BatchLimits {
+    fn default() -> Self {
+        BatchLimits::new(0, 0, 0)
+    }
+}
+
+impl BatchLimits {
+    /// Returns the provided limits, substituting the server defaults for any that are unknown.
+    pub fn new(
+        max_bson_object_size: i64,
+        max_message_size_bytes: i64,
+        max_write_batch_size: i64,
+    ) -> BatchLimits {
+        let or_default = |value: i64, default: i64| if value > 0 {
+            value as usize
+        } else {
+            default as usize
+        };
+
+        BatchLimits {
+            max_bson_object_size: or_default(max_bson_object_size, DEFAULT_MAX_BSON_OBJECT_SIZE),
+            max_message_size_bytes: or_default(
+                max_message_size_bytes,
+                DEFAULT_MAX_MESSAGE_SIZE_BYTES,
+            ),
+            max_write_batch_size: or_default(max_write_batch_size, DEFAULT_MAX_WRITE_BATCH_SIZE),
+        }
+    }
+
+    /// Returns the limits last reported by a server.
+    pub fn from_description(description: &ServerDescription) -> BatchLimits {
+        BatchLimits::new(
+            description.max_bson_object_size,
+            description.max_message_size_bytes,
+            description.max_write_batch_size,
+        )
+    }
+
+    /// Splits a sequence of statements, given their encoded sizes, into consecutive ranges that
+    /// each fit into a single write command.
+    ///
+    /// A statement that is too large to share a command is placed in a range of its own.
+    pub fn split(&self, sizes: &[usize]) -> Vec<Range<usize>> {
+        let max_bytes = cmp::min(
+            self.max_bson_object_size,
+            self.max_message_size_bytes.saturating_sub(COMMAND_OVERHEAD_BYTES),
+        );
+
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        let mut bytes = 0;
+
+        for (i, size) in sizes.iter().enumerate() {
+            let size = size + ARRAY_ELEMENT_OVERHEAD_BYTES;
+            let count = i - start;
+
+            if count > 0 && (count >= self.max_write_batch_size || bytes + size > max_bytes) {
+                ranges.push(start..i);
+                start = i;
+                bytes = 0;
+            }
+
+            bytes += size;
+        }
+
+        if start < sizes.len() {
+            ranges.push(start..sizes.len());
+        }
+
+        ranges
+    }
+}
+
+/// Returns the encoded size of a document in bytes.
+pub fn document_size(document: &Document) -> usize {
+    let mut buffer = Vec::new();
+    match bson::encode_document(&mut buffer, document) {
+        Ok(()) => buffer.len(),
+        Err(_) => 0,
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct DeleteModel {
     pub filter: Document,
     pub multi: bool,
+    pub collation: Option<Collation>,
+    pub hint: Option<Hint>,
 }
 
 impl DeleteModel {
-    pub fn new(filter: Document, multi: bool) -> DeleteModel {
+    pub fn new(
+        filter: Document,
+        multi: bool,
+        collation: Option<Collation>,
+        hint: Option<Hint>,
+    ) -> DeleteModel {
         DeleteModel {
             filter: filter,
             multi: multi,
+            collation: collation,
+            hint: hint,
+        }
+    }
+
+    /// Encodes the delete as a legacy OP_DELETE message, which cannot express a
+    /// collation or an index hint.
+    pub fn to_legacy_message(&self, request_id: i32, namespace: &str) -> Result<Message> {
+        if self.collation.is_some() || self.hint.is_some() {
+            return Err(ArgumentError(String::from(
+                "Unacknowledged deletes cannot specify a collation or hint on this server.",
+            )));
+        }
+
+        let flags = if self.multi {
+            OpDeleteFlags::empty()
+        } else {
+            OpDeleteFlags::SINGLE_REMOVE
+        };
+
+        Message::new_delete(request_id, String::from(namespace), flags, self.filter.clone())
+    }
+}
+
+impl From<DeleteModel> for Document {
+    fn from(model: DeleteModel) -> Self {
+        let mut document =
+            doc! {
+                "q": model.filter,
+                "limit": if model.multi { 0_i64 } else { 1_i64 },
+            };
+
+        if let Some(collation) = model.collation {
+            document.insert("collation", collation.to_bson());
+        }
+
+        if let Some(hint) = model.hint {
+            document.insert("hint", hint.to_bson());
+        }
+
+        document
+    }
+}
+
+impl From<DeleteModel> for WriteModel {
+    fn from(model: DeleteModel) -> Self {
+        if model.multi {
+            WriteModel::DeleteMany {
+                filter: model.filter,
+                collation: model.collation,
+                hint: model.hint,
+            }
+        } else {
+            WriteModel::DeleteOne {
+                filter: model.filter,
+                collation: model.collation,
+                hint: model.hint,
+            }
         }
     }
 }
@@ -22,25 +194,61 @@ impl DeleteModel {
 #[derive(Debug, Clone, PartialEq)]
 pub struct UpdateModel {
     pub filter: Document,
-    pub update: Document,
+    pub update: UpdateModifications,
     pub upsert: Option<bool>,
     pub multi: bool,
+    pub collation: Option<Collation>,
+    pub array_filters: Option<Vec<Document>>,
+    pub hint: Option<Hint>,
 }
 
 impl UpdateModel {
     pub fn new(
         filter: Document,
-        update: Document,
-        upsert: Option<bool>,
+        update: UpdateModifications,
         multi: bool,
+        options: UpdateOptions,
     ) -> UpdateModel {
         UpdateModel {
             filter: filter,
             update: update,
-            upsert: upsert,
+            upsert: options.upsert,
             multi: multi,
+            collation: options.collation,
+            array_filters: options.array_filters,
+            hint: options.hint,
         }
     }
+
+    /// Encodes the update as a legacy OP_UPDATE message, which cannot express a
+    /// collation, array filters, an index hint, or an aggregation pipeline.
+    pub fn to_legacy_message(&self, request_id: i32, namespace: &str) -> Result<Message> {
+        if self.collation.is_some() || self.array_filters.is_some() || self.hint.is_some() {
+            return Err(ArgumentError(String::from(
+                "Unacknowledged updates cannot specify a collation, array filters, or hint on \
+                 this server.",
+            )));
+        }
+
+        let update = match self.update {
+            UpdateModifications::Document(ref update) => update.clone(),
+            UpdateModifications::Pipeline(_) => {
+                return Err(ArgumentError(String::from(
+                    "Unacknowledged pipeline updates are not supported on this server.",
+                )))
+            }
+        };
+
+        let mut flags = OpUpdateFlags::empty();
+        if self.upsert == Some(true) {
+            flags.insert(OpUpdateFlags::UPSERT);
+        }
+        if self.multi {
+            flags.insert(OpUpdateFlags::MULTI_UPDATE);
+        }
+
+        Message::new_update(request_id, String::from(namespace), flags, self.filter.clone(), update)
+    }
 }
 
 impl From<UpdateModel> for Document {
@@ -48,7 +256,7 @@ impl From<UpdateModel> for Document {
         let mut document =
             doc! {
                 "q": model.filter,
-                "u": model.update
+                "u": model.update.to_bson()
             };
 
         if let Some(upsert) = model.upsert {
@@ -59,10 +267,49 @@ impl From<UpdateModel> for Document {
             document.insert("multi", Bson::Boolean(true));
         }
 
+        if let Some(collation) = model.collation {
+            document.insert("collation", collation.to_bson());
+        }
+
+        if let Some(array_filters) = model.array_filters {
+            document.insert(
+                "arrayFilters",
+                array_filters.into_iter().map(Bson::Document).collect::<Vec<_>>(),
+            );
+        }
+
+        if let Some(hint) = model.hint {
+            document.insert("hint", hint.to_bson());
+        }
+
         document
     }
 }
 
+impl From<UpdateModel> for WriteModel {
+    fn from(model: UpdateModel) -> Self {
+        if model.multi {
+            WriteModel::UpdateMany {
+                filter: model.filter,
+                update: model.update,
+                upsert: model.upsert,
+                collation: model.collation,
+                array_filters: model.array_filters,
+                hint: model.hint,
+            }
+        } else {
+            WriteModel::UpdateOne {
+                filter: model.filter,
+                update: model.update,
+                upsert: model.upsert,
+                collation: model.collation,
+                array_filters: model.array_filters,
+                hint: model.hint,
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Batch {
     Insert(Vec<Document>),
@@ -74,31 +321,38 @@ impl From<WriteModel> for Batch {
     fn from(model: WriteModel) -> Batch {
         match model {
             WriteModel::InsertOne { document } => Batch::Insert(vec![document]),
-            WriteModel::DeleteOne { filter } => {
-                Batch::Delete(vec![
-                    DeleteModel {
-                        filter: filter,
-                        multi: false,
-                    },
-                ])
+            WriteModel::DeleteOne { filter, collation, hint } => {
+                Batch::Delete(vec![DeleteModel::new(filter, false, collation, hint)])
             }
-            WriteModel::DeleteMany { filter } => {
-                Batch::Delete(vec![
-                    DeleteModel {
-                        filter: filter,
-                        multi: true,
-                    },
-                ])
+            WriteModel::DeleteMany { filter, collation, hint } => {
+                Batch::Delete(vec![DeleteModel::new(filter, true, collation, hint)])
             }
             WriteModel::ReplaceOne {
                 filter,
-                replacement: update,
+                replacement,
                 upsert,
-            } |
+                collation,
+                hint,
+            } => {
+                Batch::Update(vec![
+                    UpdateModel {
+                        filter: filter,
+                        update: UpdateModifications::Document(replacement),
+                        upsert: upsert,
+                        multi: false,
+                        collation: collation,
+                        array_filters: None,
+                        hint: hint,
+                    },
+                ])
+            }
             WriteModel::UpdateOne {
                 filter,
                 update,
                 upsert,
+                collation,
+                array_filters,
+                hint,
             } => {
                 Batch::Update(vec![
                     UpdateModel {
@@ -106,6 +360,9 @@ impl From<WriteModel> for Batch {
                         update: update,
                         upsert: upsert,
                         multi: false,
+                        collation: collation,
+                        array_filters: array_filters,
+                        hint: hint,
                     },
                 ])
             }
@@ -113,6 +370,9 @@ impl From<WriteModel> for Batch {
                 filter,
                 update,
                 upsert,
+                collation,
+                array_filters,
+                hint,
             } => {
                 Batch::Update(vec![
                     UpdateModel {
@@ -120,6 +380,9 @@ impl From<WriteModel> for Batch {
                         update: update,
                         upsert: upsert,
                         multi: true,
+                        collation: collation,
+                        array_filters: array_filters,
+                        hint: hint,
                     },
                 ])
             }
@@ -128,6 +391,57 @@ impl From<WriteModel> for Batch {
 }
 
 impl Batch {
+    /// Splits this batch into batches that each fit into a single write command, along with the
+    /// original indexes of their statements.
+    pub fn split(self, indexes: Vec<i64>, limits: &BatchLimits) -> Vec<(Batch, Vec<i64>)> {
+        let sizes: Vec<_> = match self {
+            Batch::Insert(ref docs) => docs.iter().map(document_size).collect(),
+            Batch::Delete(ref models) => {
+                models
+                    .iter()
+                    .map(|model| document_size(&Document::from(model.clone())))
+                    .collect()
+            }
+            Batch::Update(ref models) => {
+                models
+                    .iter()
+                    .map(|model| document_size(&Document::from(model.clone())))
+                    .collect()
+            }
+        };
+
+        let ranges = limits.split(&sizes);
+        if ranges.len() <= 1 {
+            return vec![(self, indexes)];
+        }
+
+        let mut batches = Vec::with_capacity(ranges.len());
+
+        match self {
+            Batch::Insert(mut docs) => {
+                for range in ranges.into_iter().rev() {
+                    let chunk = docs.split_off(range.start);
+                    batches.push((Batch::Insert(chunk), indexes[range].to_vec()));
+                }
+            }
+            Batch::Delete(mut models) => {
+                for range in ranges.into_iter().rev() {
+                    let chunk = models.split_off(range.start);
+                    batches.push((Batch::Delete(chunk), indexes[range].to_vec()));
+                }
+            }
+            Batch::Update(mut models) => {
+                for range in ranges.into_iter().rev() {
+                    let chunk = models.split_off(range.start);
+                    batches.push((Batch::Update(chunk), indexes[range].to_vec()));
+                }
+            }
+        }
+
+        batches.reverse();
+        batches
+    }
+
     pub fn len(&self) -> i64 {
         let length = match *self {
             Batch::Insert(ref v) => v.len(),
@@ -149,66 +463,137 @@ impl Batch {
     /// Returns `None` on success, or the model that couldn't be merged on
     /// failure.
     pub fn merge_model(&mut self, model: WriteModel) -> Option<WriteModel> {
-        match *self {
-            Batch::Insert(ref mut docs) => {
-                match model {
-                    WriteModel::InsertOne { document } => docs.push(document),
-                    _ => return Some(model),
-                }
-            }
-            Batch::Delete(ref mut models) => {
-                match model {
-                    WriteModel::DeleteOne { filter } => {
-                        models.push(DeleteModel {
-                            filter: filter,
-                            multi: false,
-                        })
-                    }
-                    WriteModel::DeleteMany { filter } => {
-                        models.push(DeleteModel {
-                            filter: filter,
-                            multi: true,
-                        })
-                    }
-                    _ => return Some(model),
-                }
+        let same_type = match (&*self, &model) {
+            (&Batch::Insert(_), &WriteModel::InsertOne { .. }) |
+            (&Batch::Delete(_), &WriteModel::DeleteOne { .. }) |
+            (&Batch::Delete(_), &WriteModel::DeleteMany { .. }) |
+            (&Batch::Update(_), &WriteModel::ReplaceOne { .. }) |
+            (&Batch::Update(_), &WriteModel::UpdateOne { .. }) |
+            (&Batch::Update(_), &WriteModel::UpdateMany { .. }) => true,
+            _ => false,
+        };
+
+        if !same_type {
+            return Some(model);
+        }
+
+        match (self, Batch::from(model)) {
+            (&mut Batch::Insert(ref mut docs), Batch::Insert(new_docs)) => docs.extend(new_docs),
+            (&mut Batch::Delete(ref mut models), Batch::Delete(new_models)) => {
+                models.extend(new_models)
             }
-            Batch::Update(ref mut models) => {
-                match model {
-                    WriteModel::ReplaceOne {
-                        filter,
-                        replacement: update,
-                        upsert,
-                    } |
-                    WriteModel::UpdateOne {
-                        filter,
-                        update,
-                        upsert,
-                    } => {
-                        models.push(UpdateModel {
-                            filter: filter,
-                            update: update,
-                            upsert: upsert,
-                            multi: false,
-                        })
-                    }
-                    WriteModel::UpdateMany {
-                        filter,
-                        update,
-                        upsert,
-                    } => {
-                        models.push(UpdateModel {
-                            filter: filter,
-                            update: update,
-                            upsert: upsert,
-                            multi: true,
-                        })
-                    }
-                    _ => return Some(model),
-                }
+            (&mut Batch::Update(ref mut models), Batch::Update(new_models)) => {
+                models.extend(new_models)
             }
+            _ => unreachable!("models are only merged into batches of the same type"),
         }
 
         None
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{Batch, BatchLimits, DeleteModel, UpdateModel, ARRAY_ELEMENT_OVERHEAD_BYTES};
+    use coll::options::{Hint, UpdateOptions};
+    use bson::{bson, doc, Document};
+
+    #[test]
+    fn split_by_write_batch_size() {
+        let limits = BatchLimits::new(0, 0, 2);
+        assert_eq!(vec![0..2, 2..4, 4..5], limits.split(&[10; 5]));
+    }
+
+    #[test]
+    fn split_by_object_size() {
+        let size = 100 - ARRAY_ELEMENT_OVERHEAD_BYTES;
+        let limits = BatchLimits::new(250, 0, 0);
+        assert_eq!(vec![0..2, 2..4, 4..5], limits.split(&[size; 5]));
+
+        // Oversized statements are sent alone.
+        assert_eq!(vec![0..1, 1..2, 2..3], limits.split(&[size, 1000, size]));
+    }
+
+    #[test]
+    fn split_by_message_size() {
+        let limits = BatchLimits::new(0, 16 * 1024 + 100, 0);
+        assert_eq!(vec![0..1, 1..2], limits.split(&[60, 60]));
+    }
+
+    #[test]
+    fn split_batch_keeps_original_indexes() {
+        let limits = BatchLimits::new(0, 0, 2);
+        let batch = Batch::Insert(vec![doc!{"a": 1}, doc!{"a": 2}, doc!{"a": 3}]);
+
+        let batches = batch.split(vec![4, 7, 9], &limits);
+        assert_eq!(
+            vec![
+                (Batch::Insert(vec![doc!{"a": 1}, doc!{"a": 2}]), vec![4, 7]),
+                (Batch::Insert(vec![doc!{"a": 3}]), vec![9]),
+            ],
+            batches
+        );
+    }
+
+    #[test]
+    fn pipeline_update_statement() {
+        let mut options = UpdateOptions::new();
+        options.upsert = Some(true);
+        options.hint = Some(Hint::Name(String::from("x_1")));
+
+        let pipeline = vec![doc!{"$set": {"total": {"$add": ["$a", "$b"]}}}];
+        let model = UpdateModel::new(doc!{"x": 1}, pipeline.into(), true, options);
+
+        assert_eq!(
+            doc!{
+                "q": {"x": 1},
+                "u": [{"$set": {"total": {"$add": ["$a", "$b"]}}}],
+                "upsert": true,
+                "multi": true,
+                "hint": "x_1",
+            },
+            Document::from(model)
+        );
+    }
+
+    #[test]
+    fn array_filters_update_statement() {
+        let mut options = UpdateOptions::new();
+        options.array_filters = Some(vec![doc!{"elem.grade": {"$gte": 85}}]);
+
+        let update = doc!{"$set": {"grades.$[elem].mean": 100}};
+        let model = UpdateModel::new(doc!{}, update.into(), false, options);
+
+        assert_eq!(
+            doc!{
+                "q": {},
+                "u": {"$set": {"grades.$[elem].mean": 100}},
+                "arrayFilters": [{"elem.grade": {"$gte": 85}}],
+            },
+            Document::from(model)
+        );
+    }
+
+    #[test]
+    fn hinted_delete_statement() {
+        let model = DeleteModel::new(doc!{"x": 1}, false, None, Some(Hint::Keys(doc!{"x": 1})));
+
+        assert_eq!(
+            doc!{"q": {"x": 1}, "limit": 1_i64, "hint": {"x": 1}},
+            Document::from(model)
+        );
+    }
+
+    #[test]
+    fn legacy_messages_reject_command_only_options() {
+        let pipeline = vec![doc!{"$set": {"x": 1}}];
+        let model = UpdateModel::new(doc!{}, pipeline.into(), false, UpdateOptions::new());
+        assert!(model.to_legacy_message(1, "db.coll").is_err());
+
+        let model = DeleteModel::new(doc!{"x": 1}, true, None, Some(Hint::Name("x_1".into())));
+        assert!(model.to_legacy_message(1, "db.coll").is_err());
+
+        let model = DeleteModel::new(doc!{"x": 1}, true, None, None);
+        assert!(model.to_legacy_message(1, "db.coll").is_ok());
+    }
+}
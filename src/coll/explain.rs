@@ -0,0 +1,310 @@
+//! Query plan explanations for collection-level operations.
+//!
+//! Each read and write operation on `Collection` has an `explain_*` counterpart that runs the
+//! operation through the server's `explain` command rather than executing it:
+//!
+//! ```ignore
+//! let explained = coll.explain_find(Some(doc! { "x": 1 }), None,
+//!                                   ExplainVerbosity::ExecutionStats)?;
+//!
+//! println!("indexes: {:?}, docs examined: {:?}",
+//!          explained.index_names(), explained.total_docs_examined);
+//! ```
+use bson::{self, Bson};
+use common::get_i64;
+
+/// The amount of detail the server reports when explaining an operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExplainVerbosity {
+    /// Reports the plan selected by the query optimizer without running it.
+    QueryPlanner,
+    /// Runs the selected plan and reports its execution statistics.
+    ExecutionStats,
+    /// Runs every candidate plan and reports the statistics of each.
+    AllPlansExecution,
+}
+
+impl ExplainVerbosity {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ExplainVerbosity::QueryPlanner => "queryPlanner",
+            ExplainVerbosity::ExecutionStats => "executionStats",
+            ExplainVerbosity::AllPlansExecution => "allPlansExecution",
+        }
+    }
+}
+
+/// A single stage of a query plan, along with the stages that feed into it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlanStage {
+    /// The stage name, such as `COLLSCAN`, `IXSCAN` or `FETCH`.
+    pub stage: String,
+    pub index_name: Option<String>,
+    pub key_pattern: Option<bson::Document>,
+    /// Execution statistics, only reported for execution stage trees.
+    pub n_returned: Option<i64>,
+    pub keys_examined: Option<i64>,
+    pub docs_examined: Option<i64>,
+    pub input_stages: Vec<PlanStage>,
+}
+
+impl PlanStage {
+    /// Parses a stage tree from a `winningPlan`, `rejectedPlans` entry or `executionStages`
+    /// document.
+    pub fn new(document: &bson::Document) -> PlanStage {
+        // The slot-based execution engine nests the classic plan under `queryPlan`.
+        if let Ok(query_plan) = document.get_document("queryPlan") {
+            return PlanStage::new(query_plan);
+        }
+
+        let mut input_stages = Vec::new();
+
+        if let Ok(input_stage) = document.get_document("inputStage") {
+            input_stages.push(PlanStage::new(input_stage));
+        }
+
+        if let Ok(stages) = document.get_array("inputStages") {
+            input_stages.extend(documents(stages).map(PlanStage::new));
+        }
+
+        // A sharded plan merges the plans chosen by each shard.
+        if let Ok(shards) = document.get_array("shards") {
+            for shard in documents(shards) {
+                let plan = shard
+                    .get_document("winningPlan")
+                    .or_else(|_| shard.get_document("executionStages"));
+
+                if let Ok(plan) = plan {
+                    input_stages.push(PlanStage::new(plan));
+                }
+            }
+        }
+
+        PlanStage {
+            stage: document.get_str("stage").unwrap_or_default().to_owned(),
+            index_name: document.get_str("indexName").ok().map(String::from),
+            key_pattern: document.get_document("keyPattern").ok().cloned(),
+            n_returned: get_i64(document, "nReturned"),
+            keys_examined: get_i64(document, "keysExamined"),
+            docs_examined: get_i64(document, "docsExamined"),
+            input_stages: input_stages,
+        }
+    }
+
+    /// Returns the names of the indexes scanned by this stage or any stage below it, in the
+    /// order they appear in the tree.
+    pub fn index_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        self.collect_index_names(&mut names);
+        names
+    }
+
+    fn collect_index_names(&self, names: &mut Vec<String>) {
+        if let Some(ref name) = self.index_name {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+
+        for stage in &self.input_stages {
+            stage.collect_index_names(names);
+        }
+    }
+}
+
+/// The server's explanation of an operation.
+///
+/// Fields that the chosen verbosity or server version does not report are left as `None`; the
+/// complete reply is always available as `raw`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExplainResult {
+    pub winning_plan: Option<PlanStage>,
+    pub rejected_plans: Vec<PlanStage>,
+    /// The executed stage tree, reported with `ExecutionStats` or `AllPlansExecution`.
+    pub execution_stages: Option<PlanStage>,
+    pub n_returned: Option<i64>,
+    pub total_keys_examined: Option<i64>,
+    pub total_docs_examined: Option<i64>,
+    pub execution_time_millis: Option<i64>,
+    pub raw: bson::Document,
+}
+
+impl ExplainResult {
+    /// Extracts plan and execution information from an explain reply.
+    pub fn new(raw: bson::Document) -> ExplainResult {
+        let mut result = ExplainResult {
+            winning_plan: None,
+            rejected_plans: Vec::new(),
+            execution_stages: None,
+            n_returned: None,
+            total_keys_examined: None,
+            total_docs_examined: None,
+            execution_time_millis: None,
+            raw: bson::Document::new(),
+        };
+
+        if let Some(query_planner) = section(&raw, "queryPlanner") {
+            result.winning_plan = query_planner.get_document("winningPlan").ok().map(
+                PlanStage::new,
+            );
+
+            if let Ok(rejected) = query_planner.get_array("rejectedPlans") {
+                result.rejected_plans = documents(rejected).map(PlanStage::new).collect();
+            }
+        }
+
+        if let Some(stats) = section(&raw, "executionStats") {
+            result.execution_stages = stats.get_document("executionStages").ok().map(
+                PlanStage::new,
+            );
+            result.n_returned = get_i64(stats, "nReturned");
+            result.total_keys_examined = get_i64(stats, "totalKeysExamined");
+            result.total_docs_examined = get_i64(stats, "totalDocsExamined");
+            result.execution_time_millis = get_i64(stats, "executionTimeMillis");
+        }
+
+        result.raw = raw;
+        result
+    }
+
+    /// Returns the names of the indexes used by the winning plan.
+    pub fn index_names(&self) -> Vec<String> {
+        self.winning_plan
+            .as_ref()
+            .map(PlanStage::index_names)
+            .unwrap_or_default()
+    }
+}
+
+// Finds a top-level explain section, which aggregations that start with a query report inside
+// their leading `$cursor` stage instead.
+fn section<'a>(raw: &'a bson::Document, key: &str) -> Option<&'a bson::Document> {
+    if let Ok(section) = raw.get_document(key) {
+        return Some(section);
+    }
+
+    let first_stage = match raw.get_array("stages").ok().and_then(|stages| stages.first()) {
+        Some(&Bson::Document(ref stage)) => stage,
+        _ => return None,
+    };
+
+    first_stage.get_document("$cursor").ok().and_then(|cursor| {
+        cursor.get_document(key).ok()
+    })
+}
+
+fn documents<'a>(array: &'a [Bson]) -> impl Iterator<Item = &'a bson::Document> {
+    array.iter().filter_map(|value| match *value {
+        Bson::Document(ref document) => Some(document),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use bson::{bson, doc};
+    use super::*;
+
+    #[test]
+    fn parses_query_planner_output() {
+        let result = ExplainResult::new(doc!{
+            "queryPlanner": {
+                "winningPlan": {
+                    "stage": "FETCH",
+                    "inputStage": {
+                        "stage": "IXSCAN",
+                        "keyPattern": {"x": 1},
+                        "indexName": "x_1",
+                    },
+                },
+                "rejectedPlans": [{"stage": "COLLSCAN"}],
+            },
+            "ok": 1.0,
+        });
+
+        let plan = result.winning_plan.clone().expect("Expected a winning plan.");
+        assert_eq!("FETCH", plan.stage);
+        assert_eq!("IXSCAN", plan.input_stages[0].stage);
+        assert_eq!(Some(doc!{"x": 1}), plan.input_stages[0].key_pattern);
+        assert_eq!(vec![String::from("x_1")], result.index_names());
+        assert_eq!("COLLSCAN", result.rejected_plans[0].stage);
+        assert!(result.execution_stages.is_none());
+        assert_eq!(None, result.total_docs_examined);
+    }
+
+    #[test]
+    fn parses_execution_stats() {
+        let result = ExplainResult::new(doc!{
+            "queryPlanner": {
+                "winningPlan": {
+                    "queryPlan": {
+                        "stage": "OR",
+                        "inputStages": [
+                            {"stage": "IXSCAN", "indexName": "a_1"},
+                            {"stage": "IXSCAN", "indexName": "b_1"},
+                            {"stage": "IXSCAN", "indexName": "a_1"},
+                        ],
+                    },
+                },
+            },
+            "executionStats": {
+                "nReturned": 3,
+                "executionTimeMillis": 2,
+                "totalKeysExamined": 4i64,
+                "totalDocsExamined": 3.0,
+                "executionStages": {
+                    "stage": "OR",
+                    "nReturned": 3,
+                    "inputStages": [{"stage": "IXSCAN", "keysExamined": 4}],
+                },
+            },
+        });
+
+        assert_eq!(vec![String::from("a_1"), String::from("b_1")], result.index_names());
+        assert_eq!(Some(3), result.n_returned);
+        assert_eq!(Some(2), result.execution_time_millis);
+        assert_eq!(Some(4), result.total_keys_examined);
+        assert_eq!(Some(3), result.total_docs_examined);
+
+        let stages = result.execution_stages.expect("Expected execution stages.");
+        assert_eq!(Some(3), stages.n_returned);
+        assert_eq!(Some(4), stages.input_stages[0].keys_examined);
+    }
+
+    #[test]
+    fn parses_aggregation_cursor_stage() {
+        let result = ExplainResult::new(doc!{
+            "stages": [
+                {
+                    "$cursor": {
+                        "queryPlanner": {"winningPlan": {"stage": "COLLSCAN"}},
+                        "executionStats": {"totalDocsExamined": 10},
+                    },
+                },
+                {"$group": {"_id": "$x"}},
+            ],
+        });
+
+        assert_eq!("COLLSCAN", result.winning_plan.unwrap().stage);
+        assert_eq!(Some(10), result.total_docs_examined);
+    }
+
+    #[test]
+    fn parses_sharded_plans() {
+        let result = ExplainResult::new(doc!{
+            "queryPlanner": {
+                "winningPlan": {
+                    "stage": "SHARD_MERGE",
+                    "shards": [
+                        {"shardName": "a", "winningPlan": {"stage": "IXSCAN", "indexName": "x_1"}},
+                        {"shardName": "b", "winningPlan": {"stage": "COLLSCAN"}},
+                    ],
+                },
+            },
+        });
+
+        let plan = result.winning_plan.clone().unwrap();
+        assert_eq!(2, plan.input_stages.len());
+        assert_eq!(vec![String::from("x_1")], result.index_names());
+    }
+}
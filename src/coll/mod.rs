@@ -1,29 +1,37 @@
 //! Interface for collection-level operations.
 mod batch;
 pub mod error;
+pub mod explain;
 pub mod options;
 pub mod results;
 
 use bson::{self, Bson, bson, doc, oid};
 use command_type::CommandType;
 
-use self::batch::{Batch, DeleteModel, UpdateModel};
+use self::batch::{Batch, BatchLimits, DeleteModel, UpdateModel};
 use self::error::{BulkWriteException, WriteException};
+use self::explain::{ExplainResult, ExplainVerbosity};
 use self::options::*;
 use self::results::*;
 
 use ThreadedClient;
-use common::{merge_options, ReadPreference, WriteConcern};
-use cursor::Cursor;
+use common::{merge_options, ReadConcern, ReadMode, ReadPreference, WriteConcern};
+use cursor::{Cursor, TailableCursor};
 use db::{Database, ThreadedDatabase};
+use db::results::CollectionSpecification;
+use wire_protocol::flags::{OpInsertFlags, OpMsgFlags};
+use wire_protocol::operations::Message;
 
 use Result;
 use Error::{ArgumentError, DecoderError, ResponseError, OperationError, BulkWriteError};
 
-use wire_protocol::flags::OpQueryFlags;
 use std::collections::{BTreeMap, VecDeque};
+use std::io::Write;
 use std::iter::FromIterator;
 
+// The first wire version to support OP_MSG.
+const OP_MSG_WIRE_VERSION: i64 = 6;
+
 /// Interfaces with a MongoDB collection.
 #[derive(Debug)]
 pub struct Collection {
@@ -33,6 +41,7 @@ pub struct Collection {
     pub namespace: String,
     read_preference: ReadPreference,
     write_concern: WriteConcern,
+    read_concern: Option<ReadConcern>,
 }
 
 impl Collection {
@@ -46,9 +55,25 @@ impl Collection {
         read_preference: Option<ReadPreference>,
         write_concern: Option<WriteConcern>,
     ) -> Collection {
+        Collection::new_with_read_concern(db, name, create, read_preference, write_concern, None)
+    }
+
+    /// Creates a collection representation with optional read and write controls and read
+    /// concern. Any control that is not provided is inherited from the database.
+    ///
+    /// If `create` is specified, the collection will be explicitly created in the database.
+    pub fn new_with_read_concern(
+        db: Database,
+        name: &str,
+        create: bool,
+        read_preference: Option<ReadPreference>,
+        write_concern: Option<WriteConcern>,
+        read_concern: Option<ReadConcern>,
+    ) -> Collection {
 
         let rp = read_preference.unwrap_or_else(|| db.read_preference.to_owned());
         let wc = write_concern.unwrap_or_else(|| db.write_concern.to_owned());
+        let rc = read_concern.or_else(|| db.read_concern.to_owned());
 
         if create {
             // Attempt to create the collection explicitly, or fail silently.
@@ -60,6 +85,7 @@ impl Collection {
             namespace: format!("{}.{}", db.name, name),
             read_preference: rp,
             write_concern: wc,
+            read_concern: rc,
         }
     }
 
@@ -90,38 +116,77 @@ impl Collection {
         self.db.drop_collection(&self.name())
     }
 
-    /// Runs an aggregation framework pipeline.
-    pub fn aggregate(
-        &self,
-        pipeline: Vec<bson::Document>,
-        options: Option<AggregateOptions>,
-    ) -> Result<Cursor> {
-        let pipeline_map: Vec<_> = pipeline.into_iter().map(Bson::Document).collect();
+    /// Changes the validation rules of the collection, or the definition of a view.
+    pub fn modify(&self, options: CollModOptions) -> Result<()> {
+        let cmd = merge_options(doc! { "collMod": self.name() }, options);
 
-        let mut spec = doc! {
-            "aggregate": self.name(),
-            "pipeline": pipeline_map
-        };
+        if cmd.len() == 1 {
+            return Err(ArgumentError(
+                String::from("modify requires at least one collection property to change."),
+            ));
+        }
 
-        let mut read_preference = self.read_preference.clone();
+        let mut result = self.db.command(cmd, CommandType::CollMod, None)?;
+        match result.remove("errmsg") {
+            Some(Bson::String(msg)) => Err(OperationError(msg)),
+            _ => Ok(()),
+        }
+    }
 
-        match options {
-            Some(aggregate_options) => {
-                if let Some(ref read_preference_option) = aggregate_options.read_preference {
-                    read_preference = read_preference_option.clone();
-                }
+    /// Returns storage statistics for the collection, with sizes divided by `scale` if given.
+    pub fn stats(&self, scale: Option<i32>) -> Result<CollStats> {
+        let mut spec = doc! { "collStats": self.name() };
 
-                spec = merge_options(spec, aggregate_options);
-            }
-            None => {
-                spec.insert("cursor", bson::Document::new());
-            }
+        if let Some(scale) = scale {
+            spec.insert("scale", scale);
+        }
+
+        self.db.command(spec, CommandType::CollStats, None).map(CollStats::new)
+    }
+
+    /// Returns the specification of the collection, including the bucketing of a time-series
+    /// collection, or None if it does not exist.
+    pub fn specification(&self) -> Result<Option<CollectionSpecification>> {
+        let filter = doc! { "name": self.name() };
+        let mut specs = self.db.list_collection_specifications(Some(filter))?;
+        Ok(specs.pop())
+    }
+
+    /// Renames the collection within its database, replacing any existing collection with the
+    /// new name if `drop_target` is set.
+    pub fn rename(&self, to: &str, drop_target: bool) -> Result<()> {
+        let cmd = doc! {
+            "renameCollection": self.namespace.clone(),
+            "to": format!("{}.{}", self.db.name, to),
+            "dropTarget": drop_target,
         };
 
-        self.db.command_cursor(
-            spec,
-            CommandType::Aggregate,
-            read_preference,
+        // renameCollection can only be run against the admin database.
+        let admin = self.db.client.db("admin");
+        let mut result = admin.command(cmd, CommandType::RenameCollection, None)?;
+        match result.remove("errmsg") {
+            Some(Bson::String(msg)) => Err(OperationError(msg)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Runs an aggregation framework pipeline, given either as raw stage documents or as an
+    /// `aggregation::Pipeline`.
+    ///
+    /// A pipeline that ends in `$out` or `$merge` runs on the primary with the collection's
+    /// write concern, and returns an empty cursor.
+    pub fn aggregate<P: Into<Vec<bson::Document>>>(
+        &self,
+        pipeline: P,
+        options: Option<AggregateOptions>,
+    ) -> Result<Cursor> {
+        self.db.aggregate_target(
+            Bson::String(self.name()),
+            pipeline.into(),
+            options,
+            self.read_preference.clone(),
+            self.read_concern,
+            self.write_concern.clone(),
         )
     }
 
@@ -131,6 +196,29 @@ impl Collection {
         filter: Option<bson::Document>,
         options: Option<CountOptions>,
     ) -> Result<i64> {
+        let (spec, read_preference, collation) = self.count_command(filter, options);
+
+        let result = self.db.command_with_collation(
+            spec,
+            CommandType::Count,
+            Some(read_preference),
+            collation,
+        )?;
+        match result.get("n") {
+            Some(&Bson::I32(n)) => Ok(n as i64),
+            Some(&Bson::I64(n)) => Ok(n),
+            _ => Err(ResponseError(
+                String::from("No count received from server."),
+            )),
+        }
+    }
+
+    // Builds the `count` command and selects its read preference and collation.
+    fn count_command(
+        &self,
+        filter: Option<bson::Document>,
+        options: Option<CountOptions>,
+    ) -> (bson::Document, ReadPreference, Option<Collation>) {
         let mut spec = doc! {
             "count": self.name()
         };
@@ -140,20 +228,87 @@ impl Collection {
         }
 
         let mut read_preference = self.read_preference.clone();
+        let mut read_concern = self.read_concern;
+        let mut collation = None;
 
         if let Some(count_options) = options {
             if let Some(ref read_preference_option) = count_options.read_preference {
                 read_preference = read_preference_option.clone();
             }
 
+            if count_options.read_concern.is_some() {
+                read_concern = count_options.read_concern;
+            }
+
+            collation = count_options.collation.clone();
             spec = merge_options(spec, count_options);
         }
 
-        let result = self.db.command(
-            spec,
-            CommandType::Count,
-            Some(read_preference),
-        )?;
+        if let Some(read_concern) = read_concern {
+            spec.insert("readConcern", read_concern.to_bson());
+        }
+
+        (spec, read_preference, collation)
+    }
+
+    /// Estimates the number of documents in the collection from its metadata.
+    ///
+    /// The estimate may be inaccurate on sharded clusters with orphaned documents or after an
+    /// unclean shutdown; use `count_documents` for an exact count.
+    pub fn estimated_document_count(
+        &self,
+        options: Option<EstimatedDocumentCountOptions>,
+    ) -> Result<i64> {
+        let options = options.unwrap_or_default();
+
+        let count_options = CountOptions {
+            max_time_ms: options.max_time_ms,
+            read_preference: options.read_preference,
+            read_concern: options.read_concern,
+            ..CountOptions::new()
+        };
+
+        self.count(None, Some(count_options))
+    }
+
+    /// Counts the documents matching the filter by running an aggregation over them.
+    pub fn count_documents(
+        &self,
+        filter: Option<bson::Document>,
+        options: Option<CountDocumentsOptions>,
+    ) -> Result<i64> {
+        let options = options.unwrap_or_default();
+
+        let mut pipeline = vec![doc! { "$match": filter.unwrap_or_default() }];
+
+        if let Some(skip) = options.skip {
+            pipeline.push(doc! { "$skip": skip });
+        }
+
+        if let Some(limit) = options.limit {
+            pipeline.push(doc! { "$limit": limit });
+        }
+
+        pipeline.push(doc! { "$group": { "_id": 1, "n": { "$sum": 1 } } });
+
+        let aggregate_options = AggregateOptions {
+            batch_size: 1,
+            max_time_ms: options.max_time_ms,
+            read_preference: options.read_preference,
+            read_concern: options.read_concern,
+            collation: options.collation,
+            hint: options.hint,
+            ..AggregateOptions::new()
+        };
+
+        let mut cursor = self.aggregate(pipeline, Some(aggregate_options))?;
+
+        // The `$group` stage produces no document when nothing matches.
+        let result = match cursor.next() {
+            Some(result) => result?,
+            None => return Ok(0),
+        };
+
         match result.get("n") {
             Some(&Bson::I32(n)) => Ok(n as i64),
             Some(&Bson::I64(n)) => Ok(n),
@@ -170,6 +325,30 @@ impl Collection {
         filter: Option<bson::Document>,
         options: Option<DistinctOptions>,
     ) -> Result<Vec<Bson>> {
+        let (spec, read_preference, collation) =
+            self.distinct_command(field_name, filter, options);
+
+        let result = self.db.command_with_collation(
+            spec,
+            CommandType::Distinct,
+            Some(read_preference),
+            collation,
+        )?;
+        match result.get("values") {
+            Some(&Bson::Array(ref vals)) => Ok(vals.to_owned()),
+            _ => Err(ResponseError(
+                String::from("No values received from server."),
+            )),
+        }
+    }
+
+    // Builds the `distinct` command and selects its read preference and collation.
+    fn distinct_command(
+        &self,
+        field_name: &str,
+        filter: Option<bson::Document>,
+        options: Option<DistinctOptions>,
+    ) -> (bson::Document, ReadPreference, Option<Collation>) {
         let mut spec = doc! {
             "distinct": self.name(),
             "key": field_name,
@@ -179,21 +358,25 @@ impl Collection {
             spec.insert("query", filter_doc);
         }
 
-        let read_preference = options.and_then(|o| o.read_preference).unwrap_or_else(|| {
+        let options = options.unwrap_or_default();
+
+        let read_preference = options.read_preference.unwrap_or_else(|| {
             self.read_preference.clone()
         });
 
-        let result = self.db.command(
-            spec,
-            CommandType::Distinct,
-            Some(read_preference),
-        )?;
-        match result.get("values") {
-            Some(&Bson::Array(ref vals)) => Ok(vals.to_owned()),
-            _ => Err(ResponseError(
-                String::from("No values received from server."),
-            )),
+        if let Some(read_concern) = options.read_concern.or(self.read_concern) {
+            spec.insert("readConcern", read_concern.to_bson());
+        }
+
+        if let Some(ref collation) = options.collation {
+            spec.insert("collation", collation.to_bson());
         }
+
+        if let Some(max_time_ms) = options.max_time_ms {
+            spec.insert("maxTimeMS", max_time_ms);
+        }
+
+        (spec, read_preference, options.collation)
     }
 
     /// Returns a list of documents within the collection that match the filter.
@@ -205,38 +388,52 @@ impl Collection {
         self.find_with_command_type(filter, options, CommandType::Find)
     }
 
+    /// Opens a tailable cursor on a capped collection over the documents that match the filter.
+    ///
+    /// Without options, the cursor is opened as `TailableAwait`; options that request a
+    /// non-tailable cursor result in an ArgumentError.
+    pub fn find_tailable(
+        &self,
+        filter: Option<bson::Document>,
+        options: Option<FindOptions>,
+    ) -> Result<TailableCursor> {
+        let find_options = options.unwrap_or_else(|| {
+            FindOptions { cursor_type: CursorType::TailableAwait, ..FindOptions::new() }
+        });
+
+        if find_options.cursor_type == CursorType::NonTailable {
+            return Err(ArgumentError(String::from(
+                "find_tailable requires a Tailable or TailableAwait cursor type.",
+            )));
+        }
+
+        let cursor = self.find_with_command_type(filter, Some(find_options), CommandType::Find)?;
+        TailableCursor::new(cursor)
+    }
+
     fn find_with_command_type(
         &self,
         filter: Option<bson::Document>,
         options: Option<FindOptions>,
         cmd_type: CommandType,
     ) -> Result<Cursor> {
-        let find_options = options.unwrap_or_default();
-        let flags = OpQueryFlags::with_find_options(&find_options);
-
-        let doc = match find_options.sort {
-            Some(ref sort_opt) => {
-                doc! {
-                    "$query": filter.unwrap_or_default(),
-                    "$orderby": sort_opt.clone(),
-                }
-            }
-            None => filter.unwrap_or_default(),
-        };
+        let mut find_options = options.unwrap_or_default();
+
+        if find_options.read_concern.is_none() {
+            find_options.read_concern = self.read_concern;
+        }
 
         let read_preference = match find_options.read_preference {
             Some(ref read_preference_option) => read_preference_option.clone(),
             None => self.read_preference.clone(),
         };
 
-        Cursor::query(
+        Cursor::find(
             self.db.client.clone(),
             self.namespace.to_owned(),
-            flags,
-            doc,
+            filter.unwrap_or_default(),
             find_options,
             cmd_type,
-            false,
             read_preference,
         )
     }
@@ -277,8 +474,8 @@ impl Collection {
         &self,
         filter: bson::Document,
         options: bson::Document,
-        _max_time_ms: Option<i64>,
         write_concern: Option<WriteConcern>,
+        collation: Option<Collation>,
         cmd_type: CommandType,
     ) -> Result<Option<bson::Document>> {
         let mut cmd = doc! {
@@ -288,7 +485,7 @@ impl Collection {
 
         cmd = merge_options(cmd, options);
 
-        let res = self.db.command(cmd, cmd_type, None)?;
+        let res = self.db.command_with_collation(cmd, cmd_type, None, collation)?;
         let wc = write_concern.unwrap_or_else(|| self.write_concern.clone());
         WriteException::validate_write_result(res.clone(), wc)?;
 
@@ -306,10 +503,8 @@ impl Collection {
         filter: bson::Document,
         options: Option<FindOneAndDeleteOptions>,
     ) -> Result<Option<bson::Document>> {
-        let (max_time_ms, write_concern) = match options {
-            Some(ref opts) => (opts.max_time_ms, opts.write_concern.clone()),
-            None => (None, None),
-        };
+        let write_concern = options.as_ref().and_then(|opts| opts.write_concern.clone());
+        let collation = options.as_ref().and_then(|opts| opts.collation.clone());
 
         let mut options_doc = doc! { "remove": true };
 
@@ -320,8 +515,8 @@ impl Collection {
         self.find_and_modify(
             filter,
             options_doc,
-            max_time_ms,
             write_concern,
+            collation,
             CommandType::FindOneAndDelete,
         )
     }
@@ -336,10 +531,8 @@ impl Collection {
     ) -> Result<Option<bson::Document>> {
         Collection::validate_replace(&replacement)?;
 
-        let (max_time_ms, write_concern) = match options {
-            Some(ref opts) => (opts.max_time_ms, opts.write_concern.clone()),
-            None => (None, None),
-        };
+        let write_concern = options.as_ref().and_then(|opts| opts.write_concern.clone());
+        let collation = options.as_ref().and_then(|opts| opts.collation.clone());
 
         let mut options_doc = doc! { "update": replacement };
 
@@ -350,28 +543,27 @@ impl Collection {
         self.find_and_modify(
             filter,
             options_doc,
-            max_time_ms,
             write_concern,
+            collation,
             CommandType::FindOneAndReplace,
         )
     }
 
     /// Finds a single document and updates it, returning either the original
     /// or updated document.
-    pub fn find_one_and_update(
+    pub fn find_one_and_update<U: Into<UpdateModifications>>(
         &self,
         filter: bson::Document,
-        update: bson::Document,
+        update: U,
         options: Option<FindOneAndUpdateOptions>,
     ) -> Result<Option<bson::Document>> {
+        let update = update.into();
         Collection::validate_update(&update)?;
 
-        let (max_time_ms, write_concern) = match options {
-            Some(ref opts) => (opts.max_time_ms, opts.write_concern.clone()),
-            None => (None, None),
-        };
+        let write_concern = options.as_ref().and_then(|opts| opts.write_concern.clone());
+        let collation = options.as_ref().and_then(|opts| opts.collation.clone());
 
-        let mut options_doc = doc! { "update": update };
+        let mut options_doc = doc! { "update": update.to_bson() };
 
         if let Some(find_one_and_update_options) = options {
             options_doc = merge_options(options_doc, find_one_and_update_options);
@@ -380,79 +572,38 @@ impl Collection {
         self.find_and_modify(
             filter,
             options_doc,
-            max_time_ms,
             write_concern,
+            collation,
             CommandType::FindOneAndUpdate,
         )
     }
 
-    fn get_unordered_batches(requests: Vec<WriteModel>) -> Vec<Batch> {
-        let mut inserts = Vec::new();
-        let mut deletes = Vec::new();
-        let mut updates = Vec::new();
-
-        for req in requests {
-            match req {
-                WriteModel::InsertOne { document } => inserts.push(document),
-                WriteModel::DeleteOne { filter } => {
-                    deletes.push(DeleteModel {
-                        filter: filter,
-                        multi: false,
-                    })
-                }
-                WriteModel::DeleteMany { filter } => {
-                    deletes.push(DeleteModel {
-                        filter: filter,
-                        multi: true,
-                    })
-                }
-                WriteModel::ReplaceOne {
-                    filter,
-                    replacement,
-                    upsert,
-                } => {
-                    updates.push(UpdateModel {
-                        filter: filter,
-                        update: replacement,
-                        upsert: upsert,
-                        multi: false,
-                    })
-                }
-                WriteModel::UpdateOne {
-                    filter,
-                    update,
-                    upsert,
-                } => {
-                    updates.push(UpdateModel {
-                        filter: filter,
-                        update: update,
-                        upsert: upsert,
-                        multi: false,
-                    })
-                }
-                WriteModel::UpdateMany {
-                    filter,
-                    update,
-                    upsert,
-                } => {
-                    updates.push(UpdateModel {
-                        filter: filter,
-                        update: update,
-                        upsert: upsert,
-                        multi: true,
-                    })
+    // Groups requests by type, along with the original index of each request in its group.
+    fn get_unordered_batches(requests: Vec<WriteModel>) -> Vec<(Batch, Vec<i64>)> {
+        let mut batches = vec![
+            (Batch::Insert(Vec::new()), Vec::new()),
+            (Batch::Delete(Vec::new()), Vec::new()),
+            (Batch::Update(Vec::new()), Vec::new()),
+        ];
+
+        for (index, mut model) in requests.into_iter().enumerate() {
+            for &mut (ref mut batch, ref mut indexes) in &mut batches {
+                match batch.merge_model(model) {
+                    Some(unmerged) => model = unmerged,
+                    None => {
+                        indexes.push(index as i64);
+                        break;
+                    }
                 }
             }
         }
 
-        vec![
-            Batch::Insert(inserts),
-            Batch::Delete(deletes),
-            Batch::Update(updates),
-        ]
+        batches
     }
 
-    fn get_ordered_batches(mut requests: VecDeque<WriteModel>) -> Vec<Batch> {
+    // Groups consecutive requests of the same type, along with the original index of each request
+    // in its group.
+    fn get_ordered_batches(mut requests: VecDeque<WriteModel>) -> Vec<(Batch, Vec<i64>)> {
         let first_model = match requests.pop_front() {
             Some(model) => model,
             None => return Vec::new(),
@@ -468,13 +619,58 @@ impl Collection {
             }
         }
 
+        let mut start_index = 0;
+
         batches
+            .into_iter()
+            .map(|batch| {
+                let end_index = start_index + batch.len();
+                let indexes = (start_index..end_index).collect();
+                start_index = end_index;
+                (batch, indexes)
+            })
+            .collect()
+    }
+
+    // Returns the write command limits last reported by a server that writes can be sent to,
+    // without checking out a stream. Until such a server is discovered, the server defaults
+    // are used; the server still rejects any command that exceeds its actual limits.
+    fn batch_limits(&self) -> BatchLimits {
+        match self.db.client.topology.write_server_description() {
+            Some(description) => BatchLimits::from_description(&description),
+            None => BatchLimits::default(),
+        }
+    }
+
+    // Sends a write command without waiting for a reply. Servers that support OP_MSG receive
+    // the command with `moreToCome` set; older servers receive the legacy messages built by
+    // `legacy` instead.
+    fn send_unacknowledged<F>(&self, mut cmd: bson::Document, legacy: F) -> Result<()>
+    where
+        F: FnOnce() -> Result<Vec<Message>>,
+    {
+        let mut stream = self.db.client.acquire_write_stream()?;
+
+        let messages = if stream.max_wire_version >= OP_MSG_WIRE_VERSION {
+            cmd.insert("$db", self.db.name.clone());
+            vec![Message::new_msg(self.get_req_id(), OpMsgFlags::MORE_TO_COME, cmd)?]
+        } else {
+            legacy()?
+        };
+
+        let socket = stream.get_socket().get_mut();
+        for message in messages {
+            message.write(socket)?;
+        }
+
+        socket.flush()?;
+        Ok(())
     }
 
     fn execute_insert_batch(
         &self,
         documents: Vec<bson::Document>,
-        start_index: i64,
+        indexes: &[i64],
         ordered: bool,
         result: &mut BulkWriteResult,
         exception: &mut BulkWriteException,
@@ -492,7 +688,7 @@ impl Collection {
 
         match self.insert_many(documents, options) {
             Ok(insert_result) => {
-                result.process_insert_many_result(insert_result, models, start_index, exception)
+                result.process_insert_many_result(insert_result, models, indexes, exception)
             }
             Err(_) => {
                 exception.add_unproccessed_models(models);
@@ -504,22 +700,21 @@ impl Collection {
     fn execute_delete_batch(
         &self,
         models: Vec<DeleteModel>,
+        indexes: &[i64],
         ordered: bool,
         result: &mut BulkWriteResult,
         exception: &mut BulkWriteException,
     ) -> bool {
-        let original_models = models
-            .iter()
-            .map(|model| if model.multi {
-                WriteModel::DeleteMany { filter: model.filter.clone() }
-            } else {
-                WriteModel::DeleteOne { filter: model.filter.clone() }
-            })
-            .collect();
+        let original_models = models.iter().cloned().map(WriteModel::from).collect();
 
         match self.bulk_delete(models, ordered, None, CommandType::DeleteMany) {
             Ok(bulk_delete_result) => {
-                result.process_bulk_delete_result(bulk_delete_result, original_models, exception)
+                result.process_bulk_delete_result(
+                    bulk_delete_result,
+                    original_models,
+                    indexes,
+                    exception,
+                )
             }
             Err(_) => {
                 exception.add_unproccessed_models(original_models);
@@ -531,34 +726,19 @@ impl Collection {
     fn execute_update_batch(
         &self,
         models: Vec<UpdateModel>,
-        start_index: i64,
+        indexes: &[i64],
         ordered: bool,
         result: &mut BulkWriteResult,
         exception: &mut BulkWriteException,
     ) -> bool {
-        let original_models = models
-            .iter()
-            .map(|model| if model.multi {
-                WriteModel::UpdateMany {
-                    filter: model.filter.clone(),
-                    update: model.update.clone(),
-                    upsert: model.upsert.clone(),
-                }
-            } else {
-                WriteModel::UpdateOne {
-                    filter: model.filter.clone(),
-                    update: model.update.clone(),
-                    upsert: model.upsert.clone(),
-                }
-            })
-            .collect();
+        let original_models = models.iter().cloned().map(WriteModel::from).collect();
 
-        match self.bulk_update(models, ordered, None, CommandType::UpdateMany) {
+        match self.bulk_update(models, ordered, None, None, CommandType::UpdateMany) {
             Ok(bulk_update_result) => {
                 result.process_bulk_update_result(
                     bulk_update_result,
                     original_models,
-                    start_index,
+                    indexes,
                     exception,
                 )
             }
@@ -572,18 +752,20 @@ impl Collection {
     fn execute_batch(
         &self,
         batch: Batch,
-        start_index: i64,
+        indexes: &[i64],
         ordered: bool,
         result: &mut BulkWriteResult,
         exception: &mut BulkWriteException,
     ) -> bool {
         match batch {
             Batch::Insert(docs) => {
-                self.execute_insert_batch(docs, start_index, ordered, result, exception)
+                self.execute_insert_batch(docs, indexes, ordered, result, exception)
+            }
+            Batch::Delete(models) => {
+                self.execute_delete_batch(models, indexes, ordered, result, exception)
             }
-            Batch::Delete(models) => self.execute_delete_batch(models, ordered, result, exception),
             Batch::Update(models) => {
-                self.execute_update_batch(models, start_index, ordered, result, exception)
+                self.execute_update_batch(models, indexes, ordered, result, exception)
             }
         }
     }
@@ -597,20 +779,23 @@ impl Collection {
         };
 
         let mut result = BulkWriteResult::new();
+        result.acknowledged = self.write_concern.is_acknowledged();
         let mut exception = BulkWriteException::new(Vec::new(), Vec::new(), Vec::new(), None);
 
-        let mut start_index = 0;
+        let limits = self.batch_limits();
 
-        for batch in batches {
-            let length = batch.len();
+        let batches = batches
+            .into_iter()
+            .filter(|&(ref batch, _)| batch.len() > 0)
+            .flat_map(|(batch, indexes)| batch.split(indexes, &limits));
+
+        for (batch, indexes) in batches {
             let success =
-                self.execute_batch(batch, start_index, ordered, &mut result, &mut exception);
+                self.execute_batch(batch, &indexes, ordered, &mut result, &mut exception);
 
             if !success && ordered {
                 break;
             }
-
-            start_index += length;
         }
 
         if !exception.unprocessed_requests.is_empty() {
@@ -630,7 +815,9 @@ impl Collection {
     ) -> Result<(Vec<Bson>, Option<BulkWriteException>)> {
 
         let wc = write_concern.unwrap_or_else(|| self.write_concern.clone());
-        let mut converted_docs = Vec::with_capacity(docs.len());
+        let limits = self.batch_limits();
+        let mut sizes = Vec::with_capacity(docs.len());
+        let mut converted_docs: Vec<bson::Document> = Vec::with_capacity(docs.len());
         let mut ids = Vec::with_capacity(docs.len());
 
         for mut doc in docs {
@@ -642,28 +829,91 @@ impl Collection {
                     Bson::ObjectId(id)
                 },
             };
+
+            let size = batch::document_size(&doc);
+            if size > limits.max_bson_object_size {
+                return Err(ArgumentError(format!(
+                    "Document {} is {} bytes, which exceeds the maximum BSON object size of {} \
+                     bytes.",
+                    ids.len(),
+                    size,
+                    limits.max_bson_object_size
+                )));
+            }
+
+            sizes.push(size);
             ids.push(id);
-            converted_docs.push(Bson::Document(doc));
+            converted_docs.push(doc);
         }
 
-        let mut cmd = doc! {
-            "insert": self.name(),
-            "documents": converted_docs
-        };
+        let ordered = options.as_ref().and_then(|opts| opts.ordered).unwrap_or(true);
+        let mut exception: Option<BulkWriteException> = None;
 
-        if let Some(insert_options) = options {
-            cmd = merge_options(cmd, insert_options);
-        }
+        for range in limits.split(&sizes) {
+            let start = range.start;
+            let documents = &converted_docs[range];
 
-        let result = self.db.command(cmd, cmd_type, None)?;
+            let mut cmd = doc! {
+                "insert": self.name(),
+                "documents": documents.iter().cloned().map(Bson::Document).collect::<Vec<_>>()
+            };
 
-        // Intercept bulk write exceptions and insert into the result
-        let exception_res = BulkWriteException::validate_bulk_write_result(result.clone(), wc);
-        let exception = match exception_res {
-            Ok(()) => None,
-            Err(BulkWriteError(err)) => Some(err),
-            Err(e) => return Err(e),
-        };
+            if let Some(ref insert_options) = options {
+                cmd = merge_options(cmd, insert_options.clone());
+            }
+
+            cmd.insert("writeConcern", wc.to_bson());
+
+            if !wc.is_acknowledged() {
+                let flags = if ordered {
+                    OpInsertFlags::empty()
+                } else {
+                    OpInsertFlags::CONTINUE_ON_ERROR
+                };
+
+                self.send_unacknowledged(cmd, || {
+                    let message = Message::new_insert(
+                        self.get_req_id(),
+                        flags,
+                        self.namespace.clone(),
+                        documents.to_vec(),
+                    )?;
+                    Ok(vec![message])
+                })?;
+                continue;
+            }
+
+            let result = self.db.command(cmd, cmd_type, None)?;
+
+            // Intercept bulk write exceptions and insert into the result
+            let exception_res =
+                BulkWriteException::validate_bulk_write_result(result, wc.clone());
+            let mut batch_exception = match exception_res {
+                Ok(()) => continue,
+                Err(BulkWriteError(err)) => err,
+                Err(e) => return Err(e),
+            };
+
+            // Write error indexes are relative to the documents of the current command.
+            for error in &mut batch_exception.write_errors {
+                error.index += start as i32;
+            }
+
+            // An ordered insert stops at the first failed document.
+            let failed_index = batch_exception.write_errors.iter().map(|err| err.index).min();
+
+            match exception {
+                Some(ref mut exception) => {
+                    let _ = exception.add_bulk_write_exception(Some(batch_exception), Vec::new());
+                }
+                None => exception = Some(batch_exception),
+            }
+
+            if let (true, Some(index)) = (ordered, failed_index) {
+                ids.truncate(index as usize + 1);
+                break;
+            }
+        }
 
         Ok((ids, exception))
     }
@@ -675,15 +925,16 @@ impl Collection {
         doc: bson::Document,
         write_concern: Option<WriteConcern>,
     ) -> Result<InsertOneResult> {
+        let wc = write_concern.unwrap_or_else(|| self.write_concern.clone());
         let options = InsertManyOptions {
-            write_concern: write_concern.clone(),
+            write_concern: Some(wc.clone()),
             ..Default::default()
         };
 
         let (ids, bulk_exception) = self.insert(
             vec![doc],
             Some(options),
-            write_concern,
+            Some(wc.clone()),
             CommandType::InsertOne,
         )?;
 
@@ -709,7 +960,9 @@ impl Collection {
             None => Some(ids[0].to_owned()),
         };
 
-        Ok(InsertOneResult::new(id, exception))
+        let mut result = InsertOneResult::new(id, exception);
+        result.acknowledged = wc.is_acknowledged();
+        Ok(result)
     }
 
     /// Inserts the provided documents. If any documents are missing an identifier,
@@ -719,15 +972,15 @@ impl Collection {
         docs: Vec<bson::Document>,
         options: Option<InsertManyOptions>,
     ) -> Result<InsertManyResult> {
-        let write_concern = options.as_ref().map_or(
-            None,
-            |opts| opts.write_concern.clone(),
-        );
+        let wc = options
+            .as_ref()
+            .and_then(|opts| opts.write_concern.clone())
+            .unwrap_or_else(|| self.write_concern.clone());
 
         let (ids, exception) = self.insert(
             docs,
             options,
-            write_concern,
+            Some(wc.clone()),
             CommandType::InsertMany,
         )?;
 
@@ -741,7 +994,9 @@ impl Collection {
             }
         }
 
-        Ok(InsertManyResult::new(Some(map), exception))
+        let mut result = InsertManyResult::new(Some(map), exception);
+        result.acknowledged = wc.is_acknowledged();
+        Ok(result)
     }
 
     // Sends a batch of delete ops to the server at once.
@@ -755,11 +1010,9 @@ impl Collection {
 
         let wc = write_concern.unwrap_or_else(|| self.write_concern.clone());
         let deletes: Vec<_> = models
-            .into_iter()
-            .map(|model| bson!({
-                "q": model.filter,
-                "limit": if model.multi { 0_i64 } else { 1_i64 },
-            }))
+            .iter()
+            .cloned()
+            .map(|model| Bson::Document(bson::Document::from(model)))
             .collect();
 
         let cmd = doc! {
@@ -768,7 +1021,26 @@ impl Collection {
             "ordered": ordered,
             "writeConcern": wc.to_bson(),
         };
-        let result = self.db.command(cmd, cmd_type, None)?;
+
+        if !wc.is_acknowledged() {
+            self.send_unacknowledged(cmd, || {
+                models
+                    .iter()
+                    .map(|model| model.to_legacy_message(self.get_req_id(), &self.namespace))
+                    .collect()
+            })?;
+
+            let mut result = BulkDeleteResult::new(doc!{}, None);
+            result.acknowledged = false;
+            return Ok(result);
+        }
+
+        // The whole batch is rejected by servers without collation support if any statement
+        // specifies a collation.
+        let collation = models.iter().filter_map(|model| model.collation.clone()).next();
+        let result = self.db.command_with_collation(cmd, cmd_type, None, collation)?;
+
+        let acknowledged = wc.is_acknowledged();
 
         // Intercept write exceptions and insert into the result
         let exception_res = BulkWriteException::validate_bulk_write_result(result.clone(), wc);
@@ -778,7 +1050,9 @@ impl Collection {
             Err(e) => return Err(e),
         };
 
-        Ok(BulkDeleteResult::new(result, exception))
+        let mut result = BulkDeleteResult::new(result, exception);
+        result.acknowledged = acknowledged;
+        Ok(result)
     }
 
     // Internal deletion helper function.
@@ -786,8 +1060,10 @@ impl Collection {
         &self,
         filter: bson::Document,
         multi: bool,
-        write_concern: Option<WriteConcern>,
+        options: Option<DeleteOptions>,
     ) -> Result<DeleteResult> {
+        let options = options.unwrap_or_default();
+
         let cmd_type = if multi {
             CommandType::DeleteMany
         } else {
@@ -795,9 +1071,9 @@ impl Collection {
         };
 
         self.bulk_delete(
-            vec![DeleteModel::new(filter, multi)],
+            vec![DeleteModel::new(filter, multi, options.collation, options.hint)],
             true,
-            write_concern,
+            options.write_concern,
             cmd_type,
         ).map(
             DeleteResult::with_bulk_result
@@ -810,7 +1086,21 @@ impl Collection {
         filter: bson::Document,
         write_concern: Option<WriteConcern>,
     ) -> Result<DeleteResult> {
-        self.delete(filter, false, write_concern)
+        let options = DeleteOptions {
+            write_concern: write_concern,
+            ..DeleteOptions::new()
+        };
+
+        self.delete(filter, false, Some(options))
+    }
+
+    /// Deletes a single document, with options such as a collation or an index hint.
+    pub fn delete_one_with_options(
+        &self,
+        filter: bson::Document,
+        options: Option<DeleteOptions>,
+    ) -> Result<DeleteResult> {
+        self.delete(filter, false, options)
     }
 
     /// Deletes multiple documents.
@@ -819,7 +1109,21 @@ impl Collection {
         filter: bson::Document,
         write_concern: Option<WriteConcern>,
     ) -> Result<DeleteResult> {
-        self.delete(filter, true, write_concern)
+        let options = DeleteOptions {
+            write_concern: write_concern,
+            ..DeleteOptions::new()
+        };
+
+        self.delete(filter, true, Some(options))
+    }
+
+    /// Deletes multiple documents, with options such as a collation or an index hint.
+    pub fn delete_many_with_options(
+        &self,
+        filter: bson::Document,
+        options: Option<DeleteOptions>,
+    ) -> Result<DeleteResult> {
+        self.delete(filter, true, options)
     }
 
     // Sends a batch of replace and update ops to the server at once.
@@ -827,23 +1131,47 @@ impl Collection {
         &self,
         models: Vec<UpdateModel>,
         ordered: bool,
+        bypass_document_validation: Option<bool>,
         write_concern: Option<WriteConcern>,
         cmd_type: CommandType,
     ) -> Result<BulkUpdateResult> {
         let wc = write_concern.unwrap_or_else(|| self.write_concern.clone());
         let updates: Vec<_> = models
-            .into_iter()
+            .iter()
+            .cloned()
             .map(|model| Bson::Document(bson::Document::from(model)))
             .collect();
 
-        let cmd = doc! {
+        let mut cmd = doc! {
             "update": self.name(),
             "updates": updates,
             "ordered": ordered,
             "writeConcern": wc.to_bson()
         };
 
-        let result = self.db.command(cmd, cmd_type, None)?;
+        if let Some(bypass_document_validation) = bypass_document_validation {
+            cmd.insert("bypassDocumentValidation", bypass_document_validation);
+        }
+
+        if !wc.is_acknowledged() {
+            self.send_unacknowledged(cmd, || {
+                models
+                    .iter()
+                    .map(|model| model.to_legacy_message(self.get_req_id(), &self.namespace))
+                    .collect()
+            })?;
+
+            let mut result = BulkUpdateResult::new(doc!{}, None);
+            result.acknowledged = false;
+            return Ok(result);
+        }
+
+        // The whole batch is rejected by servers without collation support if any statement
+        // specifies a collation.
+        let collation = models.iter().filter_map(|model| model.collation.clone()).next();
+        let result = self.db.command_with_collation(cmd, cmd_type, None, collation)?;
+
+        let acknowledged = wc.is_acknowledged();
 
         // Intercept write exceptions and insert into the result
         let exception_res = BulkWriteException::validate_bulk_write_result(result.clone(), wc);
@@ -853,17 +1181,18 @@ impl Collection {
             Err(e) => return Err(e),
         };
 
-        Ok(BulkUpdateResult::new(result, exception))
+        let mut result = BulkUpdateResult::new(result, exception);
+        result.acknowledged = acknowledged;
+        Ok(result)
     }
 
     // Internal update helper function.
     fn update(
         &self,
         filter: bson::Document,
-        update: bson::Document,
-        upsert: Option<bool>,
+        update: UpdateModifications,
         multi: bool,
-        write_concern: Option<WriteConcern>,
+        options: UpdateOptions,
     ) -> Result<UpdateResult> {
 
         let cmd_type = if multi {
@@ -872,9 +1201,13 @@ impl Collection {
             CommandType::UpdateOne
         };
 
+        let bypass_document_validation = options.bypass_document_validation;
+        let write_concern = options.write_concern.clone();
+
         self.bulk_update(
-            vec![UpdateModel::new(filter, update, upsert, multi)],
+            vec![UpdateModel::new(filter, update, multi, options)],
             true,
+            bypass_document_validation,
             write_concern,
             cmd_type,
         ).map(
@@ -893,53 +1226,45 @@ impl Collection {
 
         Collection::validate_replace(&replacement)?;
 
-        self.update(
-            filter,
-            replacement,
-            options.upsert,
-            false,
-            options.write_concern,
-        )
+        if options.array_filters.is_some() {
+            return Err(ArgumentError(
+                String::from("Array filters cannot be used with a replacement."),
+            ));
+        }
+
+        self.update(filter, UpdateModifications::Document(replacement), false, options)
     }
 
-    /// Updates a single document.
-    pub fn update_one(
+    /// Updates a single document, with either a document of update operators or an
+    /// aggregation pipeline.
+    pub fn update_one<U: Into<UpdateModifications>>(
         &self,
         filter: bson::Document,
-        update: bson::Document,
+        update: U,
         options: Option<UpdateOptions>,
     ) -> Result<UpdateResult> {
         let options = options.unwrap_or_default();
+        let update = update.into();
 
         Collection::validate_update(&update)?;
 
-        self.update(
-            filter,
-            update,
-            options.upsert,
-            false,
-            options.write_concern
-        )
+        self.update(filter, update, false, options)
     }
 
-    /// Updates multiple documents.
-    pub fn update_many(
+    /// Updates multiple documents, with either a document of update operators or an
+    /// aggregation pipeline.
+    pub fn update_many<U: Into<UpdateModifications>>(
         &self,
         filter: bson::Document,
-        update: bson::Document,
+        update: U,
         options: Option<UpdateOptions>,
     ) -> Result<UpdateResult> {
         let options = options.unwrap_or_default();
+        let update = update.into();
 
         Collection::validate_update(&update)?;
 
-        self.update(
-            filter,
-            update,
-            options.upsert,
-            true,
-            options.write_concern
-        )
+        self.update(filter, update, true, options)
     }
 
     fn validate_replace(replacement: &bson::Document) -> Result<()> {
@@ -953,7 +1278,13 @@ impl Collection {
         Ok(())
     }
 
-    fn validate_update(update: &bson::Document) -> Result<()> {
+    fn validate_update(update: &UpdateModifications) -> Result<()> {
+        let update = match *update {
+            UpdateModifications::Document(ref update) => update,
+            // Pipeline stages are validated by the server.
+            UpdateModifications::Pipeline(_) => return Ok(()),
+        };
+
         for key in update.keys() {
             if !key.starts_with('$') {
                 return Err(ArgumentError(
@@ -964,6 +1295,155 @@ impl Collection {
         Ok(())
     }
 
+    /// Explains a find operation.
+    pub fn explain_find(
+        &self,
+        filter: Option<bson::Document>,
+        options: Option<FindOptions>,
+        verbosity: ExplainVerbosity,
+    ) -> Result<ExplainResult> {
+        let mut options = options.unwrap_or_default();
+
+        let read_preference = options.read_preference.take().unwrap_or_else(|| {
+            self.read_preference.clone()
+        });
+
+        let collation = options.collation.clone();
+        let command = Cursor::find_command(self.name(), filter.unwrap_or_default(), options);
+        self.explain(command, verbosity, read_preference, collation)
+    }
+
+    /// Explains an aggregation pipeline.
+    pub fn explain_aggregate<P: Into<Vec<bson::Document>>>(
+        &self,
+        pipeline: P,
+        options: Option<AggregateOptions>,
+        verbosity: ExplainVerbosity,
+    ) -> Result<ExplainResult> {
+        let mut options = options.unwrap_or_default();
+        options.explain = None;
+
+        let read_preference = options.read_preference.take().unwrap_or_else(|| {
+            self.read_preference.clone()
+        });
+
+        let pipeline: Vec<_> = pipeline.into().into_iter().map(Bson::Document).collect();
+        let collation = options.collation.clone();
+
+        let command = merge_options(
+            doc! {
+                "aggregate": self.name(),
+                "pipeline": pipeline,
+            },
+            options,
+        );
+
+        self.explain(command, verbosity, read_preference, collation)
+    }
+
+    /// Explains a count operation.
+    pub fn explain_count(
+        &self,
+        filter: Option<bson::Document>,
+        options: Option<CountOptions>,
+        verbosity: ExplainVerbosity,
+    ) -> Result<ExplainResult> {
+        let (command, read_preference, collation) = self.count_command(filter, options);
+        self.explain(command, verbosity, read_preference, collation)
+    }
+
+    /// Explains a distinct operation.
+    pub fn explain_distinct(
+        &self,
+        field_name: &str,
+        filter: Option<bson::Document>,
+        options: Option<DistinctOptions>,
+        verbosity: ExplainVerbosity,
+    ) -> Result<ExplainResult> {
+        let (command, read_preference, collation) =
+            self.distinct_command(field_name, filter, options);
+        self.explain(command, verbosity, read_preference, collation)
+    }
+
+    /// Explains an update of one or, if `multi` is set, all matching documents. The update may
+    /// be a document of update operators, a replacement document or an aggregation pipeline.
+    ///
+    /// No documents are modified, even when the verbosity requests execution statistics.
+    pub fn explain_update<U: Into<UpdateModifications>>(
+        &self,
+        filter: bson::Document,
+        update: U,
+        multi: bool,
+        options: Option<UpdateOptions>,
+        verbosity: ExplainVerbosity,
+    ) -> Result<ExplainResult> {
+        let options = options.unwrap_or_default();
+        let bypass_document_validation = options.bypass_document_validation;
+        let collation = options.collation.clone();
+        let model = UpdateModel::new(filter, update.into(), multi, options);
+
+        let mut command = doc! {
+            "update": self.name(),
+            "updates": [bson::Document::from(model)],
+        };
+
+        if let Some(bypass_document_validation) = bypass_document_validation {
+            command.insert("bypassDocumentValidation", bypass_document_validation);
+        }
+
+        let read_preference = ReadPreference::new(ReadMode::Primary, None);
+        self.explain(command, verbosity, read_preference, collation)
+    }
+
+    /// Explains a deletion of one or, if `multi` is set, all matching documents.
+    ///
+    /// No documents are removed, even when the verbosity requests execution statistics.
+    pub fn explain_delete(
+        &self,
+        filter: bson::Document,
+        multi: bool,
+        options: Option<DeleteOptions>,
+        verbosity: ExplainVerbosity,
+    ) -> Result<ExplainResult> {
+        let options = options.unwrap_or_default();
+        let collation = options.collation.clone();
+        let model = DeleteModel::new(filter, multi, options.collation, options.hint);
+
+        let command = doc! {
+            "delete": self.name(),
+            "deletes": [bson::Document::from(model)],
+        };
+
+        let read_preference = ReadPreference::new(ReadMode::Primary, None);
+        self.explain(command, verbosity, read_preference, collation)
+    }
+
+    // Wraps a command in an `explain` and parses the reply.
+    fn explain(
+        &self,
+        mut command: bson::Document,
+        verbosity: ExplainVerbosity,
+        read_preference: ReadPreference,
+        collation: Option<Collation>,
+    ) -> Result<ExplainResult> {
+        // The explained command is never executed as given, so it cannot carry its own read
+        // concern.
+        command.remove("readConcern");
+
+        let spec = doc! {
+            "explain": command,
+            "verbosity": verbosity.as_str(),
+        };
+
+        let result = self.db.command_with_collation(
+            spec,
+            CommandType::Explain,
+            Some(read_preference),
+            collation,
+        )?;
+        Ok(ExplainResult::new(result))
+    }
+
     /// Create a single index.
     pub fn create_index(
         &self,
@@ -982,19 +1462,35 @@ impl Collection {
 
     /// Create multiple indexes.
     pub fn create_indexes(&self, models: Vec<IndexModel>) -> Result<Vec<String>> {
+        self.create_indexes_with_options(models, None)
+    }
+
+    /// Create multiple indexes, with options for the `createIndexes` command as a whole.
+    pub fn create_indexes_with_options(
+        &self,
+        models: Vec<IndexModel>,
+        options: Option<CreateIndexesOptions>,
+    ) -> Result<Vec<String>> {
         let mut names = Vec::with_capacity(models.len());
         let mut indexes = Vec::with_capacity(models.len());
+        let collation = models.iter().filter_map(|model| model.options.collation.clone()).next();
 
         for model in models {
             names.push(model.name()?);
             indexes.push(Bson::Document(model.to_bson()?));
         }
 
-        let cmd = doc! {
+        let mut cmd = doc! {
             "createIndexes": self.name(),
             "indexes": indexes,
         };
-        let mut result = self.db.command(cmd, CommandType::CreateIndexes, None)?;
+
+        if let Some(options) = options {
+            cmd = merge_options(cmd, options);
+        }
+
+        let mut result =
+            self.db.command_with_collation(cmd, CommandType::CreateIndexes, None, collation)?;
 
         match result.remove("errmsg") {
             Some(Bson::String(msg)) => Err(OperationError(msg)),
@@ -1008,20 +1504,27 @@ impl Collection {
         self.drop_index_model(model)
     }
 
-    /// Drop an index by name.
-    pub fn drop_index_string(&self, name: String) -> Result<()> {
-        let mut opts = IndexOptions::new();
-        opts.name = Some(name);
+    /// Drop one or more indexes by name. Dropping several indexes in one call requires
+    /// MongoDB 4.2 or newer.
+    pub fn drop_index_string<N: Into<IndexNames>>(&self, names: N) -> Result<()> {
+        let names = names.into();
 
-        let model = IndexModel::new(bson::Document::new(), Some(opts));
-        self.drop_index_model(model)
+        if names.0.is_empty() {
+            return Err(ArgumentError(String::from("At least one index name is required.")));
+        }
+
+        self.drop_indexes_by_name(names.to_bson())
     }
 
     /// Drop an index by IndexModel.
     pub fn drop_index_model(&self, model: IndexModel) -> Result<()> {
+        self.drop_indexes_by_name(Bson::String(model.name()?))
+    }
+
+    fn drop_indexes_by_name(&self, index: Bson) -> Result<()> {
         let cmd = doc! {
             "dropIndexes": self.name(),
-            "index": model.name()?,
+            "index": index,
         };
         let mut result = self.db.command(cmd, CommandType::DropIndexes, None)?;
         match result.remove("errmsg") {
@@ -1039,6 +1542,51 @@ impl Collection {
         self.drop_index_model(model)
     }
 
+    /// Changes the properties of an existing index, given by its key pattern or name.
+    pub fn modify_index<I: Into<IndexSpecifier>>(
+        &self,
+        index: I,
+        options: ModifyIndexOptions,
+    ) -> Result<()> {
+        let mut spec = match index.into() {
+            IndexSpecifier::KeyPattern(keys) => doc! { "keyPattern": keys },
+            IndexSpecifier::Name(name) => doc! { "name": name },
+        };
+
+        if let Some(hidden) = options.hidden {
+            spec.insert("hidden", hidden);
+        }
+
+        if let Some(expire_after_seconds) = options.expire_after_seconds {
+            spec.insert("expireAfterSeconds", expire_after_seconds);
+        }
+
+        if let Some(prepare_unique) = options.prepare_unique {
+            spec.insert("prepareUnique", prepare_unique);
+        }
+
+        if spec.len() == 1 {
+            return Err(ArgumentError(
+                String::from("modify_index requires at least one index property to change."),
+            ));
+        }
+
+        let mut cmd = doc! {
+            "collMod": self.name(),
+            "index": spec,
+        };
+
+        if let Some(write_concern) = options.write_concern {
+            cmd.insert("writeConcern", write_concern.to_bson());
+        }
+
+        let mut result = self.db.command(cmd, CommandType::CollMod, None)?;
+        match result.remove("errmsg") {
+            Some(Bson::String(msg)) => Err(OperationError(msg)),
+            _ => Ok(()),
+        }
+    }
+
     /// List all indexes in the collection.
     pub fn list_indexes(&self) -> Result<Cursor> {
         let cmd = doc!{ "listIndexes": self.name() };
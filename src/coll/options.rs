@@ -1,9 +1,13 @@
 //! Options for collection-level operations.
 use bson::{self, Bson, bson, doc};
-use common::{ReadPreference, WriteConcern};
+use common::{ReadConcern, ReadConcernLevel, ReadPreference, WriteConcern};
+use db::options::{ValidationAction, ValidationLevel};
 use Error::ArgumentError;
 use Result;
 
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error as DeError;
+
 /// Describes the type of cursor to return on collection queries.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum CursorType {
@@ -34,37 +38,291 @@ impl ReturnDocument {
     }
 }
 
+/// Specifies the index to use for an operation, either by its key pattern or by its name.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Hint {
+    Keys(bson::Document),
+    Name(String),
+}
+
+impl Hint {
+    pub fn to_bson(&self) -> Bson {
+        match *self {
+            Hint::Keys(ref keys) => Bson::Document(keys.clone()),
+            Hint::Name(ref name) => Bson::String(name.to_owned()),
+        }
+    }
+}
+
+/// The modifications applied by an update: either a document of update operators, or an
+/// aggregation pipeline (MongoDB 4.2+).
+#[derive(Clone, Debug, PartialEq)]
+pub enum UpdateModifications {
+    Document(bson::Document),
+    Pipeline(Vec<bson::Document>),
+}
+
+impl UpdateModifications {
+    pub fn to_bson(&self) -> Bson {
+        match *self {
+            UpdateModifications::Document(ref doc) => Bson::Document(doc.clone()),
+            UpdateModifications::Pipeline(ref stages) => {
+                Bson::Array(stages.iter().cloned().map(Bson::Document).collect())
+            }
+        }
+    }
+}
+
+impl From<bson::Document> for UpdateModifications {
+    fn from(doc: bson::Document) -> Self {
+        UpdateModifications::Document(doc)
+    }
+}
+
+impl From<Vec<bson::Document>> for UpdateModifications {
+    fn from(stages: Vec<bson::Document>) -> Self {
+        UpdateModifications::Pipeline(stages)
+    }
+}
+
+/// The comparison level used by a collation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CollationStrength {
+    /// Compares base characters only.
+    Primary,
+    /// Compares base characters and diacritics.
+    Secondary,
+    /// Compares base characters, diacritics, and case.
+    Tertiary,
+    /// Additionally distinguishes punctuation when `alternate` is `shifted`.
+    Quaternary,
+    /// Additionally breaks ties on the code point values of the strings.
+    Identical,
+}
+
+impl CollationStrength {
+    pub fn as_i32(&self) -> i32 {
+        match *self {
+            CollationStrength::Primary => 1,
+            CollationStrength::Secondary => 2,
+            CollationStrength::Tertiary => 3,
+            CollationStrength::Quaternary => 4,
+            CollationStrength::Identical => 5,
+        }
+    }
+
+    pub fn from_i32(level: i32) -> Result<CollationStrength> {
+        Ok(match level {
+            1 => CollationStrength::Primary,
+            2 => CollationStrength::Secondary,
+            3 => CollationStrength::Tertiary,
+            4 => CollationStrength::Quaternary,
+            5 => CollationStrength::Identical,
+            _ => {
+                return Err(ArgumentError(
+                    format!("Could not convert '{}' to CollationStrength.", level),
+                ))
+            }
+        })
+    }
+}
+
+impl Serialize for CollationStrength {
+    fn serialize<S: Serializer>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_i32(self.as_i32())
+    }
+}
+
+impl<'de> Deserialize<'de> for CollationStrength {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> ::std::result::Result<Self, D::Error> {
+        let level = i32::deserialize(deserializer)?;
+        CollationStrength::from_i32(level).map_err(|e| D::Error::custom(e.to_string()))
+    }
+}
+
+/// Determines the sort order of case differences during tertiary level comparisons.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CollationCaseFirst {
+    #[serde(rename="upper")]
+    Upper,
+    #[serde(rename="lower")]
+    Lower,
+    #[serde(rename="off")]
+    Off,
+}
+
+impl CollationCaseFirst {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            CollationCaseFirst::Upper => "upper",
+            CollationCaseFirst::Lower => "lower",
+            CollationCaseFirst::Off => "off",
+        }
+    }
+}
+
+/// Determines whether whitespace and punctuation are considered as base characters.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CollationAlternate {
+    #[serde(rename="non-ignorable")]
+    NonIgnorable,
+    #[serde(rename="shifted")]
+    Shifted,
+}
+
+impl CollationAlternate {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            CollationAlternate::NonIgnorable => "non-ignorable",
+            CollationAlternate::Shifted => "shifted",
+        }
+    }
+}
+
+/// Determines which characters are ignorable when `alternate` is `shifted`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CollationMaxVariable {
+    #[serde(rename="punct")]
+    Punct,
+    #[serde(rename="space")]
+    Space,
+}
+
+impl CollationMaxVariable {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            CollationMaxVariable::Punct => "punct",
+            CollationMaxVariable::Space => "space",
+        }
+    }
+}
+
+/// Language-specific rules for string comparison. Requires MongoDB 3.4 or newer.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Collation {
+    /// The ICU locale, e.g. "en_US", or "simple" for binary comparison.
+    pub locale: String,
+
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub strength: Option<CollationStrength>,
+
+    #[serde(rename="caseLevel", skip_serializing_if="Option::is_none")]
+    pub case_level: Option<bool>,
+
+    #[serde(rename="caseFirst", skip_serializing_if="Option::is_none")]
+    pub case_first: Option<CollationCaseFirst>,
+
+    #[serde(rename="numericOrdering", skip_serializing_if="Option::is_none")]
+    pub numeric_ordering: Option<bool>,
+
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub alternate: Option<CollationAlternate>,
+
+    #[serde(rename="maxVariable", skip_serializing_if="Option::is_none")]
+    pub max_variable: Option<CollationMaxVariable>,
+
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub backwards: Option<bool>,
+}
+
+impl Collation {
+    /// Creates a collation for the given locale, using the server defaults for all other fields.
+    pub fn new(locale: &str) -> Collation {
+        Collation {
+            locale: String::from(locale),
+            ..Default::default()
+        }
+    }
+
+    /// Converts the collation to its BSON document representation.
+    pub fn to_bson(&self) -> bson::Document {
+        let mut doc = doc! { "locale": self.locale.clone() };
+
+        if let Some(val) = self.strength {
+            doc.insert("strength", val.as_i32());
+        }
+        if let Some(val) = self.case_level {
+            doc.insert("caseLevel", val);
+        }
+        if let Some(val) = self.case_first {
+            doc.insert("caseFirst", val.as_str());
+        }
+        if let Some(val) = self.numeric_ordering {
+            doc.insert("numericOrdering", val);
+        }
+        if let Some(val) = self.alternate {
+            doc.insert("alternate", val.as_str());
+        }
+        if let Some(val) = self.max_variable {
+            doc.insert("maxVariable", val.as_str());
+        }
+        if let Some(val) = self.backwards {
+            doc.insert("backwards", val);
+        }
+
+        doc
+    }
+}
+
 /// Marker interface for writes that can be batched together.
 #[derive(Debug, Clone, PartialEq)]
 pub enum WriteModel {
     InsertOne { document: bson::Document },
-    DeleteOne { filter: bson::Document },
-    DeleteMany { filter: bson::Document },
+    DeleteOne {
+        filter: bson::Document,
+        collation: Option<Collation>,
+        hint: Option<Hint>,
+    },
+    DeleteMany {
+        filter: bson::Document,
+        collation: Option<Collation>,
+        hint: Option<Hint>,
+    },
     ReplaceOne {
         filter: bson::Document,
         replacement: bson::Document,
         upsert: Option<bool>,
+        collation: Option<Collation>,
+        hint: Option<Hint>,
     },
     UpdateOne {
         filter: bson::Document,
-        update: bson::Document,
+        update: UpdateModifications,
         upsert: Option<bool>,
+        collation: Option<Collation>,
+        array_filters: Option<Vec<bson::Document>>,
+        hint: Option<Hint>,
     },
     UpdateMany {
         filter: bson::Document,
-        update: bson::Document,
+        update: UpdateModifications,
         upsert: Option<bool>,
+        collation: Option<Collation>,
+        array_filters: Option<Vec<bson::Document>>,
+        hint: Option<Hint>,
     },
 }
 
 /// Options for aggregation queries.
-#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct AggregateOptions {
     pub allow_disk_use: Option<bool>,
     pub use_cursor: Option<bool>,
     pub batch_size: i32,
     pub max_time_ms: Option<i64>,
     pub read_preference: Option<ReadPreference>,
+    pub read_concern: Option<ReadConcern>,
+    pub collation: Option<Collation>,
+    pub hint: Option<Hint>,
+    /// Whether to return the server's plan for the pipeline instead of running it.
+    pub explain: Option<bool>,
+    /// Whether `$out` and `$merge` may write documents that fail validation.
+    pub bypass_document_validation: Option<bool>,
+    pub comment: Option<String>,
+    /// Variables that the pipeline can access with the `$$` prefix.
+    pub let_vars: Option<bson::Document>,
+    /// The write concern for pipelines that end in `$out` or `$merge`.
+    pub write_concern: Option<WriteConcern>,
 }
 
 impl AggregateOptions {
@@ -83,13 +341,40 @@ impl From<AggregateOptions> for bson::Document {
 
         // useCursor not currently used by the driver.
 
+        // An explained aggregation replies with its plan rather than a cursor.
+        if options.explain == Some(true) {
+            document.insert("explain", true);
+        } else {
+            let cursor = doc! { "batchSize": options.batch_size };
+            document.insert("cursor", cursor);
+        }
+
+        if let Some(max_time_ms) = options.max_time_ms {
+            document.insert("maxTimeMS", max_time_ms);
+        }
 
-        let cursor = doc! { "batchSize": options.batch_size };
-        document.insert("cursor", cursor);
+        // read_preference, read_concern, and write_concern are used directly by
+        // Collection::aggregate and Database::aggregate.
 
-        // maxTimeMS is not currently used by the driver.
+        if let Some(collation) = options.collation {
+            document.insert("collation", collation.to_bson());
+        }
 
-        // read_preference is used directly by Collection::aggregate.
+        if let Some(hint) = options.hint {
+            document.insert("hint", hint.to_bson());
+        }
+
+        if let Some(bypass_document_validation) = options.bypass_document_validation {
+            document.insert("bypassDocumentValidation", bypass_document_validation);
+        }
+
+        if let Some(comment) = options.comment {
+            document.insert("comment", comment);
+        }
+
+        if let Some(let_vars) = options.let_vars {
+            document.insert("let", let_vars);
+        }
 
         document
     }
@@ -104,6 +389,8 @@ pub struct CountOptions {
     pub hint_doc: Option<bson::Document>,
     pub max_time_ms: Option<i64>,
     pub read_preference: Option<ReadPreference>,
+    pub read_concern: Option<ReadConcern>,
+    pub collation: Option<Collation>,
 }
 
 impl CountOptions {
@@ -132,19 +419,59 @@ impl From<CountOptions> for bson::Document {
             document.insert("hint_doc", hint_doc);
         }
 
-        // maxTimeMS is not currently used by the driver.
+        if let Some(max_time_ms) = options.max_time_ms {
+            document.insert("maxTimeMS", max_time_ms);
+        }
 
-        // read_preference is used directly by Collection::count.
+        // read_preference and read_concern are used directly by Collection::count.
+
+        if let Some(collation) = options.collation {
+            document.insert("collation", collation.to_bson());
+        }
 
         document
     }
 }
 
+/// Options for estimating the number of documents in a collection from its metadata.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EstimatedDocumentCountOptions {
+    pub max_time_ms: Option<i64>,
+    pub read_preference: Option<ReadPreference>,
+    pub read_concern: Option<ReadConcern>,
+}
+
+impl EstimatedDocumentCountOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+/// Options for counting the documents that match a filter.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CountDocumentsOptions {
+    pub skip: Option<i64>,
+    pub limit: Option<i64>,
+    pub hint: Option<Hint>,
+    pub max_time_ms: Option<i64>,
+    pub read_preference: Option<ReadPreference>,
+    pub read_concern: Option<ReadConcern>,
+    pub collation: Option<Collation>,
+}
+
+impl CountDocumentsOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
 /// Options for distinct queries.
 #[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DistinctOptions {
     pub max_time_ms: Option<i64>,
     pub read_preference: Option<ReadPreference>,
+    pub read_concern: Option<ReadConcern>,
+    pub collation: Option<Collation>,
 }
 
 impl DistinctOptions {
@@ -159,6 +486,10 @@ pub struct FindOptions {
     pub allow_partial_results: bool,
     pub no_cursor_timeout: bool,
     pub oplog_replay: bool,
+    /// Whether the server should stream every batch over the same connection without waiting
+    /// for a `getMore`. Servers older than MongoDB 4.2 receive exhaust queries as a legacy
+    /// OP_QUERY; newer servers stream the replies to an OP_MSG `getMore`.
+    pub exhaust: bool,
     pub skip: Option<i64>,
     pub limit: Option<i64>,
     pub cursor_type: CursorType,
@@ -169,6 +500,20 @@ pub struct FindOptions {
     pub projection: Option<bson::Document>,
     pub sort: Option<bson::Document>,
     pub read_preference: Option<ReadPreference>,
+    pub read_concern: Option<ReadConcern>,
+    pub collation: Option<Collation>,
+    pub hint: Option<Hint>,
+    /// The exclusive upper bound for a specific index.
+    pub max: Option<bson::Document>,
+    /// The inclusive lower bound for a specific index.
+    pub min: Option<bson::Document>,
+    /// The time limit for a `getMore` on a `TailableAwait` cursor to wait for new data.
+    pub max_await_time_ms: Option<i64>,
+    pub return_key: Option<bool>,
+    pub show_record_id: Option<bool>,
+    pub allow_disk_use: Option<bool>,
+    /// Variables that can be accessed in the filter with the `$$` prefix.
+    pub let_vars: Option<bson::Document>,
 }
 
 impl FindOptions {
@@ -176,18 +521,110 @@ impl FindOptions {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Builds the query document of a legacy OP_QUERY, with the options that OP_QUERY supports
+    /// encoded as `$`-modifiers around the filter.
+    ///
+    /// Options that only a `find` command can express result in an ArgumentError.
+    pub fn to_legacy_query(&self, filter: bson::Document) -> Result<bson::Document> {
+        if self.collation.is_some() || self.allow_disk_use.is_some() || self.let_vars.is_some() {
+            return Err(ArgumentError(String::from(
+                "`collation`, `allow_disk_use`, and `let_vars` are only supported when the query \
+                 is sent as a find command.",
+            )));
+        }
+
+        // OP_QUERY always reads with the server's default `local` read concern.
+        if self.read_concern.map_or(false, |rc| rc.level != ReadConcernLevel::Local) {
+            return Err(ArgumentError(String::from(
+                "A read concern other than `local` is only supported when the query is sent as a \
+                 find command.",
+            )));
+        }
+
+        let mut modifiers = self.modifiers.clone().unwrap_or_default();
+
+        if let Some(ref sort) = self.sort {
+            modifiers.insert("$orderby", sort.clone());
+        }
+
+        if let Some(ref hint) = self.hint {
+            modifiers.insert("$hint", hint.to_bson());
+        }
+
+        if let Some(ref max) = self.max {
+            modifiers.insert("$max", max.clone());
+        }
+
+        if let Some(ref min) = self.min {
+            modifiers.insert("$min", min.clone());
+        }
+
+        if let Some(max_time_ms) = self.max_time_ms {
+            modifiers.insert("$maxTimeMS", max_time_ms);
+        }
+
+        if let Some(ref comment) = self.comment {
+            modifiers.insert("$comment", comment.to_owned());
+        }
+
+        if let Some(return_key) = self.return_key {
+            modifiers.insert("$returnKey", return_key);
+        }
+
+        if let Some(show_record_id) = self.show_record_id {
+            modifiers.insert("$showDiskLoc", show_record_id);
+        }
+
+        // `max_await_time_ms` cannot be expressed through OP_GET_MORE, so the server default
+        // is used.
+
+        if modifiers.is_empty() {
+            return Ok(filter);
+        }
+
+        let mut query = doc! { "$query": filter };
+        for (key, value) in modifiers {
+            query.insert(key, value);
+        }
+
+        Ok(query)
+    }
+}
+
+// Returns the `find` command field corresponding to a legacy `$`-modifier, if any.
+fn find_command_field(modifier: &str) -> Option<&'static str> {
+    Some(match modifier {
+        "$comment" => "comment",
+        "$hint" => "hint",
+        "$max" => "max",
+        "$maxScan" => "maxScan",
+        "$maxTimeMS" => "maxTimeMS",
+        "$min" => "min",
+        "$orderby" => "sort",
+        "$returnKey" => "returnKey",
+        "$showDiskLoc" => "showRecordId",
+        "$snapshot" => "snapshot",
+        _ => return None,
+    })
 }
 
 impl From<FindOptions> for bson::Document {
     fn from(options: FindOptions) -> Self {
         let mut document = bson::Document::new();
 
-        // `allow_partial_results`, `no_cursor_timeout`, `oplog_relay`, and `cursor_type` are used by
-        // wire_protocol::OpQueryFlags.
-        //
-        // `max_time_ms` and `modifiers` are not currently used by the driver.
+        // read_preference is used directly by Collection::find_with_command_type, and
+        // `max_await_time_ms` is sent with each `getMore` rather than with the `find`.
         //
-        // read_preference is used directly by Collection::find_with_command_type.
+        // Legacy `modifiers` are translated first so that the explicit options take precedence.
+
+        if let Some(modifiers) = options.modifiers {
+            for (key, value) in modifiers {
+                if let Some(field) = find_command_field(&key) {
+                    document.insert(field, value);
+                }
+            }
+        }
 
         if let Some(projection) = options.projection {
             document.insert("projection", projection);
@@ -209,6 +646,70 @@ impl From<FindOptions> for bson::Document {
             document.insert("sort", sort);
         }
 
+        if let Some(hint) = options.hint {
+            document.insert("hint", hint.to_bson());
+        }
+
+        if let Some(max) = options.max {
+            document.insert("max", max);
+        }
+
+        if let Some(min) = options.min {
+            document.insert("min", min);
+        }
+
+        if let Some(max_time_ms) = options.max_time_ms {
+            document.insert("maxTimeMS", max_time_ms);
+        }
+
+        if let Some(comment) = options.comment {
+            document.insert("comment", comment);
+        }
+
+        if let Some(return_key) = options.return_key {
+            document.insert("returnKey", return_key);
+        }
+
+        if let Some(show_record_id) = options.show_record_id {
+            document.insert("showRecordId", show_record_id);
+        }
+
+        if let Some(allow_disk_use) = options.allow_disk_use {
+            document.insert("allowDiskUse", allow_disk_use);
+        }
+
+        if let Some(let_vars) = options.let_vars {
+            document.insert("let", let_vars);
+        }
+
+        if options.cursor_type != CursorType::NonTailable {
+            document.insert("tailable", true);
+        }
+
+        if options.cursor_type == CursorType::TailableAwait {
+            document.insert("awaitData", true);
+        }
+
+        if options.oplog_replay {
+            document.insert("oplogReplay", true);
+        }
+
+        if options.no_cursor_timeout {
+            document.insert("noCursorTimeout", true);
+        }
+
+        if options.allow_partial_results {
+            document.insert("allowPartialResults", true);
+        }
+
+        if let Some(read_concern) = options.read_concern {
+            document.insert("readConcern", read_concern.to_bson());
+        }
+
+        if let Some(collation) = options.collation {
+            document.insert("collation", collation.to_bson());
+        }
+
         document
     }
 }
@@ -220,6 +721,7 @@ pub struct FindOneAndDeleteOptions {
     pub projection: Option<bson::Document>,
     pub sort: Option<bson::Document>,
     pub write_concern: Option<WriteConcern>,
+    pub collation: Option<Collation>,
 }
 
 impl FindOneAndDeleteOptions {
@@ -232,7 +734,9 @@ impl From<FindOneAndDeleteOptions> for bson::Document {
     fn from(options: FindOneAndDeleteOptions) -> Self {
         let mut document = bson::Document::new();
 
-        // max_time_ms is not currently used by the driver
+        if let Some(max_time_ms) = options.max_time_ms {
+            document.insert("maxTimeMS", max_time_ms);
+        }
 
         if let Some(projection) = options.projection {
             document.insert("fields", projection);
@@ -246,6 +750,10 @@ impl From<FindOneAndDeleteOptions> for bson::Document {
             document.insert("writeConcern", write_concern.to_bson());
         }
 
+        if let Some(collation) = options.collation {
+            document.insert("collation", collation.to_bson());
+        }
+
         document
     }
 }
@@ -259,6 +767,11 @@ pub struct FindOneAndUpdateOptions {
     pub sort: Option<bson::Document>,
     pub upsert: Option<bool>,
     pub write_concern: Option<WriteConcern>,
+    pub collation: Option<Collation>,
+    /// Filters that determine which array elements an update applies to.
+    pub array_filters: Option<Vec<bson::Document>>,
+    pub hint: Option<Hint>,
+    pub bypass_document_validation: Option<bool>,
 }
 
 impl FindOneAndUpdateOptions {
@@ -275,7 +788,9 @@ impl From<FindOneAndUpdateOptions> for bson::Document {
             document.insert("new", return_document.as_bool());
         }
 
-        // max_time_ms is not currently used by the driver
+        if let Some(max_time_ms) = options.max_time_ms {
+            document.insert("maxTimeMS", max_time_ms);
+        }
 
         if let Some(projection) = options.projection {
             document.insert("fields", projection);
@@ -293,6 +808,25 @@ impl From<FindOneAndUpdateOptions> for bson::Document {
             document.insert("writeConcern", write_concern.to_bson());
         }
 
+        if let Some(collation) = options.collation {
+            document.insert("collation", collation.to_bson());
+        }
+
+        if let Some(array_filters) = options.array_filters {
+            document.insert(
+                "arrayFilters",
+                array_filters.into_iter().map(Bson::Document).collect::<Vec<_>>(),
+            );
+        }
+
+        if let Some(hint) = options.hint {
+            document.insert("hint", hint.to_bson());
+        }
+
+        if let Some(bypass_document_validation) = options.bypass_document_validation {
+            document.insert("bypassDocumentValidation", bypass_document_validation);
+        }
+
         document
     }
 }
@@ -351,6 +885,30 @@ pub struct IndexOptions {
     // Options for geoHaystack indexes
     #[serde(rename="bucketSize", skip_serializing_if="Option::is_none")]
     pub bucket_size: Option<i32>,
+
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub collation: Option<Collation>,
+
+    /// Restricts the index to documents that match the filter.
+    #[serde(rename="partialFilterExpression", skip_serializing_if="Option::is_none")]
+    pub partial_filter_expression: Option<bson::Document>,
+
+    /// Hides the index from the query planner. Requires MongoDB 4.4 or newer.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub hidden: Option<bool>,
+
+    /// Rejects new duplicate keys in preparation for converting the index to a unique one.
+    /// Requires MongoDB 6.0 or newer.
+    #[serde(rename="prepareUnique", skip_serializing_if="Option::is_none")]
+    pub prepare_unique: Option<bool>,
+
+    // Options for wildcard indexes
+    #[serde(rename="wildcardProjection", skip_serializing_if="Option::is_none")]
+    pub wildcard_projection: Option<bson::Document>,
+
+    // Options for columnstore indexes
+    #[serde(rename="columnstoreProjection", skip_serializing_if="Option::is_none")]
+    pub columnstore_projection: Option<bson::Document>,
 }
 
 impl IndexOptions {
@@ -400,12 +958,13 @@ impl IndexModel {
             match *bson {
                 Bson::I32(ref i) => name.push_str(&format!("{}", i)),
                 Bson::String(ref s)
-                    if s == "text" || s == "hashed" || s == "2d" || s == "2dsphere" || s == "geoHaystack" => {
+                    if s == "text" || s == "hashed" || s == "2d" || s == "2dsphere" || s == "geoHaystack" ||
+                        s == "columnstore" => {
                     name.push_str(s)
                 }
                 _ => {
                     return Err(ArgumentError(String::from(
-                        r#"Index model keys must map to i32, "text", "hashed", "2d", "2dsphere" or "geoHaystack"."#,
+                        r#"Index model keys must map to i32, "text", "hashed", "2d", "2dsphere", "geoHaystack" or "columnstore"."#,
                     )))
                 }
             }
@@ -467,11 +1026,224 @@ impl IndexModel {
         if let Some(val) = self.options.bucket_size {
             doc.insert("bucketSize", val);
         }
+        if let Some(ref val) = self.options.collation {
+            doc.insert("collation", val.to_bson());
+        }
+        if let Some(ref val) = self.options.partial_filter_expression {
+            doc.insert("partialFilterExpression", val.clone());
+        }
+        if let Some(val) = self.options.hidden {
+            doc.insert("hidden", val);
+        }
+        if let Some(val) = self.options.prepare_unique {
+            doc.insert("prepareUnique", val);
+        }
+        if let Some(ref val) = self.options.wildcard_projection {
+            doc.insert("wildcardProjection", val.clone());
+        }
+        if let Some(ref val) = self.options.columnstore_projection {
+            doc.insert("columnstoreProjection", val.clone());
+        }
 
         Ok(doc)
     }
 }
 
+/// The number of data-bearing replica set members that must finish building new indexes
+/// before the primary marks them ready.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommitQuorum {
+    /// All data-bearing voting members, which is the server default.
+    VotingMembers,
+    Majority,
+    Nodes(i32),
+    /// A replica set tag set name.
+    Custom(String),
+}
+
+impl CommitQuorum {
+    pub fn to_bson(&self) -> Bson {
+        match *self {
+            CommitQuorum::VotingMembers => Bson::String(String::from("votingMembers")),
+            CommitQuorum::Majority => Bson::String(String::from("majority")),
+            CommitQuorum::Nodes(n) => Bson::I32(n),
+            CommitQuorum::Custom(ref name) => Bson::String(name.clone()),
+        }
+    }
+}
+
+/// Options for createIndexes operations.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CreateIndexesOptions {
+    /// Requires MongoDB 4.4 or newer.
+    pub commit_quorum: Option<CommitQuorum>,
+    pub max_time_ms: Option<i64>,
+    pub write_concern: Option<WriteConcern>,
+}
+
+impl CreateIndexesOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl From<CreateIndexesOptions> for bson::Document {
+    fn from(options: CreateIndexesOptions) -> Self {
+        let mut document = bson::Document::new();
+
+        if let Some(commit_quorum) = options.commit_quorum {
+            document.insert("commitQuorum", commit_quorum.to_bson());
+        }
+
+        if let Some(max_time_ms) = options.max_time_ms {
+            document.insert("maxTimeMS", max_time_ms);
+        }
+
+        if let Some(write_concern) = options.write_concern {
+            document.insert("writeConcern", write_concern.to_bson());
+        }
+
+        document
+    }
+}
+
+/// The properties of an existing index to change with `Collection::modify_index`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ModifyIndexOptions {
+    /// Hides the index from, or restores it to, the query planner. Requires MongoDB 4.4 or
+    /// newer.
+    pub hidden: Option<bool>,
+    /// Changes the expiry of a TTL index.
+    pub expire_after_seconds: Option<i32>,
+    /// Requires MongoDB 6.0 or newer.
+    pub prepare_unique: Option<bool>,
+    pub write_concern: Option<WriteConcern>,
+}
+
+impl ModifyIndexOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+/// Changes to apply to a collection or view with `Collection::modify`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CollModOptions {
+    /// A query filter or `$jsonSchema` document that inserted and updated documents must match.
+    pub validator: Option<bson::Document>,
+    pub validation_level: Option<ValidationLevel>,
+    pub validation_action: Option<ValidationAction>,
+    /// The new source collection of a view; `pipeline` must be given as well.
+    pub view_on: Option<String>,
+    /// The new pipeline of a view.
+    pub pipeline: Option<Vec<bson::Document>>,
+    pub write_concern: Option<WriteConcern>,
+}
+
+impl CollModOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl From<CollModOptions> for bson::Document {
+    fn from(options: CollModOptions) -> Self {
+        let mut document = bson::Document::new();
+
+        if let Some(validator) = options.validator {
+            document.insert("validator", validator);
+        }
+
+        if let Some(validation_level) = options.validation_level {
+            document.insert("validationLevel", validation_level.as_str());
+        }
+
+        if let Some(validation_action) = options.validation_action {
+            document.insert("validationAction", validation_action.as_str());
+        }
+
+        if let Some(view_on) = options.view_on {
+            document.insert("viewOn", view_on);
+        }
+
+        if let Some(pipeline) = options.pipeline {
+            let pipeline: Vec<_> = pipeline.into_iter().map(Bson::Document).collect();
+            document.insert("pipeline", pipeline);
+        }
+
+        if let Some(write_concern) = options.write_concern {
+            document.insert("writeConcern", write_concern.to_bson());
+        }
+
+        document
+    }
+}
+
+/// The names of one or more indexes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexNames(pub Vec<String>);
+
+impl IndexNames {
+    /// Encodes a single name as a string and several as an array, which requires MongoDB 4.2 or
+    /// newer.
+    pub fn to_bson(&self) -> Bson {
+        if self.0.len() == 1 {
+            Bson::String(self.0[0].clone())
+        } else {
+            Bson::Array(self.0.iter().cloned().map(Bson::String).collect())
+        }
+    }
+}
+
+impl From<String> for IndexNames {
+    fn from(name: String) -> Self {
+        IndexNames(vec![name])
+    }
+}
+
+impl<'a> From<&'a str> for IndexNames {
+    fn from(name: &'a str) -> Self {
+        IndexNames(vec![String::from(name)])
+    }
+}
+
+impl From<Vec<String>> for IndexNames {
+    fn from(names: Vec<String>) -> Self {
+        IndexNames(names)
+    }
+}
+
+impl<'a> From<Vec<&'a str>> for IndexNames {
+    fn from(names: Vec<&'a str>) -> Self {
+        IndexNames(names.into_iter().map(String::from).collect())
+    }
+}
+
+/// Identifies a single existing index by its key pattern or by its name.
+#[derive(Clone, Debug, PartialEq)]
+pub enum IndexSpecifier {
+    KeyPattern(bson::Document),
+    Name(String),
+}
+
+impl From<bson::Document> for IndexSpecifier {
+    fn from(keys: bson::Document) -> Self {
+        IndexSpecifier::KeyPattern(keys)
+    }
+}
+
+impl From<String> for IndexSpecifier {
+    fn from(name: String) -> Self {
+        IndexSpecifier::Name(name)
+    }
+}
+
+impl<'a> From<&'a str> for IndexSpecifier {
+    fn from(name: &'a str) -> Self {
+        IndexSpecifier::Name(String::from(name))
+    }
+}
+
 /// Options for insertMany operations.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct InsertManyOptions {
@@ -502,10 +1274,15 @@ impl From<InsertManyOptions> for bson::Document {
 }
 
 /// Options for update operations.
-#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct UpdateOptions {
     pub upsert: Option<bool>,
     pub write_concern: Option<WriteConcern>,
+    pub collation: Option<Collation>,
+    /// Filters that determine which array elements an update applies to.
+    pub array_filters: Option<Vec<bson::Document>>,
+    pub hint: Option<Hint>,
+    pub bypass_document_validation: Option<bool>,
 }
 
 impl UpdateOptions {
@@ -516,6 +1293,20 @@ impl UpdateOptions {
 
 pub type ReplaceOptions = UpdateOptions;
 
+/// Options for delete operations.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeleteOptions {
+    pub collation: Option<Collation>,
+    pub write_concern: Option<WriteConcern>,
+    pub hint: Option<Hint>,
+}
+
+impl DeleteOptions {
+    pub fn new() -> DeleteOptions {
+        Default::default()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -539,6 +1330,21 @@ mod test {
         opts.max = Some(-180.0);
         opts.min = Some(180.0);
         opts.bucket_size = Some(10);
+        opts.collation = Some(Collation {
+            locale: "fr".to_string(),
+            strength: Some(CollationStrength::Secondary),
+            case_level: Some(false),
+            case_first: Some(CollationCaseFirst::Upper),
+            numeric_ordering: Some(true),
+            alternate: Some(CollationAlternate::Shifted),
+            max_variable: Some(CollationMaxVariable::Space),
+            backwards: Some(true),
+        });
+        opts.partial_filter_expression = Some(doc!{"test_field": {"$exists": true}});
+        opts.hidden = Some(true);
+        opts.prepare_unique = Some(false);
+        opts.wildcard_projection = Some(doc!{"a.b": 1});
+        opts.columnstore_projection = Some(doc!{"c": 0});
         opts
     }
 
@@ -588,4 +1394,180 @@ mod test {
         assert_eq!(doc!{"test_field": "text"}, de.keys);
         assert_eq!(opts, de.options);
     }
+
+    #[test]
+    fn find_options_should_encode_as_legacy_modifiers() {
+        let mut opts = FindOptions::new();
+        opts.modifiers = Some(doc!{"$comment": "overridden", "$snapshot": true});
+        opts.comment = Some("find".to_string());
+        opts.sort = Some(doc!{"x": 1});
+        opts.hint = Some(Hint::Name("x_1".to_string()));
+        opts.max_time_ms = Some(100);
+        opts.show_record_id = Some(true);
+
+        let query = opts.to_legacy_query(doc!{"x": {"$gt": 1}}).unwrap();
+        assert_eq!(
+            doc!{
+                "$query": {"x": {"$gt": 1}},
+                "$snapshot": true,
+                "$orderby": {"x": 1},
+                "$hint": "x_1",
+                "$maxTimeMS": 100i64,
+                "$comment": "find",
+                "$showDiskLoc": true,
+            },
+            query
+        );
+
+        let query = FindOptions::new().to_legacy_query(doc!{"x": 1}).unwrap();
+        assert_eq!(doc!{"x": 1}, query);
+
+        let mut opts = FindOptions::new();
+        opts.allow_disk_use = Some(true);
+        assert!(opts.to_legacy_query(doc!{}).is_err());
+
+        let mut opts = FindOptions::new();
+        opts.read_concern = Some(ReadConcern::new(ReadConcernLevel::Majority));
+        assert!(opts.to_legacy_query(doc!{}).is_err());
+
+        opts.read_concern = Some(ReadConcern::new(ReadConcernLevel::Local));
+        assert_eq!(doc!{}, opts.to_legacy_query(doc!{}).unwrap());
+    }
+
+    #[test]
+    fn find_options_should_encode_as_find_command_fields() {
+        let mut opts = FindOptions::new();
+        opts.modifiers = Some(doc!{"$orderby": {"y": 1}, "$maxTimeMS": 5, "$showDiskLoc": true});
+        opts.sort = Some(doc!{"x": 1});
+        opts.hint = Some(Hint::Keys(doc!{"x": 1}));
+        opts.min = Some(doc!{"x": 0});
+        opts.return_key = Some(false);
+        opts.allow_disk_use = Some(true);
+        opts.let_vars = Some(doc!{"target": 1});
+        opts.max_await_time_ms = Some(10);
+        opts.cursor_type = CursorType::TailableAwait;
+
+        let document = bson::Document::from(opts);
+        assert_eq!(
+            doc!{
+                "maxTimeMS": 5,
+                "showRecordId": true,
+                "sort": {"x": 1},
+                "hint": {"x": 1},
+                "min": {"x": 0},
+                "returnKey": false,
+                "allowDiskUse": true,
+                "let": {"target": 1},
+                "tailable": true,
+                "awaitData": true,
+            },
+            document
+        );
+    }
+
+    #[test]
+    fn command_options_should_encode_max_time_ms() {
+        let mut aggregate = AggregateOptions::new();
+        aggregate.max_time_ms = Some(10);
+        assert_eq!(Some(&Bson::I64(10)), bson::Document::from(aggregate).get("maxTimeMS"));
+
+        let mut count = CountOptions::new();
+        count.max_time_ms = Some(20);
+        assert_eq!(Some(&Bson::I64(20)), bson::Document::from(count).get("maxTimeMS"));
+
+        let mut find_one_and_delete = FindOneAndDeleteOptions::new();
+        find_one_and_delete.max_time_ms = Some(30);
+        assert_eq!(
+            Some(&Bson::I64(30)),
+            bson::Document::from(find_one_and_delete).get("maxTimeMS")
+        );
+
+        let mut find_one_and_update = FindOneAndUpdateOptions::new();
+        find_one_and_update.max_time_ms = Some(40);
+        assert_eq!(
+            Some(&Bson::I64(40)),
+            bson::Document::from(find_one_and_update).get("maxTimeMS")
+        );
+
+        assert!(!bson::Document::from(CountOptions::new()).contains_key("maxTimeMS"));
+    }
+
+    #[test]
+    fn aggregate_options_should_encode_command_fields() {
+        let mut opts = AggregateOptions::new();
+        opts.batch_size = 10;
+        opts.hint = Some(Hint::Name("x_1".to_string()));
+        opts.bypass_document_validation = Some(true);
+        opts.comment = Some("report".to_string());
+        opts.let_vars = Some(doc!{"min": 5});
+        opts.write_concern = Some(WriteConcern::new());
+
+        assert_eq!(
+            doc!{
+                "cursor": {"batchSize": 10},
+                "hint": "x_1",
+                "bypassDocumentValidation": true,
+                "comment": "report",
+                "let": {"min": 5},
+            },
+            bson::Document::from(opts)
+        );
+
+        let mut opts = AggregateOptions::new();
+        opts.explain = Some(true);
+        assert_eq!(doc!{"explain": true}, bson::Document::from(opts));
+    }
+
+    #[test]
+    fn create_indexes_options_should_encode_commit_quorum() {
+        let mut opts = CreateIndexesOptions::new();
+        opts.commit_quorum = Some(CommitQuorum::Majority);
+        opts.max_time_ms = Some(500);
+        assert_eq!(
+            doc!{"commitQuorum": "majority", "maxTimeMS": 500i64},
+            bson::Document::from(opts)
+        );
+
+        let mut opts = CreateIndexesOptions::new();
+        opts.commit_quorum = Some(CommitQuorum::Nodes(2));
+        assert_eq!(doc!{"commitQuorum": 2}, bson::Document::from(opts));
+    }
+
+    #[test]
+    fn index_names_should_encode_single_names_as_strings() {
+        assert_eq!(Bson::String("a_1".to_string()), IndexNames::from("a_1").to_bson());
+        assert_eq!(
+            Bson::Array(vec![Bson::String("a_1".to_string()), Bson::String("b_1".to_string())]),
+            IndexNames::from(vec!["a_1", "b_1"]).to_bson()
+        );
+    }
+
+    #[test]
+    fn listed_index_specifications_should_deserialize() {
+        let spec = doc!{
+            "v": 2,
+            "key": {"$**": 1},
+            "name": "$**_1",
+            "wildcardProjection": {"a": 1},
+            "partialFilterExpression": {"x": {"$gt": 5}},
+            "hidden": true,
+            "collation": {
+                "locale": "en",
+                "caseLevel": false,
+                "strength": 2,
+                "normalization": false,
+                "version": "57.1",
+            },
+        };
+
+        let model: IndexModel = bson::from_bson(Bson::Document(spec)).unwrap();
+        assert_eq!(Some(doc!{"a": 1}), model.options.wildcard_projection);
+        assert_eq!(Some(doc!{"x": {"$gt": 5}}), model.options.partial_filter_expression);
+        assert_eq!(Some(true), model.options.hidden);
+        assert_eq!(
+            Some(CollationStrength::Secondary),
+            model.options.collation.as_ref().unwrap().strength
+        );
+        assert_eq!("$**_1", model.name().unwrap());
+    }
 }
@@ -1,6 +1,7 @@
 //! Results for collection-level operations.
 use bson;
 use bson::Bson;
+use common::{get_f64, get_i64};
 use std::collections::BTreeMap;
 use super::error::{BulkWriteException, WriteException};
 use super::options::WriteModel;
@@ -87,32 +88,49 @@ impl BulkWriteResult {
         }
     }
 
-    /// Adds the data in a BulkDeleteResult to this result.
+    // Maps the batch-relative indexes of write errors to the indexes of the original requests.
+    fn remap_write_errors(exception: &mut Option<BulkWriteException>, indexes: &[i64]) {
+        if let Some(ref mut exception) = *exception {
+            for error in &mut exception.write_errors {
+                if let Some(&index) = indexes.get(error.index as usize) {
+                    error.index = index as i32;
+                }
+            }
+        }
+    }
+
+    /// Adds the data in a BulkDeleteResult to this result. `indexes` holds the original request
+    /// index of each model in the batch.
     pub fn process_bulk_delete_result(
         &mut self,
-        result: BulkDeleteResult,
+        mut result: BulkDeleteResult,
         models: Vec<WriteModel>,
+        indexes: &[i64],
         exception: &mut BulkWriteException,
     ) -> bool {
+        BulkWriteResult::remap_write_errors(&mut result.write_exception, indexes);
         let ok = exception.add_bulk_write_exception(result.write_exception, models);
         self.deleted_count += result.deleted_count;
 
         ok
     }
 
-    /// Adds the data in an InsertManyResult to this result.
+    /// Adds the data in an InsertManyResult to this result. `indexes` holds the original request
+    /// index of each model in the batch.
     pub fn process_insert_many_result(
         &mut self,
-        result: InsertManyResult,
+        mut result: InsertManyResult,
         models: Vec<WriteModel>,
-        start_index: i64,
+        indexes: &[i64],
         exception: &mut BulkWriteException,
     ) -> bool {
+        BulkWriteResult::remap_write_errors(&mut result.bulk_write_exception, indexes);
         let ok = exception.add_bulk_write_exception(result.bulk_write_exception, models);
 
         if let Some(ids) = result.inserted_ids {
             for (i, id) in ids {
-                self.inserted_ids.insert(start_index + i, id);
+                let index = indexes.get(i as usize).cloned().unwrap_or(i);
+                self.inserted_ids.insert(index, id);
                 self.inserted_count += 1;
             }
         }
@@ -124,21 +142,24 @@ impl BulkWriteResult {
     // the tree of upserted ids.
     fn parse_upserted_id(
         mut document: bson::Document,
-        start_index: i64,
+        indexes: &[i64],
         upserted_ids: &mut BTreeMap<i64, Bson>,
     ) -> i32 {
         let (index, id) = (document.remove("index"), document.remove("_id"));
 
-        match (index, id) {
-            (Some(Bson::I32(i)), Some(bson_id)) => {
-                let _ = upserted_ids.insert(start_index + i as i64, bson_id);
-                1
-            }
-            (Some(Bson::I64(i)), Some(bson_id)) => {
-                let _ = upserted_ids.insert(start_index + i, bson_id.clone());
+        let index = match index {
+            Some(Bson::I32(i)) => i as i64,
+            Some(Bson::I64(i)) => i,
+            _ => return 0,
+        };
+
+        match id {
+            Some(bson_id) => {
+                let index = indexes.get(index as usize).cloned().unwrap_or(index);
+                let _ = upserted_ids.insert(index, bson_id);
                 1
             }
-            _ => 0,
+            None => 0,
         }
     }
 
@@ -146,19 +167,19 @@ impl BulkWriteResult {
     // them to the tree of upserted ids.
     fn parse_upserted_ids(
         bson: Bson,
-        start_index: i64,
+        indexes: &[i64],
         upserted_ids: &mut BTreeMap<i64, Bson>,
     ) -> i32 {
         match bson {
             Bson::Document(doc) => {
-                BulkWriteResult::parse_upserted_id(doc, start_index, upserted_ids)
+                BulkWriteResult::parse_upserted_id(doc, indexes, upserted_ids)
             }
             Bson::Array(vec) => {
                 let mut count = 0;
 
                 for bson in vec {
                     if let Bson::Document(doc) = bson {
-                        count += BulkWriteResult::parse_upserted_id(doc, start_index, upserted_ids)
+                        count += BulkWriteResult::parse_upserted_id(doc, indexes, upserted_ids)
                     }
                 }
 
@@ -168,14 +189,16 @@ impl BulkWriteResult {
         }
     }
 
-    /// Adds the data in a BulkUpdateResult to this result.
+    /// Adds the data in a BulkUpdateResult to this result. `indexes` holds the original request
+    /// index of each model in the batch.
     pub fn process_bulk_update_result(
         &mut self,
-        result: BulkUpdateResult,
+        mut result: BulkUpdateResult,
         models: Vec<WriteModel>,
-        start_index: i64,
+        indexes: &[i64],
         exception: &mut BulkWriteException,
     ) -> bool {
+        BulkWriteResult::remap_write_errors(&mut result.write_exception, indexes);
         let ok = exception.add_bulk_write_exception(result.write_exception, models);
 
         self.matched_count += result.matched_count;
@@ -184,7 +207,7 @@ impl BulkWriteResult {
         if let Some(upserted_ids) = result.upserted_ids {
             self.upserted_count += BulkWriteResult::parse_upserted_ids(
                 upserted_ids,
-                start_index,
+                indexes,
                 &mut self.upserted_ids,
             );
         }
@@ -217,8 +240,8 @@ impl BulkUpdateResult {
             _ => 0,
         };
 
-        let (n_upserted, id) = match doc.get("upserted") {
-            Some(&Bson::Array(ref arr)) => (arr.len() as i32, Some(arr[0].clone())),
+        let (n_upserted, ids) = match doc.get("upserted") {
+            Some(&Bson::Array(ref arr)) => (arr.len() as i32, Some(Bson::Array(arr.clone()))),
             _ => (0, None),
         };
 
@@ -233,7 +256,7 @@ impl BulkUpdateResult {
             acknowledged: true,
             matched_count: n_matched,
             modified_count: n_modified,
-            upserted_ids: id,
+            upserted_ids: ids,
             write_exception: exception,
         }
     }
@@ -328,12 +351,105 @@ impl UpdateResult {
             None => None,
         };
 
+        // A single update upserts at most one document.
+        let upserted_id = match result.upserted_ids {
+            Some(Bson::Array(mut ids)) => if ids.is_empty() { None } else { Some(ids.remove(0)) },
+            other => other,
+        };
+
         UpdateResult {
             acknowledged: result.acknowledged,
             matched_count: result.matched_count,
             modified_count: result.modified_count,
-            upserted_id: result.upserted_ids,
+            upserted_id: upserted_id,
             write_exception: exception,
         }
     }
 }
+
+/// Storage statistics for a collection, as reported by `collStats`.
+///
+/// Sizes are in bytes unless a scale was requested.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollStats {
+    pub ns: String,
+    pub count: i64,
+    pub size: i64,
+    pub avg_obj_size: f64,
+    pub storage_size: i64,
+    pub nindexes: i64,
+    pub total_index_size: i64,
+    /// The size of each index, by name.
+    pub index_sizes: BTreeMap<String, i64>,
+    /// The sum of the storage and index sizes. Not reported before MongoDB 4.4.
+    pub total_size: Option<i64>,
+    pub capped: bool,
+    /// The document limit of a capped collection.
+    pub max: Option<i64>,
+    pub scale_factor: i64,
+    pub raw: bson::Document,
+}
+
+impl CollStats {
+    /// Extracts the statistics from a `collStats` reply.
+    pub fn new(raw: bson::Document) -> CollStats {
+        let index_sizes = match raw.get_document("indexSizes") {
+            Ok(sizes) => {
+                sizes
+                    .keys()
+                    .filter_map(|name| get_i64(sizes, name).map(|size| (name.to_owned(), size)))
+                    .collect()
+            }
+            Err(_) => BTreeMap::new(),
+        };
+
+        CollStats {
+            ns: raw.get_str("ns").unwrap_or_default().to_owned(),
+            count: get_i64(&raw, "count").unwrap_or(0),
+            size: get_i64(&raw, "size").unwrap_or(0),
+            avg_obj_size: get_f64(&raw, "avgObjSize").unwrap_or(0.0),
+            storage_size: get_i64(&raw, "storageSize").unwrap_or(0),
+            nindexes: get_i64(&raw, "nindexes").unwrap_or(0),
+            total_index_size: get_i64(&raw, "totalIndexSize").unwrap_or(0),
+            index_sizes: index_sizes,
+            total_size: get_i64(&raw, "totalSize"),
+            capped: raw.get_bool("capped").unwrap_or(false),
+            max: get_i64(&raw, "max"),
+            scale_factor: get_i64(&raw, "scaleFactor").unwrap_or(1),
+            raw: raw,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bson::{bson, doc};
+    use super::*;
+
+    #[test]
+    fn coll_stats_are_parsed() {
+        let stats = CollStats::new(doc!{
+            "ns": "shop.orders",
+            "size": 4096,
+            "count": 40,
+            "avgObjSize": 102,
+            "storageSize": 36864,
+            "capped": false,
+            "nindexes": 2,
+            "totalIndexSize": 73728,
+            "totalSize": 110592,
+            "indexSizes": {"_id_": 36864, "x_1": 36864i64},
+            "scaleFactor": 1,
+            "ok": 1.0,
+        });
+
+        assert_eq!("shop.orders", stats.ns);
+        assert_eq!(40, stats.count);
+        assert_eq!(102.0, stats.avg_obj_size);
+        assert_eq!(2, stats.nindexes);
+        assert_eq!(Some(&36864), stats.index_sizes.get("x_1"));
+        assert_eq!(Some(110592), stats.total_size);
+        assert!(!stats.capped);
+        assert_eq!(None, stats.max);
+    }
+}
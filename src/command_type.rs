@@ -4,11 +4,18 @@
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum CommandType {
     Aggregate,
+    /// An aggregation whose pipeline ends in an `$out` or `$merge` stage.
+    AggregateWrite,
     BuildInfo,
+    CollMod,
+    CollStats,
     Count,
     CreateCollection,
     CreateIndexes,
+    CreateRole,
     CreateUser,
+    CurrentOp,
+    DbStats,
     DeleteMany,
     DeleteOne,
     Distinct,
@@ -16,33 +23,52 @@ pub enum CommandType {
     DropCollection,
     DropDatabase,
     DropIndexes,
+    DropRole,
     DropUser,
+    Explain,
     Find,
     FindOneAndDelete,
     FindOneAndReplace,
     FindOneAndUpdate,
+    GetRoles,
     GetUser,
     GetUsers,
+    GrantPrivilegesToRole,
+    GrantRolesToUser,
     InsertMany,
     InsertOne,
     IsMaster,
+    KillOp,
     ListCollections,
     ListDatabases,
     ListIndexes,
+    Profile,
+    RenameCollection,
+    RevokePrivilegesFromRole,
+    RevokeRolesFromUser,
+    ServerStatus,
     Suppressed,
     UpdateMany,
     UpdateOne,
+    UpdateRole,
+    UpdateUser,
 }
 
 impl CommandType {
     pub fn to_str(&self) -> &str {
         match *self {
             CommandType::Aggregate => "aggregate",
+            CommandType::AggregateWrite => "aggregate",
             CommandType::BuildInfo => "buildinfo",
+            CommandType::CollMod => "coll_mod",
+            CommandType::CollStats => "coll_stats",
             CommandType::Count => "count",
             CommandType::CreateCollection => "create_collection",
             CommandType::CreateIndexes => "create_indexes",
+            CommandType::CreateRole => "create_role",
             CommandType::CreateUser => "create_user",
+            CommandType::CurrentOp => "current_op",
+            CommandType::DbStats => "db_stats",
             CommandType::DeleteMany => "delete_many",
             CommandType::DeleteOne => "delete_one",
             CommandType::Distinct => "distinct",
@@ -50,29 +76,45 @@ impl CommandType {
             CommandType::DropCollection => "drop_collection",
             CommandType::DropDatabase => "drop_database",
             CommandType::DropIndexes => "drop_indexes",
+            CommandType::DropRole => "drop_role",
             CommandType::DropUser => "drop_user",
+            CommandType::Explain => "explain",
             CommandType::Find => "find",
             CommandType::FindOneAndDelete => "find_one_and_delete",
             CommandType::FindOneAndReplace => "find_one_and_replace",
             CommandType::FindOneAndUpdate => "find_one_and_update",
+            CommandType::GetRoles => "get_roles",
             CommandType::GetUser => "get_user",
             CommandType::GetUsers => "get_users",
+            CommandType::GrantPrivilegesToRole => "grant_privileges_to_role",
+            CommandType::GrantRolesToUser => "grant_roles_to_user",
             CommandType::InsertMany => "insert_many",
             CommandType::InsertOne => "insert_one",
             CommandType::IsMaster => "is_master",
+            CommandType::KillOp => "kill_op",
             CommandType::ListCollections => "list_collections",
             CommandType::ListDatabases => "list_databases",
             CommandType::ListIndexes => "list_indexes",
+            CommandType::Profile => "profile",
+            CommandType::RenameCollection => "rename_collection",
+            CommandType::RevokePrivilegesFromRole => "revoke_privileges_from_role",
+            CommandType::RevokeRolesFromUser => "revoke_roles_from_user",
+            CommandType::ServerStatus => "server_status",
             CommandType::Suppressed => "suppressed",
             CommandType::UpdateMany => "update_many",
             CommandType::UpdateOne => "update_one",
+            CommandType::UpdateRole => "update_role",
+            CommandType::UpdateUser => "update_user",
         }
     }
 
     pub fn is_write_command(&self) -> bool {
         match *self {
+            CommandType::AggregateWrite |
+            CommandType::CollMod |
             CommandType::CreateCollection |
             CommandType::CreateIndexes |
+            CommandType::CreateRole |
             CommandType::CreateUser |
             CommandType::DeleteMany |
             CommandType::DeleteOne |
@@ -80,25 +122,41 @@ impl CommandType {
             CommandType::DropCollection |
             CommandType::DropDatabase |
             CommandType::DropIndexes |
+            CommandType::DropRole |
             CommandType::DropUser |
             CommandType::FindOneAndDelete |
             CommandType::FindOneAndReplace |
             CommandType::FindOneAndUpdate |
+            CommandType::GrantPrivilegesToRole |
+            CommandType::GrantRolesToUser |
             CommandType::InsertMany |
             CommandType::InsertOne |
+            CommandType::KillOp |
+            CommandType::Profile |
+            CommandType::RenameCollection |
+            CommandType::RevokePrivilegesFromRole |
+            CommandType::RevokeRolesFromUser |
             CommandType::UpdateMany |
-            CommandType::UpdateOne => true,
+            CommandType::UpdateOne |
+            CommandType::UpdateRole |
+            CommandType::UpdateUser => true,
             CommandType::Aggregate |
             CommandType::BuildInfo |
+            CommandType::CollStats |
             CommandType::Count |
+            CommandType::CurrentOp |
+            CommandType::DbStats |
             CommandType::Distinct |
+            CommandType::Explain |
             CommandType::Find |
+            CommandType::GetRoles |
             CommandType::GetUser |
             CommandType::GetUsers |
             CommandType::IsMaster |
             CommandType::ListCollections |
             CommandType::ListDatabases |
             CommandType::ListIndexes |
+            CommandType::ServerStatus |
             CommandType::Suppressed => false,
         }
     }
@@ -68,14 +68,114 @@ impl ReadPreference {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Indicates the consistency and isolation guarantees of data returned by read operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReadConcernLevel {
+    Local,
+    Available,
+    Majority,
+    Linearizable,
+    Snapshot,
+}
+
+impl ReadConcernLevel {
+    pub fn to_str(&self) -> &'static str {
+        match *self {
+            ReadConcernLevel::Local => "local",
+            ReadConcernLevel::Available => "available",
+            ReadConcernLevel::Majority => "majority",
+            ReadConcernLevel::Linearizable => "linearizable",
+            ReadConcernLevel::Snapshot => "snapshot",
+        }
+    }
+}
+
+impl FromStr for ReadConcernLevel {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "local" => ReadConcernLevel::Local,
+            "available" => ReadConcernLevel::Available,
+            "majority" => ReadConcernLevel::Majority,
+            "linearizable" => ReadConcernLevel::Linearizable,
+            "snapshot" => ReadConcernLevel::Snapshot,
+            _ => {
+                return Err(ArgumentError(
+                    format!("Could not convert '{}' to ReadConcernLevel.", s),
+                ))
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ReadConcern {
+    /// The level of consistency and isolation requested from the server.
+    pub level: ReadConcernLevel,
+}
+
+impl ReadConcern {
+    pub fn new(level: ReadConcernLevel) -> ReadConcern {
+        ReadConcern { level: level }
+    }
+
+    pub fn to_bson(&self) -> bson::Document {
+        doc! { "level": self.level.to_str() }
+    }
+}
+
+/// Indicates how many servers, or which tagged set of servers, must acknowledge a write.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Acknowledgment {
+    /// Requires acknowledgment from the given number of nodes. A value of 0 requests no
+    /// acknowledgment at all.
+    Nodes(i32),
+    /// Requires acknowledgment from a majority of the voting members of the replica set.
+    Majority,
+    /// Requires acknowledgment from the members matching a custom `getLastErrorModes` tag set.
+    Tag(String),
+}
+
+impl Acknowledgment {
+    pub fn to_bson(&self) -> Bson {
+        match *self {
+            Acknowledgment::Nodes(n) => Bson::I32(n),
+            Acknowledgment::Majority => Bson::String(String::from("majority")),
+            Acknowledgment::Tag(ref tag) => Bson::String(tag.to_owned()),
+        }
+    }
+}
+
+impl FromStr for Acknowledgment {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        if s.is_empty() {
+            return Err(ArgumentError(
+                String::from("Could not convert an empty string to Acknowledgment."),
+            ));
+        }
+
+        Ok(match s.parse::<i32>() {
+            Ok(n) if n < 0 => {
+                return Err(ArgumentError(
+                    format!("Could not convert '{}' to Acknowledgment.", s),
+                ))
+            }
+            Ok(n) => Acknowledgment::Nodes(n),
+            Err(_) if s == "majority" => Acknowledgment::Majority,
+            Err(_) => Acknowledgment::Tag(s.to_owned()),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct WriteConcern {
     /// Write replication
-    pub w: i32,
+    pub w: Acknowledgment,
     /// Used in conjunction with 'w'. Propagation timeout in ms.
-    pub w_timeout: i32,
+    pub w_timeout: Option<i32>,
     /// If true, will block until write operations have been committed to journal.
-    pub j: bool,
+    pub j: Option<bool>,
     /// If true and server is not journaling, blocks until server has synced all data files to disk.
     pub fsync: bool,
 }
@@ -83,19 +183,42 @@ pub struct WriteConcern {
 impl WriteConcern {
     pub fn new() -> WriteConcern {
         WriteConcern {
-            w: 1,
-            w_timeout: 0,
-            j: false,
+            w: Acknowledgment::Nodes(1),
+            w_timeout: None,
+            j: None,
             fsync: false,
         }
     }
 
+    /// Creates a write concern that requests no acknowledgment from the server.
+    pub fn unacknowledged() -> WriteConcern {
+        WriteConcern {
+            w: Acknowledgment::Nodes(0),
+            ..WriteConcern::new()
+        }
+    }
+
+    /// Returns false if writes using this concern will not be acknowledged by the server.
+    pub fn is_acknowledged(&self) -> bool {
+        self.w != Acknowledgment::Nodes(0) || self.j == Some(true) || self.fsync
+    }
+
     pub fn to_bson(&self) -> bson::Document {
-        doc! {
-            "w": self.w,
-            "wtimeout": self.w_timeout,
-            "j": self.j,
+        let mut doc = doc! { "w": self.w.to_bson() };
+
+        if let Some(w_timeout) = self.w_timeout {
+            doc.insert("wtimeout", w_timeout);
+        }
+
+        if let Some(j) = self.j {
+            doc.insert("j", j);
+        }
+
+        if self.fsync {
+            doc.insert("fsync", true);
         }
+
+        doc
     }
 }
 
@@ -115,3 +238,23 @@ pub fn merge_options<T: Into<bson::Document>>(
         .chain(options_doc.into_iter())
         .collect()
 }
+
+// Reads an integer field that the server may encode as any numeric BSON type.
+pub(crate) fn get_i64(document: &bson::Document, key: &str) -> Option<i64> {
+    match document.get(key) {
+        Some(&Bson::I32(n)) => Some(n as i64),
+        Some(&Bson::I64(n)) => Some(n),
+        Some(&Bson::FloatingPoint(n)) => Some(n as i64),
+        _ => None,
+    }
+}
+
+// Reads a floating point field that the server may encode as any numeric BSON type.
+pub(crate) fn get_f64(document: &bson::Document, key: &str) -> Option<f64> {
+    match document.get(key) {
+        Some(&Bson::I32(n)) => Some(n as f64),
+        Some(&Bson::I64(n)) => Some(n as f64),
+        Some(&Bson::FloatingPoint(n)) => Some(n),
+        _ => None,
+    }
+}
@@ -1,5 +1,6 @@
 //! Connection string parsing and options.
 use Result;
+use common::WriteConcern;
 use Error::ArgumentError;
 use std::collections::BTreeMap;
 
@@ -63,6 +64,35 @@ impl ConnectionOptions {
     pub fn get(&self, key: &str) -> Option<&String> {
         self.options.get(key)
     }
+
+    /// Builds a write concern from the `w`, `wtimeoutMS`, `journal`, and `fsync` options.
+    pub fn write_concern(&self) -> Result<WriteConcern> {
+        let mut write_concern = WriteConcern::new();
+
+        if let Some(w) = self.get("w") {
+            write_concern.w = w.parse()?;
+        }
+
+        if let Some(w_timeout) = self.get("wtimeoutMS") {
+            write_concern.w_timeout = Some(w_timeout.parse().map_err(|_| {
+                ArgumentError(format!("Could not convert '{}' to wtimeoutMS.", w_timeout))
+            })?);
+        }
+
+        if let Some(journal) = self.get("journal") {
+            write_concern.j = Some(journal.parse().map_err(|_| {
+                ArgumentError(format!("Could not convert '{}' to journal.", journal))
+            })?);
+        }
+
+        if let Some(fsync) = self.get("fsync") {
+            write_concern.fsync = fsync.parse().map_err(|_| {
+                ArgumentError(format!("Could not convert '{}' to fsync.", fsync))
+            })?;
+        }
+
+        Ok(write_concern)
+    }
 }
 
 /// Encapsulates information for connection to a single MongoDB host or replicated set.
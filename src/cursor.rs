@@ -27,11 +27,12 @@ use {Client, CommandType, Error, ErrorCode, Result, ThreadedClient};
 use apm::{CommandStarted, CommandResult, EventRunner};
 
 use bson::{self, bson, doc, Bson};
-use common::{merge_options, ReadMode, ReadPreference};
-use coll::options::FindOptions;
+use common::{merge_options, ReadPreference};
+use coll::options::{Collation, CursorType, FindOptions};
+use connstring::Host;
 use pool::PooledStream;
 use time;
-use wire_protocol::flags::OpQueryFlags;
+use wire_protocol::flags::{OpMsgFlags, OpQueryFlags};
 use wire_protocol::operations::Message;
 
 use std::{ i32, usize };
@@ -41,6 +42,13 @@ use std::collections::vec_deque::VecDeque;
 // Allows the server to decide the batch size.
 pub const DEFAULT_BATCH_SIZE: i32 = 0;
 
+// The first wire version with the `find`, `getMore`, and `killCursors` commands (MongoDB 3.2).
+const FIND_COMMAND_WIRE_VERSION: i64 = 4;
+
+// The first wire version to stream `getMore` replies to OP_MSG requests with `exhaustAllowed`
+// set (MongoDB 4.2).
+const OP_MSG_EXHAUST_WIRE_VERSION: i64 = 8;
+
 /// Maintains a connection to the server and lazily returns documents from a
 /// query.
 #[derive(Debug)]
@@ -59,7 +67,20 @@ pub struct Cursor {
     count: i32,
     // A cache for documents received from the query that have not yet been returned.
     buffer: VecDeque<bson::Document>,
-    read_preference: ReadPreference,
+    // The server that owns the cursor; follow-up operations are always sent here.
+    host: Host,
+    // The connection pool iteration of the server at the moment the cursor was opened.
+    pool_iteration: usize,
+    // Whether the cursor was opened with the SLAVE_OK flag.
+    slave_ok: bool,
+    // Whether the cursor remains open after its last document has been returned.
+    tailable: bool,
+    // How long a `getMore` on an awaitData cursor waits for new documents.
+    max_await_time_ms: Option<i64>,
+    // The stream of an exhaust cursor, on which the server streams its batches.
+    exhaust_stream: Option<PooledStream>,
+    // Whether the server sends the next batch of an exhaust cursor without being asked for it.
+    more_to_come: bool,
     cmd_type: CommandType,
 }
 
@@ -109,9 +130,23 @@ impl Cursor {
         doc: bson::Document,
         cmd_type: CommandType,
         read_pref: ReadPreference,
+    ) -> Result<Cursor> {
+        Cursor::command_cursor_with_collation(client, db, doc, cmd_type, read_pref, None)
+    }
+
+    // Constructs a cursor for a command that carries the given collation, so that servers
+    // without collation support reject it before it is sent.
+    pub(crate) fn command_cursor_with_collation(
+        client: Client,
+        db: &str,
+        doc: bson::Document,
+        cmd_type: CommandType,
+        read_pref: ReadPreference,
+        collation: Option<Collation>,
     ) -> Result<Cursor> {
         let mut options = FindOptions::new();
         options.batch_size = Some(1);
+        options.collation = collation;
 
         Cursor::query(
             client.clone(),
@@ -157,19 +192,90 @@ impl Cursor {
         }
     }
 
+    // Returns the error code of a failed command reply, or None if the command succeeded.
+    fn command_reply_error_code(reply: &bson::Document) -> Option<i32> {
+        let ok = match reply.get("ok") {
+            Some(&Bson::FloatingPoint(ok)) => ok == 1.0,
+            Some(&Bson::I32(ok)) => ok == 1,
+            Some(&Bson::I64(ok)) => ok == 1,
+            _ => return None,
+        };
+
+        if ok {
+            return None;
+        }
+
+        match reply.get("code") {
+            Some(&Bson::I32(code)) => Some(code),
+            Some(&Bson::I64(code)) => Some(code as i32),
+            Some(&Bson::FloatingPoint(code)) => Some(code as i32),
+            _ => Some(0),
+        }
+    }
+
+    // Converts an error reply from a cursor-returning command into an OperationError that
+    // carries the server's error code and message.
+    fn check_command_reply(reply: &bson::Document) -> Result<()> {
+        let code = match Cursor::command_reply_error_code(reply) {
+            Some(code) => code,
+            None => return Ok(()),
+        };
+
+        let msg = match reply.get("errmsg") {
+            Some(&Bson::String(ref msg)) => msg.to_owned(),
+            _ => format!("Command failed with reply {}", reply),
+        };
+
+        match ErrorCode::from_i32(code) {
+            Some(ErrorCode::CursorNotFound) => Err(Error::CursorNotFoundError),
+            Some(name) => Err(Error::OperationError(format!("{} ({}): {}", name, code, msg))),
+            None => Err(Error::OperationError(msg)),
+        }
+    }
+
+    // Reads the cursor information out of the reply to a cursor-returning command, which
+    // arrives in an OP_REPLY for commands sent with OP_QUERY and in an OP_MSG otherwise.
     fn get_bson_and_cursor_info_from_command_message(
         message: Message,
+        namespace: &str,
+        empty_if_missing: bool,
     ) -> Result<(bson::Document, VecDeque<bson::Document>, i64, String)> {
+        let first = match message {
+            Message::OpReply { mut documents, .. } => {
+                if documents.is_empty() {
+                    return Err(Error::CursorNotFoundError);
+                }
+                documents.remove(0)
+            }
+            Message::OpMsg { body, .. } => body,
+            _ => return Err(Error::CursorNotFoundError),
+        };
 
-        let (first, mut v, _) = Cursor::get_bson_and_cid_from_message(message)?;
+        // Listing the collections of a missing database or the indexes of a missing collection
+        // is reported as an exhausted, empty cursor.
+        let code = Cursor::command_reply_error_code(&first);
+        if empty_if_missing && (code == Some(ErrorCode::CommandNotFound as i32) ||
+            code == Some(ErrorCode::NamespaceNotFound as i32))
+        {
+            return Ok((first, VecDeque::new(), 0, String::from(namespace)));
+        }
+
+        Cursor::check_command_reply(&first)?;
 
         // Extract cursor information
-        let mut cursor = match v.remove(0).and_then(|mut doc| doc.remove("cursor")) {
-            Some(Bson::Document(cursor)) => cursor,
+        let mut cursor = match first.get("cursor") {
+            Some(&Bson::Document(ref cursor)) => cursor.clone(),
             _ => return Err(Error::CursorNotFoundError),
         };
 
-        match (cursor.remove("id"), cursor.remove("ns"), cursor.remove("firstBatch")) {
+        // `find` and other cursor-returning commands reply with a `firstBatch`, and `getMore`
+        // replies with a `nextBatch`.
+        let batch = match cursor.remove("firstBatch") {
+            Some(batch) => Some(batch),
+            None => cursor.remove("nextBatch"),
+        };
+
+        match (cursor.remove("id"), cursor.remove("ns"), batch) {
             (Some(Bson::I64(id)),
              Some(Bson::String(ns)),
              Some(Bson::Array(batch))) => {
@@ -225,6 +331,186 @@ impl Cursor {
             client.acquire_stream(read_pref.to_owned())?
         };
 
+        Cursor::query_with_selection(
+            &mut stream,
+            slave_ok,
+            send_read_pref,
+            client,
+            namespace,
+            flags,
+            query,
+            options,
+            cmd_type,
+            is_cmd_cursor,
+            read_pref,
+        )
+    }
+
+    /// Executes a find on a collection, using a `find` command if the selected server supports
+    /// it or a legacy OP_QUERY otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// `client` - The client to read from.
+    /// `namespace` - The namespace of the collection to query.
+    /// `filter` - Specifies which documents to return.
+    /// `options` - Options for the query.
+    /// `cmd_type` - The type of command, which will be used for monitoring events.
+    /// `read_pref` - The read preference for the query.
+    ///
+    /// # Return value
+    ///
+    /// Returns the cursor for the query results on success, or an Error on
+    /// failure.
+    pub fn find(
+        client: Client,
+        namespace: String,
+        filter: bson::Document,
+        options: FindOptions,
+        cmd_type: CommandType,
+        read_pref: ReadPreference,
+    ) -> Result<Cursor> {
+        let (mut stream, slave_ok, send_read_pref) =
+            client.acquire_stream(read_pref.to_owned())?;
+
+        let tailable = options.cursor_type != CursorType::NonTailable;
+        let exhaust = options.exhaust;
+
+        // Servers that predate `exhaustAllowed` only stream batches in reply to an OP_QUERY with
+        // the exhaust flag set.
+        let legacy_exhaust = exhaust && stream.max_wire_version < OP_MSG_EXHAUST_WIRE_VERSION;
+
+        if legacy_exhaust || stream.max_wire_version < FIND_COMMAND_WIRE_VERSION {
+            let flags = OpQueryFlags::with_find_options(&options);
+            let query = options.to_legacy_query(filter)?;
+
+            let mut cursor = Cursor::query_with_selection(
+                &mut stream,
+                slave_ok,
+                send_read_pref,
+                client,
+                namespace,
+                flags,
+                query,
+                options,
+                cmd_type,
+                false,
+                read_pref,
+            )?;
+
+            cursor.tailable = tailable;
+
+            // The remaining batches will arrive on this stream, so the cursor keeps it.
+            if exhaust && cursor.cursor_id != 0 {
+                cursor.exhaust_stream = Some(stream);
+                cursor.more_to_come = true;
+            }
+
+            return Ok(cursor);
+        }
+
+        // The server only accepts `maxTimeMS` on a `getMore` for awaitData cursors.
+        let max_await_time_ms = if options.cursor_type == CursorType::TailableAwait {
+            options.max_await_time_ms
+        } else {
+            None
+        };
+
+        let (db_name, coll_name) = split_namespace(&namespace)?;
+
+        let batch_size = options.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
+        let limit = options.limit;
+        let collation = options.collation.clone();
+        let command = Cursor::find_command(coll_name, filter, options);
+
+        let cmd_options = FindOptions {
+            batch_size: Some(1),
+            limit: limit.map(i64::abs),
+            collation: collation,
+            ..FindOptions::new()
+        };
+
+        let mut cursor = Cursor::query_with_selection(
+            &mut stream,
+            slave_ok,
+            send_read_pref,
+            client,
+            format!("{}.$cmd", db_name),
+            OpQueryFlags::empty(),
+            command,
+            cmd_options,
+            cmd_type,
+            true,
+            read_pref,
+        )?;
+
+        cursor.batch_size = batch_size;
+        cursor.tailable = tailable;
+        cursor.max_await_time_ms = max_await_time_ms;
+
+        // The remaining batches are requested with an exhaustAllowed `getMore` on this stream.
+        if exhaust && cursor.cursor_id != 0 {
+            cursor.exhaust_stream = Some(stream);
+        }
+
+        Ok(cursor)
+    }
+
+    // Builds the `find` command for a query on the named collection.
+    pub(crate) fn find_command(
+        coll_name: String,
+        filter: bson::Document,
+        options: FindOptions,
+    ) -> bson::Document {
+        // A negative limit requests a single batch of at most that many documents.
+        let mut find_options = options;
+        let single_batch = match find_options.limit {
+            Some(limit) if limit < 0 => {
+                find_options.limit = Some(-limit);
+                true
+            }
+            _ => false,
+        };
+
+        let mut command = merge_options(
+            doc! {
+                "find": coll_name,
+                "filter": filter,
+            },
+            find_options,
+        );
+
+        if single_batch {
+            command.insert("singleBatch", true);
+        }
+
+        command
+    }
+
+    // Executes a query on a stream that was selected for the provided read preference.
+    fn query_with_selection(
+        stream: &mut PooledStream,
+        slave_ok: bool,
+        send_read_pref: bool,
+        client: Client,
+        namespace: String,
+        flags: OpQueryFlags,
+        query: bson::Document,
+        options: FindOptions,
+        cmd_type: CommandType,
+        is_cmd_cursor: bool,
+        read_pref: ReadPreference,
+    ) -> Result<Cursor> {
+
+        // Collation was introduced in MongoDB 3.4 (wire version 5).
+        if stream.max_wire_version < 5 && options.collation.is_some() {
+            return Err(Error::ArgumentError(format!(
+                "Collation is not supported by the selected server (max wire version {}); \
+                 MongoDB 3.4 or newer is required.",
+                stream.max_wire_version
+            )));
+        }
+
         // Set slave_ok flag based on the result from server selection.
         let new_flags = if slave_ok {
             flags | OpQueryFlags::SLAVE_OK
@@ -249,7 +535,7 @@ impl Cursor {
         };
 
         Cursor::query_with_stream(
-            &mut stream,
+            stream,
             client,
             namespace,
             new_flags,
@@ -257,7 +543,6 @@ impl Cursor {
             options,
             cmd_type,
             is_cmd_cursor,
-            Some(read_pref),
         )
     }
 
@@ -270,15 +555,14 @@ impl Cursor {
         options: FindOptions,
         cmd_type: CommandType,
         is_cmd_cursor: bool,
-        read_pref: Option<ReadPreference>,
     ) -> Result<Cursor> {
 
+        let host = stream.host().clone();
+        let pool_iteration = stream.iteration();
         let socket = stream.get_socket();
         let req_id = client.get_req_id();
 
-        let index = namespace.find('.').unwrap_or_else(|| namespace.len());
-        let db_name = String::from(&namespace[..index]);
-        let coll_name = String::from(&namespace[index + 1..]);
+        let (db_name, coll_name) = split_namespace(&namespace)?;
         let cmd_name = cmd_type.to_str();
         let connstring = socket.get_ref().peer_addr()?.to_string();
 
@@ -287,8 +571,9 @@ impl Cursor {
             _ => query.clone(),
         };
 
+        // Legacy finds are reported to listeners as the equivalent `find` command.
         let command = match cmd_type {
-            CommandType::Find => {
+            CommandType::Find if !is_cmd_cursor => {
                 let document = doc! {
                     "find": coll_name,
                     "filter": filter
@@ -337,7 +622,7 @@ impl Cursor {
             cmd_name,
             req_id,
             connstring,
-            Message::read(socket),
+            stream.read_reply(),
             client
         );
 
@@ -349,7 +634,12 @@ impl Cursor {
                 cmd_name,
                 req_id,
                 connstring,
-                Cursor::get_bson_and_cursor_info_from_command_message(reply),
+                Cursor::get_bson_and_cursor_info_from_command_message(
+                    reply,
+                    &namespace,
+                    cmd_type == CommandType::ListCollections ||
+                        cmd_type == CommandType::ListIndexes,
+                ),
                 client
             )
         } else {
@@ -365,7 +655,7 @@ impl Cursor {
         };
 
         let reply = match cmd_type {
-            CommandType::Find => doc! {
+            CommandType::Find if !is_cmd_cursor => doc! {
                 "cursor": {
                     "id": cursor_id,
                     "ns": &namespace,
@@ -386,9 +676,6 @@ impl Cursor {
             });
         }
 
-        let read_preference =
-            read_pref.unwrap_or_else(|| ReadPreference::new(ReadMode::Primary, None));
-
         // Check if actual batch size fits into an `i32`.
         if size_of::<i32>() <= size_of::<usize>() && buf.len() > i32::MAX as usize {
             return Err(Error::DefaultError(
@@ -404,13 +691,29 @@ impl Cursor {
             limit: options.limit.unwrap_or(0) as i32,
             count: 0,
             buffer: buf,
-            read_preference: read_preference,
+            host: host,
+            pool_iteration: pool_iteration,
+            slave_ok: flags.contains(OpQueryFlags::SLAVE_OK),
+            tailable: false,
+            max_await_time_ms: None,
+            exhaust_stream: None,
+            more_to_come: false,
             cmd_type: cmd_type.clone(),
         })
     }
 
     fn get_from_stream(&mut self) -> Result<()> {
-        let (mut stream, _, _) = self.client.acquire_stream(self.read_preference.to_owned())?;
+        if self.exhaust_stream.is_some() {
+            return self.read_exhaust_batch();
+        }
+
+        let mut stream = self.client.acquire_stream_for_host(&self.host, self.pool_iteration)?;
+        let slave_ok = self.slave_ok;
+
+        if stream.max_wire_version >= FIND_COMMAND_WIRE_VERSION {
+            return self.get_more_command(&mut stream, slave_ok);
+        }
+
         let socket = stream.get_socket();
 
         let req_id = self.client.get_req_id();
@@ -450,13 +753,269 @@ impl Cursor {
             get_more.write(socket.get_mut()),
             self.client
         );
-        let reply = Message::read(socket.get_mut())?;
+        let reply = stream.read_reply()?;
 
-        let (_, v, _) = Cursor::get_bson_and_cid_from_message(reply)?;
+        let (_, v, cursor_id) = Cursor::get_bson_and_cid_from_message(reply)?;
+        self.cursor_id = cursor_id;
         self.buffer.extend(v);
         Ok(())
     }
 
+    // Reads the next batch of an exhaust cursor off its stream.
+    fn read_exhaust_batch(&mut self) -> Result<()> {
+        let mut stream = match self.exhaust_stream.take() {
+            Some(stream) => stream,
+            None => return Ok(()),
+        };
+
+        if let Err(err) = self.read_exhaust_reply(&mut stream) {
+            // The socket may still hold part of the streamed replies.
+            stream.discard();
+            self.more_to_come = false;
+            return Err(err);
+        }
+
+        // Once the final batch has been read, the socket can be returned to the pool.
+        if self.cursor_id != 0 {
+            self.exhaust_stream = Some(stream);
+        }
+
+        Ok(())
+    }
+
+    // Asks for the next batch with an exhaustAllowed `getMore` unless the server is already
+    // streaming them, and then reads it.
+    fn read_exhaust_reply(&mut self, stream: &mut PooledStream) -> Result<()> {
+        if !self.more_to_come {
+            let (db_name, coll_name) = split_namespace(&self.namespace)?;
+
+            let mut command = doc! {
+                "getMore": self.cursor_id,
+                "collection": coll_name,
+                "$db": db_name,
+            };
+
+            if self.batch_size > 0 {
+                command.insert("batchSize", self.batch_size);
+            }
+
+            let req_id = self.client.get_req_id();
+            let message = Message::new_msg(req_id, OpMsgFlags::EXHAUST_ALLOWED, command)?;
+            message.write(stream.get_socket().get_mut())?;
+        }
+
+        let reply = stream.read_reply()?;
+
+        let more_to_come = match reply {
+            Message::OpMsg { ref flags, .. } => Some(flags.contains(OpMsgFlags::MORE_TO_COME)),
+            _ => None,
+        };
+
+        let (v, cursor_id) = match more_to_come {
+            Some(more_to_come) => {
+                self.more_to_come = more_to_come;
+                let (_, v, cursor_id, _) = Cursor::get_bson_and_cursor_info_from_command_message(
+                    reply,
+                    &self.namespace,
+                    false,
+                )?;
+                (v, cursor_id)
+            }
+            None => {
+                let (_, v, cursor_id) = Cursor::get_bson_and_cid_from_message(reply)?;
+                (v, cursor_id)
+            }
+        };
+
+        self.cursor_id = cursor_id;
+        self.buffer.extend(v);
+        Ok(())
+    }
+
+    // Retrieves the next batch of documents with a `getMore` command.
+    fn get_more_command(&mut self, stream: &mut PooledStream, slave_ok: bool) -> Result<()> {
+        let (db_name, coll_name) = split_namespace(&self.namespace)?;
+
+        let mut command = doc! {
+            "getMore": self.cursor_id,
+            "collection": coll_name,
+        };
+
+        if self.batch_size > 0 {
+            command.insert("batchSize", self.batch_size);
+        }
+
+        if let Some(max_await_time_ms) = self.max_await_time_ms {
+            command.insert("maxTimeMS", max_await_time_ms);
+        }
+
+        let flags = if slave_ok {
+            OpQueryFlags::SLAVE_OK
+        } else {
+            OpQueryFlags::empty()
+        };
+
+        let socket = stream.get_socket();
+        let req_id = self.client.get_req_id();
+        let cmd_name = String::from("getMore");
+        let connstring = socket.get_ref().peer_addr()?.to_string();
+
+        let message = Message::new_query(
+            req_id,
+            flags,
+            format!("{}.$cmd", db_name),
+            0,
+            1,
+            command.clone(),
+            None,
+        )?;
+
+        if self.cmd_type != CommandType::Suppressed {
+            let hook_result = self.client.run_start_hooks(&CommandStarted {
+                command: command,
+                database_name: db_name,
+                command_name: cmd_name.clone(),
+                request_id: req_id as i64,
+                connection_string: connstring.clone(),
+            });
+
+            if hook_result.is_err() {
+                return Err(Error::EventListenerError(None));
+            }
+        }
+
+        let init_time = time::precise_time_ns();
+
+        try_or_emit!(
+            self.cmd_type,
+            cmd_name,
+            req_id,
+            connstring,
+            message.write(socket.get_mut()),
+            self.client
+        );
+        let reply = try_or_emit!(
+            self.cmd_type,
+            cmd_name,
+            req_id,
+            connstring,
+            stream.read_reply(),
+            self.client
+        );
+        let (doc, buf, cursor_id, _) = try_or_emit!(
+            self.cmd_type,
+            cmd_name,
+            req_id,
+            connstring,
+            Cursor::get_bson_and_cursor_info_from_command_message(reply, &self.namespace, false),
+            self.client
+        );
+
+        let fin_time = time::precise_time_ns();
+
+        if self.cmd_type != CommandType::Suppressed {
+            let _hook_result = self.client.run_completion_hooks(&CommandResult::Success {
+                duration: fin_time - init_time,
+                reply: doc,
+                command_name: cmd_name,
+                request_id: req_id as i64,
+                connection_string: connstring,
+            });
+        }
+
+        self.cursor_id = cursor_id;
+        self.buffer.extend(buf);
+        Ok(())
+    }
+
+    /// Closes the cursor on the server, with a `killCursors` command if the server supports it or
+    /// a legacy OP_KILL_CURSORS otherwise.
+    ///
+    /// Dropping a cursor that has not been exhausted only makes a best-effort attempt to close
+    /// it without waiting on the network; call `kill` to close it reliably and observe failures.
+    pub fn kill(&mut self) -> Result<()> {
+        // A socket that the server is still streaming batches to can't be reused.
+        if let Some(mut stream) = self.exhaust_stream.take() {
+            if self.more_to_come {
+                stream.discard();
+                self.more_to_come = false;
+            }
+        }
+
+        if self.cursor_id == 0 {
+            return Ok(());
+        }
+
+        let mut stream = self.client.acquire_stream_for_host(&self.host, self.pool_iteration)?;
+        let message = self.kill_cursors_message(stream.max_wire_version)?;
+        message.write(stream.get_socket().get_mut())?;
+        self.cursor_id = 0;
+
+        // The server does not reply to OP_KILL_CURSORS.
+        if stream.max_wire_version < FIND_COMMAND_WIRE_VERSION {
+            return Ok(());
+        }
+
+        let reply = stream.read_reply()?;
+        if let Message::OpReply { ref documents, .. } = reply {
+            if let Some(reply) = documents.get(0) {
+                Cursor::check_command_reply(reply)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Closes the cursor on the server without blocking, using an idle connection to its server
+    // if one is available. The reply to `killCursors` is not read, so the socket is closed
+    // afterwards rather than returned to the pool; if no idle connection is available, the
+    // server times the cursor out on its own.
+    fn kill_without_reply(&mut self) -> Result<()> {
+        let mut stream = match self.client.topology.try_acquire_idle_stream_for_host(
+            &self.host,
+            self.pool_iteration,
+        ) {
+            Some(stream) => stream,
+            None => return Ok(()),
+        };
+
+        let message = self.kill_cursors_message(stream.max_wire_version)?;
+        if stream.max_wire_version >= FIND_COMMAND_WIRE_VERSION {
+            stream.discard();
+        }
+
+        message.write(stream.get_socket().get_mut())
+    }
+
+    fn kill_cursors_message(&self, max_wire_version: i64) -> Result<Message> {
+        let req_id = self.client.get_req_id();
+
+        if max_wire_version < FIND_COMMAND_WIRE_VERSION {
+            return Ok(Message::new_kill_cursors(req_id, vec![self.cursor_id]));
+        }
+
+        let (db_name, coll_name) = split_namespace(&self.namespace)?;
+
+        let flags = if self.slave_ok {
+            OpQueryFlags::SLAVE_OK
+        } else {
+            OpQueryFlags::empty()
+        };
+
+        Message::new_query(
+            req_id,
+            flags,
+            format!("{}.$cmd", db_name),
+            0,
+            1,
+            doc! {
+                "killCursors": coll_name,
+                "cursors": [self.cursor_id],
+            },
+            None,
+        )
+    }
+
     /// Attempts to read a specified number of BSON documents from the cursor.
     ///
     /// # Arguments
@@ -532,6 +1091,23 @@ impl Cursor {
     }
 }
 
+impl Drop for Cursor {
+    fn drop(&mut self) {
+        // The socket of an exhaust cursor may still hold unread batches, so it can't be reused.
+        if let Some(mut stream) = self.exhaust_stream.take() {
+            if self.more_to_come {
+                stream.discard();
+            }
+        }
+
+        // Cursors that have been exhausted are already closed on the server. Errors can't be
+        // reported from a drop; callers that need them use `kill`.
+        if self.cursor_id != 0 {
+            let _ = self.kill_without_reply();
+        }
+    }
+}
+
 impl Iterator for Cursor {
     type Item = Result<bson::Document>;
 
@@ -553,3 +1129,78 @@ impl Iterator for Cursor {
         }
     }
 }
+
+/// A cursor on a capped collection that remains open after its last document has been returned,
+/// so that documents inserted later can be read as they arrive.
+///
+/// Unlike the `Iterator` implementation of `Cursor`, which ends as soon as a batch comes back
+/// empty, `try_next` distinguishes between a live cursor that has no new documents yet and a
+/// cursor that has been closed by the server.
+#[derive(Debug)]
+pub struct TailableCursor {
+    cursor: Cursor,
+}
+
+impl TailableCursor {
+    /// Wraps a cursor that was opened with a `Tailable` or `TailableAwait` cursor type.
+    pub fn new(cursor: Cursor) -> Result<TailableCursor> {
+        if !cursor.tailable {
+            return Err(Error::ArgumentError(String::from(
+                "A TailableCursor requires a cursor opened with a tailable cursor type.",
+            )));
+        }
+
+        Ok(TailableCursor { cursor: cursor })
+    }
+
+    /// Checks whether the cursor can still return documents, either from its current batch or
+    /// from the server.
+    pub fn is_alive(&self) -> bool {
+        self.cursor.cursor_id != 0 || !self.cursor.buffer.is_empty()
+    }
+
+    /// Attempts to read the next document from the cursor, fetching a new batch from the server
+    /// if the current one has been exhausted. For `TailableAwait` cursors, the server waits up to
+    /// `max_await_time_ms` for new documents before replying.
+    ///
+    /// # Return value
+    ///
+    /// Returns the next document if one is available; `None` if the cursor is still open but no
+    /// new documents have arrived yet; or `CursorNotFoundError` once the server has closed the
+    /// cursor, after which it must be re-created to resume tailing.
+    pub fn try_next(&mut self) -> Result<Option<bson::Document>> {
+        if self.cursor.buffer.is_empty() {
+            if self.cursor.cursor_id == 0 {
+                return Err(Error::CursorNotFoundError);
+            }
+
+            self.cursor.get_from_stream()?;
+
+            if self.cursor.buffer.is_empty() && self.cursor.cursor_id == 0 {
+                return Err(Error::CursorNotFoundError);
+            }
+        }
+
+        let next = self.cursor.buffer.pop_front();
+        if next.is_some() {
+            self.cursor.count += 1;
+        }
+
+        Ok(next)
+    }
+}
+
+// Splits a namespace into its database and collection names.
+fn split_namespace(namespace: &str) -> Result<(String, String)> {
+    let mut parts = namespace.splitn(2, '.');
+
+    match (parts.next(), parts.next()) {
+        (Some(db_name), Some(coll_name)) if !db_name.is_empty() => {
+            Ok((String::from(db_name), String::from(coll_name)))
+        }
+        _ => Err(Error::ArgumentError(format!(
+            "'{}' is not a valid namespace of the form <database>.<collection>.",
+            namespace
+        ))),
+    }
+}
@@ -32,6 +32,26 @@
 //! let success = db.list_collections(None).unwrap();
 //! ```
 //!
+//! ## Users and Roles
+//!
+//! ```no_run
+//! # use mongodb::{Client, ThreadedClient};
+//! # use mongodb::db::ThreadedDatabase;
+//! # use mongodb::db::options::CreateRoleOptions;
+//! # use mongodb::db::roles::{Privilege, Resource, Role};
+//! # let client = Client::connect("localhost", 27017).unwrap();
+//! #
+//! let db = client.db("shop");
+//!
+//! let mut options = CreateRoleOptions::new();
+//! options.privileges.push(Privilege::new(Resource::collection("shop", "orders"), vec!["find"]));
+//! db.create_role("orderReader", Some(options)).unwrap();
+//!
+//! let role = Role::Custom { role: String::from("orderReader"), db: String::from("shop") };
+//! db.create_user("reporting", "1234", None).unwrap();
+//! db.grant_roles_to_user("reporting", vec![role], None).unwrap();
+//! ```
+//!
 //! ## Arbitrary Database Commands
 //!
 //! Any valid MongoDB database command can be sent to the server with the `command` and
@@ -56,6 +76,7 @@
 //! # }
 //! ```
 pub mod options;
+pub mod results;
 pub mod roles;
 
 use auth::Authenticator;
@@ -63,11 +84,16 @@ use bson::{self, bson, doc, Bson};
 use {Client, CommandType, ThreadedClient, Result};
 use Error::{CursorNotFoundError, OperationError, ResponseError};
 use coll::Collection;
-use coll::options::FindOptions;
-use common::{ReadPreference, merge_options, WriteConcern};
+use coll::options::{AggregateOptions, Collation, CursorType, FindOptions};
+use common::{ReadConcern, ReadPreference, merge_options, WriteConcern};
 use cursor::{Cursor, DEFAULT_BATCH_SIZE};
-use self::options::{CreateCollectionOptions, CreateUserOptions, UserInfoOptions};
+use self::options::{CreateCollectionOptions, CreateRoleOptions, CreateUserOptions,
+                    ProfilingLevel, RoleInfoOptions, UpdateRoleOptions, UpdateUserOptions,
+                    UserInfoOptions, privileges_to_bson, roles_to_bson};
+use self::roles::{Privilege, Role};
+use self::results::{CollectionSpecification, DbStats, ProfileEntries, ProfilingStatus};
 use semver::Version;
+use wire_protocol::flags::OpQueryFlags;
 use std::error::Error;
 use std::sync::Arc;
 
@@ -83,10 +109,149 @@ pub struct DatabaseInner {
     /// Describes the guarantees provided by MongoDB when reporting the success of a write
     /// operation.
     pub write_concern: WriteConcern,
+    /// Describes the consistency and isolation of data returned by read operations.
+    pub read_concern: Option<ReadConcern>,
 }
 
 pub type Database = Arc<DatabaseInner>;
 
+impl DatabaseInner {
+    // Runs an aggregation against `target`, which is either a collection name or 1 for a
+    // database-level pipeline. Pipelines that end in `$out` or `$merge` are sent to the primary
+    // with a write concern, and explained pipelines return their plan as the only document.
+    pub(crate) fn aggregate_target(
+        &self,
+        target: Bson,
+        pipeline: Vec<bson::Document>,
+        options: Option<AggregateOptions>,
+        read_preference: ReadPreference,
+        read_concern: Option<ReadConcern>,
+        write_concern: WriteConcern,
+    ) -> Result<Cursor> {
+        let has_write_stage = pipeline.last().map_or(false, |stage| {
+            stage.contains_key("$out") || stage.contains_key("$merge")
+        });
+
+        let pipeline: Vec<_> = pipeline.into_iter().map(Bson::Document).collect();
+
+        let mut spec = doc! {
+            "aggregate": target,
+            "pipeline": pipeline
+        };
+
+        let mut read_preference = read_preference;
+        let mut read_concern = read_concern;
+        let mut write_concern = write_concern;
+        let mut explain = false;
+        let mut collation = None;
+
+        match options {
+            Some(aggregate_options) => {
+                if let Some(ref read_preference_option) = aggregate_options.read_preference {
+                    read_preference = read_preference_option.clone();
+                }
+
+                if aggregate_options.read_concern.is_some() {
+                    read_concern = aggregate_options.read_concern;
+                }
+
+                if let Some(ref write_concern_option) = aggregate_options.write_concern {
+                    write_concern = write_concern_option.clone();
+                }
+
+                explain = aggregate_options.explain == Some(true);
+                collation = aggregate_options.collation.clone();
+                spec = merge_options(spec, aggregate_options);
+            }
+            None => {
+                spec.insert("cursor", bson::Document::new());
+            }
+        };
+
+        if let Some(read_concern) = read_concern {
+            spec.insert("readConcern", read_concern.to_bson());
+        }
+
+        let cmd_type = if has_write_stage {
+            CommandType::AggregateWrite
+        } else {
+            CommandType::Aggregate
+        };
+
+        if explain {
+            let options = FindOptions {
+                batch_size: Some(1),
+                limit: Some(1),
+                collation: collation,
+                ..FindOptions::new()
+            };
+
+            return Cursor::query(
+                self.client.clone(),
+                format!("{}.$cmd", self.name),
+                OpQueryFlags::with_find_options(&options),
+                spec,
+                options,
+                cmd_type,
+                false,
+                read_preference,
+            );
+        }
+
+        if has_write_stage {
+            spec.insert("writeConcern", write_concern.to_bson());
+        }
+
+        Cursor::command_cursor_with_collation(
+            self.client.clone(),
+            &self.name[..],
+            spec,
+            cmd_type,
+            read_preference,
+            collation,
+        )
+    }
+
+    // Runs a command that carries the given collation, so that servers without collation
+    // support reject it before it is sent.
+    pub(crate) fn command_with_collation(
+        &self,
+        spec: bson::Document,
+        cmd_type: CommandType,
+        read_preference: Option<ReadPreference>,
+        collation: Option<Collation>,
+    ) -> Result<bson::Document> {
+        let options = FindOptions {
+            batch_size: Some(1),
+            limit: Some(1),
+            collation: collation,
+            ..FindOptions::new()
+        };
+
+        let read_preference = read_preference.unwrap_or_else(|| self.read_preference.clone());
+        let flags = OpQueryFlags::with_find_options(&options);
+
+        let mut cursor = Cursor::query(
+            self.client.clone(),
+            format!("{}.$cmd", self.name),
+            flags,
+            spec.clone(),
+            options,
+            cmd_type,
+            false,
+            read_preference,
+        )?;
+
+        match cursor.next() {
+            Some(Ok(doc)) => Ok(doc),
+            Some(Err(err)) => Err(err),
+            None => Err(OperationError(
+                format!("Failed to execute command with spec {:?}.", spec),
+            )),
+        }
+    }
+}
+
 pub trait ThreadedDatabase {
     /// Creates a database representation with optional read and write controls.
     fn open(
@@ -95,6 +260,15 @@ pub trait ThreadedDatabase {
         read_preference: Option<ReadPreference>,
         write_concern: Option<WriteConcern>,
     ) -> Database;
+    /// Creates a database representation with optional read and write controls and read
+    /// concern.
+    fn open_with_read_concern(
+        client: Client,
+        name: &str,
+        read_preference: Option<ReadPreference>,
+        write_concern: Option<WriteConcern>,
+        read_concern: Option<ReadConcern>,
+    ) -> Database;
     // Returns the version of the MongoDB instance.
     fn version(&self) -> Result<Version>;
     /// Logs in a user using the SCRAM-SHA-1 mechanism.
@@ -109,8 +283,25 @@ pub trait ThreadedDatabase {
         read_preference: Option<ReadPreference>,
         write_concern: Option<WriteConcern>,
     ) -> Collection;
+    /// Creates a collection representation with custom read and write controls and read
+    /// concern.
+    fn collection_with_read_concern(
+        &self,
+        coll_name: &str,
+        create: bool,
+        read_preference: Option<ReadPreference>,
+        write_concern: Option<WriteConcern>,
+        read_concern: Option<ReadConcern>,
+    ) -> Collection;
     /// Return a unique operational request id.
     fn get_req_id(&self) -> i32;
+    /// Runs a database-level aggregation pipeline, such as one beginning with `$currentOp` or
+    /// `$listLocalSessions`.
+    fn aggregate<P: Into<Vec<bson::Document>>>(
+        &self,
+        pipeline: P,
+        options: Option<AggregateOptions>,
+    ) -> Result<Cursor>;
     /// Generates a cursor for a relevant operational command.
     fn command_cursor(
         &self,
@@ -135,12 +326,45 @@ pub trait ThreadedDatabase {
     ) -> Result<Cursor>;
     /// Returns a list of collection names within the database.
     fn collection_names(&self, filter: Option<bson::Document>) -> Result<Vec<String>>;
+    /// Returns storage statistics for the database, with sizes divided by `scale` if given.
+    fn stats(&self, scale: Option<i32>) -> Result<DbStats>;
+    /// Sets which operations the database profiler records, returning the previous settings.
+    ///
+    /// `slow_ms` and `sample_rate` select the slow operations to record, and `filter` replaces
+    /// them with a query on the profiled operation, which requires MongoDB 4.4.2 or newer.
+    fn set_profiling_level(
+        &self,
+        level: ProfilingLevel,
+        slow_ms: Option<i32>,
+        sample_rate: Option<f64>,
+        filter: Option<bson::Document>,
+    ) -> Result<ProfilingStatus>;
+    /// Returns the current settings of the database profiler.
+    fn get_profiling_status(&self) -> Result<ProfilingStatus>;
+    /// Returns the operations recorded by the database profiler that match the filter.
+    ///
+    /// `system.profile` is read with a tailable cursor, so iteration ends once the recorded
+    /// operations have been consumed rather than when the cursor is closed.
+    fn profile_entries(&self, filter: Option<bson::Document>) -> Result<ProfileEntries>;
+    /// Returns the specifications of the collections and views within the database.
+    fn list_collection_specifications(
+        &self,
+        filter: Option<bson::Document>,
+    ) -> Result<Vec<CollectionSpecification>>;
     /// Creates a new collection.
     ///
     /// Note that due to the implicit creation of collections during insertion, this
-    /// method should only be used to instantiate capped collections.
+    /// method should only be used to instantiate collections with non-default options.
     fn create_collection(&self, name: &str, options: Option<CreateCollectionOptions>)
         -> Result<()>;
+    /// Creates a read-only view of the results of a pipeline run on another collection or view.
+    fn create_view<P: Into<Vec<bson::Document>>>(
+        &self,
+        name: &str,
+        view_on: &str,
+        pipeline: P,
+        collation: Option<Collation>,
+    ) -> Result<()>;
     /// Creates a new user.
     fn create_user(
         &self,
@@ -166,6 +390,53 @@ pub trait ThreadedDatabase {
         users: Vec<&str>,
         options: Option<UserInfoOptions>,
     ) -> Result<Vec<bson::Document>>;
+    /// Modifies an existing user.
+    fn update_user(&self, name: &str, options: UpdateUserOptions) -> Result<()>;
+    /// Sets a new password for an existing user.
+    fn change_user_password(
+        &self,
+        name: &str,
+        password: &str,
+        write_concern: Option<WriteConcern>,
+    ) -> Result<()>;
+    /// Grants additional roles to an existing user.
+    fn grant_roles_to_user(
+        &self,
+        name: &str,
+        roles: Vec<Role>,
+        write_concern: Option<WriteConcern>,
+    ) -> Result<()>;
+    /// Removes roles from an existing user.
+    fn revoke_roles_from_user(
+        &self,
+        name: &str,
+        roles: Vec<Role>,
+        write_concern: Option<WriteConcern>,
+    ) -> Result<()>;
+    /// Creates a new user-defined role.
+    fn create_role(&self, name: &str, options: Option<CreateRoleOptions>) -> Result<()>;
+    /// Modifies an existing user-defined role.
+    fn update_role(&self, name: &str, options: UpdateRoleOptions) -> Result<()>;
+    /// Permanently deletes the user-defined role from the database.
+    fn drop_role(&self, name: &str, write_concern: Option<WriteConcern>) -> Result<()>;
+    /// Grants additional privileges to an existing user-defined role.
+    fn grant_privileges_to_role(
+        &self,
+        name: &str,
+        privileges: Vec<Privilege>,
+        write_concern: Option<WriteConcern>,
+    ) -> Result<()>;
+    /// Removes privileges from an existing user-defined role.
+    fn revoke_privileges_from_role(
+        &self,
+        name: &str,
+        privileges: Vec<Privilege>,
+        write_concern: Option<WriteConcern>,
+    ) -> Result<()>;
+    /// Retrieves information about a given role from the database.
+    fn get_role(&self, name: &str, options: Option<RoleInfoOptions>) -> Result<bson::Document>;
+    /// Retrieves information about all roles in the database.
+    fn get_all_roles(&self, options: Option<RoleInfoOptions>) -> Result<Vec<bson::Document>>;
 }
 
 impl ThreadedDatabase for Database {
@@ -174,15 +445,27 @@ impl ThreadedDatabase for Database {
         name: &str,
         read_preference: Option<ReadPreference>,
         write_concern: Option<WriteConcern>,
+    ) -> Database {
+        Database::open_with_read_concern(client, name, read_preference, write_concern, None)
+    }
+
+    fn open_with_read_concern(
+        client: Client,
+        name: &str,
+        read_preference: Option<ReadPreference>,
+        write_concern: Option<WriteConcern>,
+        read_concern: Option<ReadConcern>,
     ) -> Database {
         let rp = read_preference.unwrap_or_else(|| client.read_preference.to_owned());
         let wc = write_concern.unwrap_or_else(|| client.write_concern.to_owned());
+        let rc = read_concern.or_else(|| client.read_concern.to_owned());
 
         Arc::new(DatabaseInner {
             name: String::from(name),
             client: client,
             read_preference: rp,
             write_concern: wc,
+            read_concern: rc,
         })
     }
 
@@ -217,10 +500,43 @@ impl ThreadedDatabase for Database {
         )
     }
 
+    fn collection_with_read_concern(
+        &self,
+        coll_name: &str,
+        create: bool,
+        read_preference: Option<ReadPreference>,
+        write_concern: Option<WriteConcern>,
+        read_concern: Option<ReadConcern>,
+    ) -> Collection {
+        Collection::new_with_read_concern(
+            self.clone(),
+            coll_name,
+            create,
+            read_preference,
+            write_concern,
+            read_concern,
+        )
+    }
+
     fn get_req_id(&self) -> i32 {
         self.client.get_req_id()
     }
 
+    fn aggregate<P: Into<Vec<bson::Document>>>(
+        &self,
+        pipeline: P,
+        options: Option<AggregateOptions>,
+    ) -> Result<Cursor> {
+        self.aggregate_target(
+            Bson::I32(1),
+            pipeline.into(),
+            options,
+            self.read_preference.clone(),
+            self.read_concern,
+            self.write_concern.clone(),
+        )
+    }
+
     fn command_cursor(
         &self,
         spec: bson::Document,
@@ -242,21 +558,7 @@ impl ThreadedDatabase for Database {
         cmd_type: CommandType,
         read_preference: Option<ReadPreference>,
     ) -> Result<bson::Document> {
-
-        let coll = self.collection("$cmd");
-        let options = FindOptions {
-            batch_size: Some(1),
-            read_preference: read_preference,
-            ..FindOptions::new()
-        };
-        let res = coll.find_one_with_command_type(
-            Some(spec.clone()),
-            Some(options),
-            cmd_type,
-        )?;
-        res.ok_or_else(|| {
-            OperationError(format!("Failed to execute command with spec {:?}.", spec))
-        })
+        self.command_with_collation(spec, cmd_type, read_preference, None)
     }
 
     fn list_collections(&self, filter: Option<bson::Document>) -> Result<Cursor> {
@@ -298,6 +600,61 @@ impl ThreadedDatabase for Database {
             .collect()
     }
 
+    fn list_collection_specifications(
+        &self,
+        filter: Option<bson::Document>,
+    ) -> Result<Vec<CollectionSpecification>> {
+        self.list_collections(filter)?
+            .map(|result| result.and_then(CollectionSpecification::new))
+            .collect()
+    }
+
+    fn stats(&self, scale: Option<i32>) -> Result<DbStats> {
+        let mut spec = doc! { "dbStats": 1 };
+
+        if let Some(scale) = scale {
+            spec.insert("scale", scale);
+        }
+
+        self.command(spec, CommandType::DbStats, None).map(DbStats::new)
+    }
+
+    fn set_profiling_level(
+        &self,
+        level: ProfilingLevel,
+        slow_ms: Option<i32>,
+        sample_rate: Option<f64>,
+        filter: Option<bson::Document>,
+    ) -> Result<ProfilingStatus> {
+        let mut spec = doc! { "profile": level.as_i32() };
+
+        if let Some(slow_ms) = slow_ms {
+            spec.insert("slowms", slow_ms);
+        }
+
+        if let Some(sample_rate) = sample_rate {
+            spec.insert("sampleRate", sample_rate);
+        }
+
+        if let Some(filter) = filter {
+            spec.insert("filter", filter);
+        }
+
+        let reply = self.command(spec, CommandType::Profile, None)?;
+        ProfilingStatus::new(&reply)
+    }
+
+    fn get_profiling_status(&self) -> Result<ProfilingStatus> {
+        let reply = self.command(doc! { "profile": -1 }, CommandType::Profile, None)?;
+        ProfilingStatus::new(&reply)
+    }
+
+    fn profile_entries(&self, filter: Option<bson::Document>) -> Result<ProfileEntries> {
+        let options = FindOptions { cursor_type: CursorType::Tailable, ..FindOptions::new() };
+        let cursor = self.collection("system.profile").find(filter, Some(options))?;
+        Ok(ProfileEntries::new(cursor))
+    }
+
     fn version(&self) -> Result<Version> {
         let doc = doc! { "buildinfo": 1 };
         let out = self.command(doc, CommandType::BuildInfo, None)?;
@@ -321,16 +678,35 @@ impl ThreadedDatabase for Database {
         options: Option<CreateCollectionOptions>,
     ) -> Result<()> {
         let mut doc = doc! { "create": name };
+        let mut collation = None;
 
         if let Some(create_collection_options) = options {
+            collation = create_collection_options.collation.clone();
             doc = merge_options(doc, create_collection_options);
         }
 
-        self.command(doc, CommandType::CreateCollection, None)?;
+        self.command_with_collation(doc, CommandType::CreateCollection, None, collation)?;
 
         Ok(())
     }
 
+    fn create_view<P: Into<Vec<bson::Document>>>(
+        &self,
+        name: &str,
+        view_on: &str,
+        pipeline: P,
+        collation: Option<Collation>,
+    ) -> Result<()> {
+        let options = CreateCollectionOptions {
+            view_on: Some(String::from(view_on)),
+            pipeline: Some(pipeline.into()),
+            collation: collation,
+            ..CreateCollectionOptions::new()
+        };
+
+        self.create_collection(name, Some(options))
+    }
+
     fn create_user(
         &self,
         name: &str,
@@ -347,7 +723,7 @@ impl ThreadedDatabase for Database {
                 doc = merge_options(doc, user_options);
             }
             None => {
-                doc.insert("roles", Vec::new());
+                doc.insert("roles", Vec::<bson::Bson>::new());
             }
         };
 
@@ -467,4 +843,162 @@ impl ThreadedDatabase for Database {
             })
             .collect()
     }
+
+    fn update_user(&self, name: &str, options: UpdateUserOptions) -> Result<()> {
+        let doc = merge_options(doc! { "updateUser": name }, options);
+        self.command(doc, CommandType::UpdateUser, None).map(drop)
+    }
+
+    fn change_user_password(
+        &self,
+        name: &str,
+        password: &str,
+        write_concern: Option<WriteConcern>,
+    ) -> Result<()> {
+        let options = UpdateUserOptions {
+            password: Some(String::from(password)),
+            write_concern: write_concern,
+            ..UpdateUserOptions::new()
+        };
+
+        self.update_user(name, options)
+    }
+
+    fn grant_roles_to_user(
+        &self,
+        name: &str,
+        roles: Vec<Role>,
+        write_concern: Option<WriteConcern>,
+    ) -> Result<()> {
+        let mut doc = doc! {
+            "grantRolesToUser": name,
+            "roles": roles_to_bson(&roles),
+        };
+
+        if let Some(concern) = write_concern {
+            doc.insert("writeConcern", concern.to_bson());
+        }
+
+        self.command(doc, CommandType::GrantRolesToUser, None).map(drop)
+    }
+
+    fn revoke_roles_from_user(
+        &self,
+        name: &str,
+        roles: Vec<Role>,
+        write_concern: Option<WriteConcern>,
+    ) -> Result<()> {
+        let mut doc = doc! {
+            "revokeRolesFromUser": name,
+            "roles": roles_to_bson(&roles),
+        };
+
+        if let Some(concern) = write_concern {
+            doc.insert("writeConcern", concern.to_bson());
+        }
+
+        self.command(doc, CommandType::RevokeRolesFromUser, None).map(drop)
+    }
+
+    fn create_role(&self, name: &str, options: Option<CreateRoleOptions>) -> Result<()> {
+        // The server requires both privileges and roles, even when they are empty.
+        let doc = merge_options(doc! { "createRole": name }, options.unwrap_or_default());
+        self.command(doc, CommandType::CreateRole, None).map(drop)
+    }
+
+    fn update_role(&self, name: &str, options: UpdateRoleOptions) -> Result<()> {
+        let doc = merge_options(doc! { "updateRole": name }, options);
+        self.command(doc, CommandType::UpdateRole, None).map(drop)
+    }
+
+    fn drop_role(&self, name: &str, write_concern: Option<WriteConcern>) -> Result<()> {
+        let mut doc = doc! { "dropRole": name };
+
+        if let Some(concern) = write_concern {
+            doc.insert("writeConcern", concern.to_bson());
+        }
+
+        self.command(doc, CommandType::DropRole, None).map(drop)
+    }
+
+    fn grant_privileges_to_role(
+        &self,
+        name: &str,
+        privileges: Vec<Privilege>,
+        write_concern: Option<WriteConcern>,
+    ) -> Result<()> {
+        let mut doc = doc! {
+            "grantPrivilegesToRole": name,
+            "privileges": privileges_to_bson(&privileges),
+        };
+
+        if let Some(concern) = write_concern {
+            doc.insert("writeConcern", concern.to_bson());
+        }
+
+        self.command(doc, CommandType::GrantPrivilegesToRole, None).map(drop)
+    }
+
+    fn revoke_privileges_from_role(
+        &self,
+        name: &str,
+        privileges: Vec<Privilege>,
+        write_concern: Option<WriteConcern>,
+    ) -> Result<()> {
+        let mut doc = doc! {
+            "revokePrivilegesFromRole": name,
+            "privileges": privileges_to_bson(&privileges),
+        };
+
+        if let Some(concern) = write_concern {
+            doc.insert("writeConcern", concern.to_bson());
+        }
+
+        self.command(doc, CommandType::RevokePrivilegesFromRole, None).map(drop)
+    }
+
+    fn get_role(&self, name: &str, options: Option<RoleInfoOptions>) -> Result<bson::Document> {
+        let mut doc = doc! {
+            "rolesInfo": {
+                "role": name,
+                "db": &self.name,
+            },
+        };
+
+        if let Some(role_info_options) = options {
+            doc = merge_options(doc, role_info_options);
+        }
+
+        let out = self.command(doc, CommandType::GetRoles, None)?;
+        let roles = match out.get("roles") {
+            Some(&Bson::Array(ref v)) => v.clone(),
+            _ => return Err(CursorNotFoundError),
+        };
+
+        match roles.first() {
+            Some(&Bson::Document(ref doc)) => Ok(doc.clone()),
+            _ => Err(CursorNotFoundError),
+        }
+    }
+
+    fn get_all_roles(&self, options: Option<RoleInfoOptions>) -> Result<Vec<bson::Document>> {
+        let mut doc = doc! { "rolesInfo": 1 };
+
+        if let Some(role_info_options) = options {
+            doc = merge_options(doc, role_info_options);
+        }
+
+        let out = self.command(doc, CommandType::GetRoles, None)?;
+        let vec = match out.get("roles") {
+            Some(&Bson::Array(ref vec)) => vec.clone(),
+            _ => return Err(CursorNotFoundError),
+        };
+
+        vec.into_iter()
+            .map(|bson| match bson {
+                Bson::Document(doc) => Ok(doc),
+                _ => Err(CursorNotFoundError),
+            })
+            .collect()
+    }
 }
@@ -1,9 +1,208 @@
 //! Options for database-level commands.
-use bson::{Bson, Document};
+use bson::{Bson, Document, bson, doc};
+use coll::options::Collation;
 use common::WriteConcern;
-use db::roles::Role;
+use db::roles::{Privilege, Role};
+use Error::{self, ArgumentError};
+use Result;
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+use std::str::FromStr;
+
+/// How strictly the server applies a collection's validator to updates of existing documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValidationLevel {
+    /// Applies no validation.
+    Off,
+    /// Validates all inserts and updates.
+    Strict,
+    /// Validates inserts and updates of documents that already satisfy the validator.
+    Moderate,
+}
+
+impl ValidationLevel {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ValidationLevel::Off => "off",
+            ValidationLevel::Strict => "strict",
+            ValidationLevel::Moderate => "moderate",
+        }
+    }
+}
+
+/// What the server does with writes that fail validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValidationAction {
+    /// Rejects the write.
+    Error,
+    /// Accepts the write and logs the violation.
+    Warn,
+}
+
+impl ValidationAction {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ValidationAction::Error => "error",
+            ValidationAction::Warn => "warn",
+        }
+    }
+}
+
+/// Which operations the database profiler records in `system.profile`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProfilingLevel {
+    Off,
+    /// Records operations slower than the `slowms` threshold or matching the profiler filter.
+    SlowOperations,
+    All,
+}
+
+impl ProfilingLevel {
+    pub fn as_i32(&self) -> i32 {
+        match *self {
+            ProfilingLevel::Off => 0,
+            ProfilingLevel::SlowOperations => 1,
+            ProfilingLevel::All => 2,
+        }
+    }
+
+    pub fn from_i32(level: i32) -> Option<ProfilingLevel> {
+        match level {
+            0 => Some(ProfilingLevel::Off),
+            1 => Some(ProfilingLevel::SlowOperations),
+            2 => Some(ProfilingLevel::All),
+            _ => None,
+        }
+    }
+}
+
+/// The expected interval between measurements in a time-series collection, which sets the span
+/// of its buckets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeseriesGranularity {
+    Seconds,
+    Minutes,
+    Hours,
+}
+
+impl TimeseriesGranularity {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            TimeseriesGranularity::Seconds => "seconds",
+            TimeseriesGranularity::Minutes => "minutes",
+            TimeseriesGranularity::Hours => "hours",
+        }
+    }
+}
+
+impl FromStr for TimeseriesGranularity {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "seconds" => TimeseriesGranularity::Seconds,
+            "minutes" => TimeseriesGranularity::Minutes,
+            "hours" => TimeseriesGranularity::Hours,
+            _ => {
+                return Err(ArgumentError(
+                    format!("Could not convert '{}' to TimeseriesGranularity.", s),
+                ))
+            }
+        })
+    }
+}
+
+/// The layout of a time-series collection. Requires MongoDB 5.0 or newer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TimeseriesOptions {
+    /// The field holding the date of each measurement.
+    pub time_field: String,
+    /// The field holding the metadata that identifies a series.
+    pub meta_field: Option<String>,
+    pub granularity: Option<TimeseriesGranularity>,
+    /// The maximum span of a bucket; requires MongoDB 6.3 or newer and replaces `granularity`.
+    pub bucket_max_span_seconds: Option<i64>,
+    /// The interval bucket start times are rounded down to; must equal
+    /// `bucket_max_span_seconds`.
+    pub bucket_rounding_seconds: Option<i64>,
+}
+
+impl TimeseriesOptions {
+    pub fn new(time_field: &str) -> TimeseriesOptions {
+        TimeseriesOptions {
+            time_field: String::from(time_field),
+            ..Default::default()
+        }
+    }
+
+    pub fn to_bson(&self) -> Document {
+        let mut document = doc! { "timeField": self.time_field.clone() };
+
+        if let Some(ref meta_field) = self.meta_field {
+            document.insert("metaField", meta_field.clone());
+        }
+
+        if let Some(granularity) = self.granularity {
+            document.insert("granularity", granularity.as_str());
+        }
+
+        if let Some(bucket_max_span_seconds) = self.bucket_max_span_seconds {
+            document.insert("bucketMaxSpanSeconds", bucket_max_span_seconds);
+        }
+
+        if let Some(bucket_rounding_seconds) = self.bucket_rounding_seconds {
+            document.insert("bucketRoundingSeconds", bucket_rounding_seconds);
+        }
+
+        document
+    }
+}
+
+/// The clustered index of a collection whose documents are stored in `_id` order. Requires
+/// MongoDB 5.3 or newer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClusteredIndex {
+    /// The cluster key, which must be `{ _id: 1 }`.
+    pub key: Document,
+    /// Must be true.
+    pub unique: bool,
+    pub name: Option<String>,
+    pub version: Option<i32>,
+}
+
+impl Default for ClusteredIndex {
+    fn default() -> Self {
+        ClusteredIndex {
+            key: doc! { "_id": 1 },
+            unique: true,
+            name: None,
+            version: None,
+        }
+    }
+}
+
+impl ClusteredIndex {
+    pub fn new() -> ClusteredIndex {
+        Default::default()
+    }
+
+    pub fn to_bson(&self) -> Document {
+        let mut document = doc! {
+            "key": self.key.clone(),
+            "unique": self.unique,
+        };
+
+        if let Some(ref name) = self.name {
+            document.insert("name", name.clone());
+        }
+
+        if let Some(version) = self.version {
+            document.insert("v", version);
+        }
+
+        document
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct CreateCollectionOptions {
     pub capped: Option<bool>,
     pub auto_index_id: Option<bool>,
@@ -11,6 +210,25 @@ pub struct CreateCollectionOptions {
     pub max: Option<i64>,
     pub use_power_of_two_sizes: Option<bool>,
     pub no_padding: Option<bool>,
+    /// A query filter or `$jsonSchema` document that inserted and updated documents must match.
+    pub validator: Option<Document>,
+    pub validation_level: Option<ValidationLevel>,
+    pub validation_action: Option<ValidationAction>,
+    pub storage_engine: Option<Document>,
+    /// The default storage engine options for indexes created on the collection.
+    pub index_option_defaults: Option<Document>,
+    pub collation: Option<Collation>,
+    /// The source collection or view, which makes the new collection a read-only view.
+    pub view_on: Option<String>,
+    /// The aggregation pipeline that defines a view.
+    pub pipeline: Option<Vec<Document>>,
+    /// Creates a time-series collection.
+    pub timeseries: Option<TimeseriesOptions>,
+    /// Removes documents of a time-series or clustered collection after this many seconds.
+    pub expire_after_seconds: Option<i64>,
+    /// Creates a clustered collection.
+    pub clustered_index: Option<ClusteredIndex>,
+    pub write_concern: Option<WriteConcern>,
 }
 
 impl CreateCollectionOptions {
@@ -53,6 +271,55 @@ impl From<CreateCollectionOptions> for Document {
             document.insert("flags", flags);
         }
 
+        if let Some(validator) = options.validator {
+            document.insert("validator", validator);
+        }
+
+        if let Some(validation_level) = options.validation_level {
+            document.insert("validationLevel", validation_level.as_str());
+        }
+
+        if let Some(validation_action) = options.validation_action {
+            document.insert("validationAction", validation_action.as_str());
+        }
+
+        if let Some(storage_engine) = options.storage_engine {
+            document.insert("storageEngine", storage_engine);
+        }
+
+        if let Some(index_option_defaults) = options.index_option_defaults {
+            document.insert("indexOptionDefaults", index_option_defaults);
+        }
+
+        if let Some(collation) = options.collation {
+            document.insert("collation", collation.to_bson());
+        }
+
+        if let Some(view_on) = options.view_on {
+            document.insert("viewOn", view_on);
+        }
+
+        if let Some(pipeline) = options.pipeline {
+            let pipeline: Vec<_> = pipeline.into_iter().map(Bson::Document).collect();
+            document.insert("pipeline", pipeline);
+        }
+
+        if let Some(timeseries) = options.timeseries {
+            document.insert("timeseries", timeseries.to_bson());
+        }
+
+        if let Some(expire_after_seconds) = options.expire_after_seconds {
+            document.insert("expireAfterSeconds", expire_after_seconds);
+        }
+
+        if let Some(clustered_index) = options.clustered_index {
+            document.insert("clusteredIndex", clustered_index.to_bson());
+        }
+
+        if let Some(write_concern) = options.write_concern {
+            document.insert("writeConcern", write_concern.to_bson());
+        }
+
         document
     }
 }
@@ -90,6 +357,147 @@ impl From<CreateUserOptions> for Document {
     }
 }
 
+/// Changes to an existing user. Fields left as `None` are not modified, and `roles` replaces the
+/// user's roles entirely.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct UpdateUserOptions {
+    pub password: Option<String>,
+    pub custom_data: Option<Document>,
+    pub roles: Option<Vec<Role>>,
+    pub write_concern: Option<WriteConcern>,
+}
+
+impl UpdateUserOptions {
+    pub fn new() -> UpdateUserOptions {
+        Default::default()
+    }
+}
+
+impl From<UpdateUserOptions> for Document {
+    fn from(options: UpdateUserOptions) -> Self {
+        let mut document = Document::new();
+
+        if let Some(password) = options.password {
+            document.insert("pwd", password);
+        }
+
+        if let Some(custom_data) = options.custom_data {
+            document.insert("customData", Bson::Document(custom_data));
+        }
+
+        if let Some(roles) = options.roles {
+            document.insert("roles", roles_to_bson(&roles));
+        }
+
+        if let Some(write_concern) = options.write_concern {
+            document.insert("writeConcern", write_concern.to_bson());
+        }
+
+        document
+    }
+}
+
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct CreateRoleOptions {
+    pub privileges: Vec<Privilege>,
+    /// The roles whose privileges the new role inherits.
+    pub roles: Vec<Role>,
+    pub write_concern: Option<WriteConcern>,
+}
+
+impl CreateRoleOptions {
+    pub fn new() -> CreateRoleOptions {
+        Default::default()
+    }
+}
+
+impl From<CreateRoleOptions> for Document {
+    fn from(options: CreateRoleOptions) -> Self {
+        let mut document = Document::new();
+
+        document.insert("privileges", privileges_to_bson(&options.privileges));
+        document.insert("roles", roles_to_bson(&options.roles));
+
+        if let Some(write_concern) = options.write_concern {
+            document.insert("writeConcern", write_concern.to_bson());
+        }
+
+        document
+    }
+}
+
+/// Changes to an existing role. Fields left as `None` are not modified, and `privileges` and
+/// `roles` replace the role's current ones entirely.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct UpdateRoleOptions {
+    pub privileges: Option<Vec<Privilege>>,
+    pub roles: Option<Vec<Role>>,
+    pub write_concern: Option<WriteConcern>,
+}
+
+impl UpdateRoleOptions {
+    pub fn new() -> UpdateRoleOptions {
+        Default::default()
+    }
+}
+
+impl From<UpdateRoleOptions> for Document {
+    fn from(options: UpdateRoleOptions) -> Self {
+        let mut document = Document::new();
+
+        if let Some(privileges) = options.privileges {
+            document.insert("privileges", privileges_to_bson(&privileges));
+        }
+
+        if let Some(roles) = options.roles {
+            document.insert("roles", roles_to_bson(&roles));
+        }
+
+        if let Some(write_concern) = options.write_concern {
+            document.insert("writeConcern", write_concern.to_bson());
+        }
+
+        document
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RoleInfoOptions {
+    pub show_privileges: Option<bool>,
+    /// Whether listing all roles includes the built-in roles as well as user-defined ones.
+    pub show_builtin_roles: Option<bool>,
+}
+
+impl RoleInfoOptions {
+    pub fn new() -> RoleInfoOptions {
+        Default::default()
+    }
+}
+
+impl From<RoleInfoOptions> for Document {
+    fn from(options: RoleInfoOptions) -> Self {
+        let mut document = Document::new();
+
+        if let Some(show_privileges) = options.show_privileges {
+            document.insert("showPrivileges", show_privileges);
+        }
+
+        if let Some(show_builtin_roles) = options.show_builtin_roles {
+            document.insert("showBuiltinRoles", show_builtin_roles);
+        }
+
+        document
+    }
+}
+
+pub(crate) fn roles_to_bson(roles: &[Role]) -> Bson {
+    Bson::Array(roles.iter().map(Role::to_bson).collect())
+}
+
+pub(crate) fn privileges_to_bson(privileges: &[Privilege]) -> Bson {
+    Bson::Array(privileges.iter().map(Privilege::to_bson).collect())
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub struct UserInfoOptions {
     pub show_credentials: Option<bool>,
@@ -117,3 +525,35 @@ impl From<UserInfoOptions> for Document {
         document
     }
 }
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ListDatabasesOptions {
+    /// Returns only the names of the databases, which avoids locking each database to compute
+    /// its size.
+    pub name_only: Option<bool>,
+    /// Lists only the databases the user is authorized to access, which allows users without the
+    /// `listDatabases` privilege to run the command.
+    pub authorized_databases: Option<bool>,
+}
+
+impl ListDatabasesOptions {
+    pub fn new() -> ListDatabasesOptions {
+        Default::default()
+    }
+}
+
+impl From<ListDatabasesOptions> for Document {
+    fn from(options: ListDatabasesOptions) -> Self {
+        let mut document = Document::new();
+
+        if let Some(name_only) = options.name_only {
+            document.insert("nameOnly", name_only);
+        }
+
+        if let Some(authorized_databases) = options.authorized_databases {
+            document.insert("authorizedDatabases", authorized_databases);
+        }
+
+        document
+    }
+}
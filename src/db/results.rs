@@ -0,0 +1,513 @@
+//! Results for database-level operations.
+use bson::{self, Bson};
+use chrono::{DateTime, Utc};
+use common::{get_f64, get_i64};
+use cursor::Cursor;
+use std::collections::BTreeMap;
+use db::options::{ClusteredIndex, ProfilingLevel, TimeseriesOptions};
+use Error::ResponseError;
+use Result;
+
+/// The kind of namespace described by a `CollectionSpecification`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CollectionType {
+    Collection,
+    /// A read-only view defined by an aggregation pipeline.
+    View,
+    /// A time-series collection, whose measurements are stored in buckets.
+    Timeseries,
+    /// Any other type reported by the server.
+    Other(String),
+}
+
+/// A collection or view, as reported by `listCollections`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollectionSpecification {
+    pub name: String,
+    pub collection_type: CollectionType,
+    /// The options the collection or view was created with.
+    pub options: bson::Document,
+    pub read_only: bool,
+    /// The index on `_id`, which views do not have.
+    pub id_index: Option<bson::Document>,
+}
+
+impl CollectionSpecification {
+    /// Extracts a specification from a `listCollections` result document.
+    pub fn new(mut document: bson::Document) -> Result<CollectionSpecification> {
+        let name = match document.remove("name") {
+            Some(Bson::String(name)) => name,
+            _ => {
+                return Err(ResponseError(
+                    String::from("No name received for listed collection."),
+                ))
+            }
+        };
+
+        // Servers before 3.4 report neither views nor a type.
+        let collection_type = match document.remove("type") {
+            Some(Bson::String(ref kind)) if kind == "view" => CollectionType::View,
+            Some(Bson::String(ref kind)) if kind == "timeseries" => CollectionType::Timeseries,
+            Some(Bson::String(ref kind)) if kind != "collection" => {
+                CollectionType::Other(kind.to_owned())
+            }
+            _ => CollectionType::Collection,
+        };
+
+        let options = match document.remove("options") {
+            Some(Bson::Document(options)) => options,
+            _ => bson::Document::new(),
+        };
+
+        let read_only = match document.get("info") {
+            Some(&Bson::Document(ref info)) => info.get_bool("readOnly").unwrap_or(false),
+            _ => false,
+        };
+
+        let id_index = match document.remove("idIndex") {
+            Some(Bson::Document(id_index)) => Some(id_index),
+            _ => None,
+        };
+
+        Ok(CollectionSpecification {
+            name: name,
+            collection_type: collection_type,
+            options: options,
+            read_only: read_only,
+            id_index: id_index,
+        })
+    }
+
+    pub fn is_view(&self) -> bool {
+        self.collection_type == CollectionType::View
+    }
+
+    /// The source collection or view of a view.
+    pub fn view_on(&self) -> Option<&str> {
+        self.options.get_str("viewOn").ok()
+    }
+
+    /// The bucketing of a time-series collection.
+    pub fn timeseries(&self) -> Option<TimeseriesOptions> {
+        let timeseries = self.options.get_document("timeseries").ok()?;
+
+        Some(TimeseriesOptions {
+            time_field: timeseries.get_str("timeField").unwrap_or_default().to_owned(),
+            meta_field: timeseries.get_str("metaField").ok().map(String::from),
+            granularity: timeseries.get_str("granularity").ok().and_then(|g| g.parse().ok()),
+            bucket_max_span_seconds: get_i64(timeseries, "bucketMaxSpanSeconds"),
+            bucket_rounding_seconds: get_i64(timeseries, "bucketRoundingSeconds"),
+        })
+    }
+
+    /// The expiry of documents in a time-series or clustered collection.
+    pub fn expire_after_seconds(&self) -> Option<i64> {
+        get_i64(&self.options, "expireAfterSeconds")
+    }
+
+    /// The clustered index of a clustered collection.
+    pub fn clustered_index(&self) -> Option<ClusteredIndex> {
+        let index = self.options.get_document("clusteredIndex").ok()?;
+
+        Some(ClusteredIndex {
+            key: index.get_document("key").ok().cloned().unwrap_or_default(),
+            unique: index.get_bool("unique").unwrap_or(true),
+            name: index.get_str("name").ok().map(String::from),
+            version: get_i64(index, "v").map(|v| v as i32),
+        })
+    }
+
+    /// The pipeline that defines a view.
+    pub fn pipeline(&self) -> Option<Vec<bson::Document>> {
+        self.options.get_array("pipeline").ok().map(|stages| {
+            stages
+                .iter()
+                .filter_map(|stage| match *stage {
+                    Bson::Document(ref stage) => Some(stage.clone()),
+                    _ => None,
+                })
+                .collect()
+        })
+    }
+}
+
+/// A database, as reported by `listDatabases`.
+///
+/// The sizes and `empty` flag are not reported when only names were requested.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DatabaseSpecification {
+    pub name: String,
+    pub size_on_disk: Option<i64>,
+    pub empty: Option<bool>,
+    /// The size of the database on each shard, when connected to `mongos`.
+    pub shards: Option<BTreeMap<String, i64>>,
+}
+
+impl DatabaseSpecification {
+    /// Extracts a specification from a `listDatabases` result document.
+    pub fn new(document: &bson::Document) -> Result<DatabaseSpecification> {
+        let name = match document.get_str("name") {
+            Ok(name) => name.to_owned(),
+            Err(_) => {
+                return Err(ResponseError(
+                    String::from("No name received for listed database."),
+                ))
+            }
+        };
+
+        let shards = document.get_document("shards").ok().map(|shards| {
+            shards
+                .keys()
+                .filter_map(|shard| get_i64(shards, shard).map(|size| (shard.to_owned(), size)))
+                .collect()
+        });
+
+        Ok(DatabaseSpecification {
+            name: name,
+            size_on_disk: get_i64(document, "sizeOnDisk"),
+            empty: document.get_bool("empty").ok(),
+            shards: shards,
+        })
+    }
+}
+
+/// The databases on a server, as reported by `listDatabases`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ListDatabasesResult {
+    pub databases: Vec<DatabaseSpecification>,
+    /// The combined size on disk of all databases, which is not reported when only names were
+    /// requested.
+    pub total_size: Option<i64>,
+}
+
+impl ListDatabasesResult {
+    /// Extracts the databases from a `listDatabases` reply.
+    pub fn new(reply: &bson::Document) -> Result<ListDatabasesResult> {
+        let databases = match reply.get_array("databases") {
+            Ok(databases) => databases,
+            Err(_) => {
+                return Err(ResponseError(
+                    String::from("Server reply does not contain 'databases'."),
+                ))
+            }
+        };
+
+        let databases = databases
+            .iter()
+            .map(|database| match *database {
+                Bson::Document(ref database) => DatabaseSpecification::new(database),
+                _ => Err(ResponseError(String::from("Listed database is not a document."))),
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(ListDatabasesResult {
+            databases: databases,
+            total_size: get_i64(reply, "totalSize"),
+        })
+    }
+}
+
+/// Storage statistics for a database, as reported by `dbStats`.
+///
+/// Sizes are in bytes unless a scale was requested.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DbStats {
+    pub db: String,
+    pub collections: i64,
+    /// Not reported before MongoDB 3.4.
+    pub views: Option<i64>,
+    pub objects: i64,
+    pub avg_obj_size: f64,
+    pub data_size: i64,
+    pub storage_size: i64,
+    pub indexes: i64,
+    pub index_size: i64,
+    /// The sum of the storage and index sizes. Not reported before MongoDB 4.4.
+    pub total_size: Option<i64>,
+    pub scale_factor: i64,
+    pub fs_used_size: Option<i64>,
+    pub fs_total_size: Option<i64>,
+    pub raw: bson::Document,
+}
+
+impl DbStats {
+    /// Extracts the statistics from a `dbStats` reply.
+    pub fn new(raw: bson::Document) -> DbStats {
+        DbStats {
+            db: raw.get_str("db").unwrap_or_default().to_owned(),
+            collections: get_i64(&raw, "collections").unwrap_or(0),
+            views: get_i64(&raw, "views"),
+            objects: get_i64(&raw, "objects").unwrap_or(0),
+            avg_obj_size: get_f64(&raw, "avgObjSize").unwrap_or(0.0),
+            data_size: get_i64(&raw, "dataSize").unwrap_or(0),
+            storage_size: get_i64(&raw, "storageSize").unwrap_or(0),
+            indexes: get_i64(&raw, "indexes").unwrap_or(0),
+            index_size: get_i64(&raw, "indexSize").unwrap_or(0),
+            total_size: get_i64(&raw, "totalSize"),
+            scale_factor: get_i64(&raw, "scaleFactor").unwrap_or(1),
+            fs_used_size: get_i64(&raw, "fsUsedSize"),
+            fs_total_size: get_i64(&raw, "fsTotalSize"),
+            raw: raw,
+        }
+    }
+}
+
+/// The settings of the database profiler, as reported by the `profile` command.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProfilingStatus {
+    pub level: ProfilingLevel,
+    /// The threshold in milliseconds above which operations are considered slow.
+    pub slow_ms: i64,
+    /// The fraction of slow operations that are recorded.
+    pub sample_rate: f64,
+    /// The filter that selects the operations to record, which takes the place of `slow_ms` and
+    /// `sample_rate` when set.
+    pub filter: Option<bson::Document>,
+}
+
+impl ProfilingStatus {
+    /// Extracts the profiler settings from a `profile` reply, which reports the level in effect
+    /// before the command ran.
+    pub fn new(reply: &bson::Document) -> Result<ProfilingStatus> {
+        let level = match get_i64(reply, "was").and_then(|l| ProfilingLevel::from_i32(l as i32)) {
+            Some(level) => level,
+            None => {
+                return Err(ResponseError(
+                    String::from("No valid profiling level received from the server."),
+                ))
+            }
+        };
+
+        Ok(ProfilingStatus {
+            level: level,
+            slow_ms: get_i64(reply, "slowms").unwrap_or(100),
+            sample_rate: get_f64(reply, "sampleRate").unwrap_or(1.0),
+            filter: reply.get_document("filter").ok().cloned(),
+        })
+    }
+}
+
+/// An operation recorded by the database profiler in `system.profile`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProfileEntry {
+    /// The operation type, such as `query`, `update` or `command`.
+    pub op: String,
+    pub ns: String,
+    /// The time the operation took, in milliseconds.
+    pub millis: i64,
+    pub plan_summary: Option<String>,
+    pub keys_examined: Option<i64>,
+    pub docs_examined: Option<i64>,
+    pub command: Option<bson::Document>,
+    /// When the operation ran.
+    pub ts: Option<DateTime<Utc>>,
+    pub raw: bson::Document,
+}
+
+impl ProfileEntry {
+    /// Extracts an operation from a `system.profile` document.
+    pub fn new(raw: bson::Document) -> ProfileEntry {
+        ProfileEntry {
+            op: raw.get_str("op").unwrap_or_default().to_owned(),
+            ns: raw.get_str("ns").unwrap_or_default().to_owned(),
+            millis: get_i64(&raw, "millis").unwrap_or(0),
+            plan_summary: raw.get_str("planSummary").ok().map(String::from),
+            keys_examined: get_i64(&raw, "keysExamined"),
+            docs_examined: get_i64(&raw, "docsExamined"),
+            command: raw.get_document("command").ok().cloned(),
+            ts: raw.get_utc_datetime("ts").ok().cloned(),
+            raw: raw,
+        }
+    }
+}
+
+/// Iterates over the operations returned by `Database::profile_entries`.
+pub struct ProfileEntries {
+    cursor: Cursor,
+}
+
+impl ProfileEntries {
+    pub fn new(cursor: Cursor) -> ProfileEntries {
+        ProfileEntries { cursor: cursor }
+    }
+}
+
+impl Iterator for ProfileEntries {
+    type Item = Result<ProfileEntry>;
+
+    fn next(&mut self) -> Option<Result<ProfileEntry>> {
+        self.cursor.next().map(|result| result.map(ProfileEntry::new))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bson::{bson, doc};
+    use db::options::TimeseriesGranularity;
+    use super::*;
+
+    #[test]
+    fn views_are_distinguished_from_collections() {
+        let spec = CollectionSpecification::new(doc!{
+            "name": "totals",
+            "type": "view",
+            "options": {"viewOn": "orders", "pipeline": [{"$match": {"x": 1}}]},
+            "info": {"readOnly": true},
+        }).unwrap();
+
+        assert!(spec.is_view());
+        assert!(spec.read_only);
+        assert_eq!(Some("orders"), spec.view_on());
+        assert_eq!(Some(vec![doc!{"$match": {"x": 1}}]), spec.pipeline());
+        assert_eq!(None, spec.id_index);
+
+        let spec = CollectionSpecification::new(doc!{
+            "name": "orders",
+            "idIndex": {"v": 2, "key": {"_id": 1}, "name": "_id_"},
+        }).unwrap();
+
+        assert_eq!(CollectionType::Collection, spec.collection_type);
+        assert!(!spec.read_only);
+        assert!(spec.id_index.is_some());
+        assert!(CollectionSpecification::new(doc!{}).is_err());
+    }
+
+    #[test]
+    fn timeseries_metadata_is_parsed() {
+        let spec = CollectionSpecification::new(doc!{
+            "name": "readings",
+            "type": "timeseries",
+            "options": {
+                "timeseries": {
+                    "timeField": "ts",
+                    "metaField": "sensor",
+                    "granularity": "minutes",
+                    "bucketMaxSpanSeconds": 86400,
+                },
+                "expireAfterSeconds": 3600i64,
+            },
+        }).unwrap();
+
+        assert_eq!(CollectionType::Timeseries, spec.collection_type);
+        assert_eq!(Some(3600), spec.expire_after_seconds());
+
+        let timeseries = spec.timeseries().unwrap();
+        assert_eq!("ts", timeseries.time_field);
+        assert_eq!(Some(String::from("sensor")), timeseries.meta_field);
+        assert_eq!(Some(TimeseriesGranularity::Minutes), timeseries.granularity);
+        assert_eq!(Some(86400), timeseries.bucket_max_span_seconds);
+
+        let spec = CollectionSpecification::new(doc!{
+            "name": "clustered",
+            "options": {"clusteredIndex": {"key": {"_id": 1}, "unique": true, "v": 2}},
+        }).unwrap();
+
+        assert_eq!(None, spec.timeseries());
+        let clustered_index = spec.clustered_index().unwrap();
+        assert_eq!(doc!{"_id": 1}, clustered_index.key);
+        assert_eq!(Some(2), clustered_index.version);
+    }
+
+    #[test]
+    fn db_stats_are_parsed() {
+        let stats = DbStats::new(doc!{
+            "db": "shop",
+            "collections": 3,
+            "views": 1,
+            "objects": 1200i64,
+            "avgObjSize": 212.5,
+            "dataSize": 255000.0,
+            "storageSize": 98304.0,
+            "indexes": 5,
+            "indexSize": 65536.0,
+            "totalSize": 163840.0,
+            "scaleFactor": 1,
+            "ok": 1.0,
+        });
+
+        assert_eq!("shop", stats.db);
+        assert_eq!(3, stats.collections);
+        assert_eq!(Some(1), stats.views);
+        assert_eq!(1200, stats.objects);
+        assert_eq!(212.5, stats.avg_obj_size);
+        assert_eq!(255000, stats.data_size);
+        assert_eq!(Some(163840), stats.total_size);
+        assert_eq!(None, stats.fs_used_size);
+    }
+    #[test]
+    fn profiling_status_is_parsed() {
+        let status = ProfilingStatus::new(&doc!{
+            "was": 1,
+            "slowms": 50,
+            "sampleRate": 0.5,
+            "filter": {"millis": {"$gt": 10}},
+            "ok": 1.0,
+        }).unwrap();
+
+        assert_eq!(ProfilingLevel::SlowOperations, status.level);
+        assert_eq!(50, status.slow_ms);
+        assert_eq!(0.5, status.sample_rate);
+        assert_eq!(Some(doc!{"millis": {"$gt": 10}}), status.filter);
+
+        assert!(ProfilingStatus::new(&doc!{"was": 7, "ok": 1.0}).is_err());
+        assert!(ProfilingStatus::new(&doc!{"ok": 1.0}).is_err());
+    }
+
+    #[test]
+    fn profile_entries_are_parsed() {
+        let entry = ProfileEntry::new(doc!{
+            "op": "query",
+            "ns": "shop.orders",
+            "command": {"find": "orders", "filter": {"x": 1}},
+            "keysExamined": 0,
+            "docsExamined": 1500i64,
+            "millis": 12,
+            "planSummary": "COLLSCAN",
+            "ts": Bson::UtcDatetime(Utc::now()),
+        });
+
+        assert_eq!("query", entry.op);
+        assert_eq!("shop.orders", entry.ns);
+        assert_eq!(12, entry.millis);
+        assert_eq!(Some(String::from("COLLSCAN")), entry.plan_summary);
+        assert_eq!(Some(0), entry.keys_examined);
+        assert_eq!(Some(1500), entry.docs_examined);
+        assert_eq!(Some(doc!{"find": "orders", "filter": {"x": 1}}), entry.command);
+        assert!(entry.ts.is_some());
+    }
+    #[test]
+    fn listed_databases_are_parsed() {
+        let result = ListDatabasesResult::new(&doc!{
+            "databases": [
+                {"name": "admin", "sizeOnDisk": 40960.0, "empty": false},
+                {
+                    "name": "shop",
+                    "sizeOnDisk": 1048576i64,
+                    "empty": false,
+                    "shards": {"rs0": 524288i64, "rs1": 524288i64},
+                },
+                {"name": "scratch", "sizeOnDisk": 0, "empty": true},
+            ],
+            "totalSize": 1089536.0,
+            "ok": 1.0,
+        }).unwrap();
+
+        assert_eq!(3, result.databases.len());
+        assert_eq!(Some(1089536), result.total_size);
+
+        let shop = &result.databases[1];
+        assert_eq!("shop", shop.name);
+        assert_eq!(Some(1048576), shop.size_on_disk);
+        let shards = shop.shards.clone().expect("Expected shard sizes.");
+        assert_eq!(Some(&524288), shards.get("rs1"));
+        assert_eq!(Some(true), result.databases[2].empty);
+        assert_eq!(None, result.databases[0].shards);
+
+        let names = ListDatabasesResult::new(&doc!{"databases": [{"name": "admin"}]}).unwrap();
+        assert_eq!(None, names.total_size);
+        assert_eq!(None, names.databases[0].size_on_disk);
+
+        assert!(ListDatabasesResult::new(&doc!{"databases": [{"sizeOnDisk": 1}]}).is_err());
+        assert!(ListDatabasesResult::new(&doc!{"ok": 1.0}).is_err());
+    }
+}
@@ -74,6 +74,11 @@ pub enum Role {
         role: SingleDatabaseRole,
         db: String,
     },
+    /// A user-defined role, created with `create_role` on the database `db`.
+    Custom {
+        role: String,
+        db: String,
+    },
 }
 
 impl From<Role> for Bson {
@@ -86,6 +91,12 @@ impl From<Role> for Bson {
                   "db": db
               })
             }
+            Role::Custom { role, db } => {
+                Bson::Document(doc! {
+                  "role": role,
+                  "db": db
+              })
+            }
         }
     }
 }
@@ -100,3 +111,69 @@ impl Role {
         Bson::Array(vec.iter().map(Self::to_bson).collect())
     }
 }
+
+/// The resource that a privilege applies to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Resource {
+    /// A collection, where an empty `db` or `collection` matches every database or collection.
+    Collection {
+        db: String,
+        collection: String,
+    },
+    /// The cluster, for actions that affect the state of the deployment.
+    Cluster,
+    /// Every resource in the deployment, including system collections.
+    AnyResource,
+}
+
+impl Resource {
+    pub fn collection(db: &str, collection: &str) -> Resource {
+        Resource::Collection {
+            db: String::from(db),
+            collection: String::from(collection),
+        }
+    }
+
+    /// Every collection in the database `db`.
+    pub fn database(db: &str) -> Resource {
+        Resource::collection(db, "")
+    }
+
+    pub fn to_bson(&self) -> Bson {
+        match *self {
+            Resource::Collection { ref db, ref collection } => {
+                Bson::Document(doc! {
+                    "db": db,
+                    "collection": collection,
+                })
+            }
+            Resource::Cluster => Bson::Document(doc! { "cluster": true }),
+            Resource::AnyResource => Bson::Document(doc! { "anyResource": true }),
+        }
+    }
+}
+
+/// A set of actions allowed on a resource, such as `find` or `insert` on a collection.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Privilege {
+    pub resource: Resource,
+    pub actions: Vec<String>,
+}
+
+impl Privilege {
+    pub fn new(resource: Resource, actions: Vec<&str>) -> Privilege {
+        Privilege {
+            resource: resource,
+            actions: actions.into_iter().map(String::from).collect(),
+        }
+    }
+
+    pub fn to_bson(&self) -> Bson {
+        let actions: Vec<_> = self.actions.iter().map(|action| Bson::from(&action[..])).collect();
+
+        Bson::Document(doc! {
+            "resource": self.resource.to_bson(),
+            "actions": actions,
+        })
+    }
+}
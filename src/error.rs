@@ -59,6 +59,9 @@ pub enum Error {
     CursorNotFoundError,
     /// The application failed to secure a mutex due to a poisoned lock.
     PoisonLockError,
+    /// An operation did not complete within the client's `timeout_ms`, whether during server
+    /// selection, connection checkout, or while waiting for the server's reply.
+    TimeoutError,
     /// A server error with a given code.
     CodedError(ErrorCode),
     /// The client was unable to emit the events to the listeners due to a poisoned lock;
@@ -155,6 +158,7 @@ impl fmt::Display for Error {
             Error::ResponseError(ref inner) => inner.fmt(fmt),
             Error::CursorNotFoundError => fmt.write_str("No cursor found for cursor operation."),
             Error::PoisonLockError => fmt.write_str("Socket lock poisoned while attempting to access."),
+            Error::TimeoutError => fmt.write_str("The operation exceeded the client timeout."),
             Error::CodedError(ref err) => write!(fmt, "{}", err),
             Error::EventListenerError(ref err) => {
                 match *err {
@@ -186,6 +190,7 @@ impl error::Error for Error {
             Error::IoError(ref inner) => inner.description(),
             Error::CursorNotFoundError => "No cursor found for cursor operation.",
             Error::PoisonLockError => "Socket lock poisoned while attempting to access.",
+            Error::TimeoutError => "The operation exceeded the client timeout.",
             Error::CodedError(ref err) => err.to_str(),
             Error::EventListenerError(ref err) => {
                 match *err {
@@ -215,6 +220,7 @@ impl error::Error for Error {
             Error::ResponseError(_) |
             Error::CursorNotFoundError |
             Error::PoisonLockError |
+            Error::TimeoutError |
             Error::CodedError(_) |
             Error::EventListenerError(_) |
             Error::MaliciousServerError(_) |
@@ -399,6 +405,166 @@ impl ErrorCode {
             *self == ErrorCode::IndexAlreadyExists
     }
 
+    /// Returns the error code corresponding to a server error code, if it is known.
+    pub fn from_i32(code: i32) -> Option<ErrorCode> {
+        Some(match code {
+            0 => ErrorCode::OK,
+            1 => ErrorCode::InternalError,
+            2 => ErrorCode::BadValue,
+            3 => ErrorCode::OBSOLETE_DuplicateKey,
+            4 => ErrorCode::NoSuchKey,
+            5 => ErrorCode::GraphContainsCycle,
+            6 => ErrorCode::HostUnreachable,
+            7 => ErrorCode::HostNotFound,
+            8 => ErrorCode::UnknownError,
+            9 => ErrorCode::FailedToParse,
+            10 => ErrorCode::CannotMutateObject,
+            11 => ErrorCode::UserNotFound,
+            12 => ErrorCode::UnsupportedFormat,
+            13 => ErrorCode::Unauthorized,
+            14 => ErrorCode::TypeMismatch,
+            15 => ErrorCode::Overflow,
+            16 => ErrorCode::InvalidLength,
+            17 => ErrorCode::ProtocolError,
+            18 => ErrorCode::AuthenticationFailed,
+            19 => ErrorCode::CannotReuseObject,
+            20 => ErrorCode::IllegalOperation,
+            21 => ErrorCode::EmptyArrayOperation,
+            22 => ErrorCode::InvalidBSON,
+            23 => ErrorCode::AlreadyInitialized,
+            24 => ErrorCode::LockTimeout,
+            25 => ErrorCode::RemoteValidationError,
+            26 => ErrorCode::NamespaceNotFound,
+            27 => ErrorCode::IndexNotFound,
+            28 => ErrorCode::PathNotViable,
+            29 => ErrorCode::NonExistentPath,
+            30 => ErrorCode::InvalidPath,
+            31 => ErrorCode::RoleNotFound,
+            32 => ErrorCode::RolesNotRelated,
+            33 => ErrorCode::PrivilegeNotFound,
+            34 => ErrorCode::CannotBackfillArray,
+            35 => ErrorCode::UserModificationFailed,
+            36 => ErrorCode::RemoteChangeDetected,
+            37 => ErrorCode::FileRenameFailed,
+            38 => ErrorCode::FileNotOpen,
+            39 => ErrorCode::FileStreamFailed,
+            40 => ErrorCode::ConflictingUpdateOperators,
+            41 => ErrorCode::FileAlreadyOpen,
+            42 => ErrorCode::LogWriteFailed,
+            43 => ErrorCode::CursorNotFound,
+            45 => ErrorCode::UserDataInconsistent,
+            46 => ErrorCode::LockBusy,
+            47 => ErrorCode::NoMatchingDocument,
+            48 => ErrorCode::NamespaceExists,
+            49 => ErrorCode::InvalidRoleModification,
+            50 => ErrorCode::ExceededTimeLimit,
+            51 => ErrorCode::ManualInterventionRequired,
+            52 => ErrorCode::DollarPrefixedFieldName,
+            53 => ErrorCode::InvalidIdField,
+            54 => ErrorCode::NotSingleValueField,
+            55 => ErrorCode::InvalidDBRef,
+            56 => ErrorCode::EmptyFieldName,
+            57 => ErrorCode::DottedFieldName,
+            58 => ErrorCode::RoleModificationFailed,
+            59 => ErrorCode::CommandNotFound,
+            60 => ErrorCode::DatabaseNotFound,
+            61 => ErrorCode::ShardKeyNotFound,
+            62 => ErrorCode::OplogOperationUnsupported,
+            63 => ErrorCode::StaleShardVersion,
+            64 => ErrorCode::WriteConcernFailed,
+            65 => ErrorCode::MultipleErrorsOccurred,
+            66 => ErrorCode::ImmutableField,
+            67 => ErrorCode::CannotCreateIndex,
+            68 => ErrorCode::IndexAlreadyExists,
+            69 => ErrorCode::AuthSchemaIncompatible,
+            70 => ErrorCode::ShardNotFound,
+            71 => ErrorCode::ReplicaSetNotFound,
+            72 => ErrorCode::InvalidOptions,
+            73 => ErrorCode::InvalidNamespace,
+            74 => ErrorCode::NodeNotFound,
+            75 => ErrorCode::WriteConcernLegacyOK,
+            76 => ErrorCode::NoReplicationEnabled,
+            77 => ErrorCode::OperationIncomplete,
+            78 => ErrorCode::CommandResultSchemaViolation,
+            79 => ErrorCode::UnknownReplWriteConcern,
+            80 => ErrorCode::RoleDataInconsistent,
+            81 => ErrorCode::NoWhereParseContext,
+            82 => ErrorCode::NoProgressMade,
+            83 => ErrorCode::RemoteResultsUnavailable,
+            84 => ErrorCode::DuplicateKeyValue,
+            85 => ErrorCode::IndexOptionsConflict,
+            86 => ErrorCode::IndexKeySpecsConflict,
+            87 => ErrorCode::CannotSplit,
+            88 => ErrorCode::SplitFailed,
+            89 => ErrorCode::NetworkTimeout,
+            90 => ErrorCode::CallbackCanceled,
+            91 => ErrorCode::ShutdownInProgress,
+            92 => ErrorCode::SecondaryAheadOfPrimary,
+            93 => ErrorCode::InvalidReplicaSetConfig,
+            94 => ErrorCode::NotYetInitialized,
+            95 => ErrorCode::NotSecondary,
+            96 => ErrorCode::OperationFailed,
+            97 => ErrorCode::NoProjectionFound,
+            98 => ErrorCode::DBPathInUse,
+            99 => ErrorCode::WriteConcernNotDefined,
+            100 => ErrorCode::CannotSatisfyWriteConcern,
+            101 => ErrorCode::OutdatedClient,
+            102 => ErrorCode::IncompatibleAuditMetadata,
+            103 => ErrorCode::NewReplicaSetConfigurationIncompatible,
+            104 => ErrorCode::NodeNotElectable,
+            105 => ErrorCode::IncompatibleShardingMetadata,
+            106 => ErrorCode::DistributedClockSkewed,
+            107 => ErrorCode::LockFailed,
+            108 => ErrorCode::InconsistentReplicaSetNames,
+            109 => ErrorCode::ConfigurationInProgress,
+            110 => ErrorCode::CannotInitializeNodeWithData,
+            111 => ErrorCode::NotExactValueField,
+            112 => ErrorCode::WriteConflict,
+            113 => ErrorCode::InitialSyncFailure,
+            114 => ErrorCode::InitialSyncOplogSourceMissing,
+            115 => ErrorCode::CommandNotSupported,
+            116 => ErrorCode::DocTooLargeForCapped,
+            117 => ErrorCode::ConflictingOperationInProgress,
+            118 => ErrorCode::NamespaceNotSharded,
+            119 => ErrorCode::InvalidSyncSource,
+            120 => ErrorCode::OplogStartMissing,
+            121 => ErrorCode::DocumentValidationFailure,
+            122 => ErrorCode::OBSOLETE_ReadAfterOptimeTimeout,
+            123 => ErrorCode::NotAReplicaSet,
+            124 => ErrorCode::IncompatibleElectionProtocol,
+            125 => ErrorCode::CommandFailed,
+            126 => ErrorCode::RPCProtocolNegotiationFailed,
+            127 => ErrorCode::UnrecoverableRollbackError,
+            128 => ErrorCode::LockNotFound,
+            129 => ErrorCode::LockStateChangeFailed,
+            130 => ErrorCode::SymbolNotFound,
+            131 => ErrorCode::RLPInitializationFailed,
+            132 => ErrorCode::ConfigServersInconsistent,
+            133 => ErrorCode::FailedToSatisfyReadPreference,
+            134 => ErrorCode::XXX_TEMP_NAME_ReadCommittedCurrentlyUnavailable,
+            135 => ErrorCode::StaleTerm,
+            136 => ErrorCode::CappedPositionLost,
+            137 => ErrorCode::IncompatibleShardingConfigVersion,
+            138 => ErrorCode::RemoteOplogStale,
+            139 => ErrorCode::JSInterpreterFailure,
+            10107 => ErrorCode::NotMaster,
+            11000 => ErrorCode::DuplicateKey,
+            11600 => ErrorCode::InterruptedAtShutdown,
+            11601 => ErrorCode::Interrupted,
+            12586 => ErrorCode::BackgroundOperationInProgressForDatabase,
+            12587 => ErrorCode::BackgroundOperationInProgressForNamespace,
+            13104 => ErrorCode::PrepareConfigsFailedCode,
+            13297 => ErrorCode::DatabaseDifferCase,
+            13334 => ErrorCode::ShardKeyTooBig,
+            13388 => ErrorCode::SendStaleConfig,
+            13435 => ErrorCode::NotMasterNoSlaveOkCode,
+            13436 => ErrorCode::NotMasterOrSecondaryCode,
+            14031 => ErrorCode::OutOfDiskSpace,
+            17280 => ErrorCode::KeyTooLong,
+            _ => return None,
+        })
+    }
+
     fn to_str(&self) -> &'static str {
         match *self {
             ErrorCode::OK => "OK",
@@ -0,0 +1,236 @@
+//! Client metadata sent to the server when a connection is established.
+//!
+//! The first command on every connection is a legacy `isMaster` carrying a `client` document
+//! that identifies the application, driver, operating system and platform, as described by the
+//! [handshake specification](https://github.com/mongodb/specifications/blob/master/source/mongodb-handshake/handshake.rst).
+//! The document is logged by the server and reported by `$currentOp`, and is limited to 512
+//! bytes; optional fields are dropped or truncated as necessary to stay within it.
+use bson::{self, bson, doc};
+use Error::ArgumentError;
+use Result;
+
+use std::env::consts;
+use std::fs::File;
+use std::io::Read;
+
+/// The largest client metadata document the server accepts, in bytes.
+pub const MAX_METADATA_SIZE: usize = 512;
+
+/// The longest application name the server accepts, in bytes.
+pub const MAX_APP_NAME_SIZE: usize = 128;
+
+/// Identifies a library that wraps this driver, such as an ODM.
+///
+/// Each field is appended to the driver's own value, separated by a `|`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DriverInfo {
+    pub name: String,
+    pub version: Option<String>,
+    pub platform: Option<String>,
+}
+
+impl DriverInfo {
+    pub fn new(name: &str) -> DriverInfo {
+        DriverInfo {
+            name: String::from(name),
+            ..Default::default()
+        }
+    }
+}
+
+/// Builds the `client` document sent with the handshake of each connection.
+///
+/// Returns an ArgumentError if `app_name` is longer than 128 bytes, or if the metadata does not
+/// fit in 512 bytes even after every optional field has been removed.
+pub fn client_metadata(
+    app_name: Option<&str>,
+    driver_info: Option<&DriverInfo>,
+) -> Result<bson::Document> {
+    let mut driver_name = String::from(::DRIVER_NAME);
+    let mut driver_version = String::from(env!("CARGO_PKG_VERSION"));
+    let mut platform = format!("Rust, {}-{}", consts::ARCH, consts::OS);
+
+    if let Some(info) = driver_info {
+        driver_name = format!("{}|{}", driver_name, info.name);
+
+        if let Some(ref version) = info.version {
+            driver_version = format!("{}|{}", driver_version, version);
+        }
+
+        if let Some(ref wrapper_platform) = info.platform {
+            platform = format!("{}|{}", platform, wrapper_platform);
+        }
+    }
+
+    let mut metadata = doc! {
+        "driver": {
+            "name": driver_name,
+            "version": driver_version,
+        },
+        "os": os_metadata(),
+        "platform": platform.clone(),
+    };
+
+    if let Some(app_name) = app_name {
+        if app_name.len() > MAX_APP_NAME_SIZE {
+            return Err(ArgumentError(format!(
+                "appName must not exceed {} bytes, but '{}' is {} bytes long.",
+                MAX_APP_NAME_SIZE,
+                app_name,
+                app_name.len()
+            )));
+        }
+
+        metadata.insert("application", doc! { "name": app_name });
+    }
+
+    fit_metadata(metadata, &platform)
+}
+
+// Drops the optional operating system fields, then truncates and finally drops the platform,
+// until the metadata fits in MAX_METADATA_SIZE.
+fn fit_metadata(mut metadata: bson::Document, platform: &str) -> Result<bson::Document> {
+    if metadata_size(&metadata) <= MAX_METADATA_SIZE {
+        return Ok(metadata);
+    }
+
+    metadata.insert("os", doc! { "type": os_type() });
+
+    let excess = metadata_size(&metadata).saturating_sub(MAX_METADATA_SIZE);
+    if excess == 0 {
+        return Ok(metadata);
+    }
+
+    if excess < platform.len() {
+        let mut end = platform.len() - excess;
+        while !platform.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        metadata.insert("platform", &platform[..end]);
+    } else {
+        metadata.remove("platform");
+    }
+
+    if metadata_size(&metadata) > MAX_METADATA_SIZE {
+        return Err(ArgumentError(format!(
+            "Client metadata must not exceed {} bytes; shorten the driver info.",
+            MAX_METADATA_SIZE
+        )));
+    }
+
+    Ok(metadata)
+}
+
+fn metadata_size(metadata: &bson::Document) -> usize {
+    let mut buffer = Vec::new();
+    match bson::encode_document(&mut buffer, metadata) {
+        Ok(()) => buffer.len(),
+        Err(_) => 0,
+    }
+}
+
+// The operating system type, named as the server expects for the common platforms.
+fn os_type() -> &'static str {
+    match consts::OS {
+        "linux" => "Linux",
+        "macos" => "Darwin",
+        "windows" => "Windows",
+        "freebsd" => "FreeBSD",
+        os => os,
+    }
+}
+
+fn os_metadata() -> bson::Document {
+    let mut os = doc! {
+        "type": os_type(),
+        "architecture": consts::ARCH,
+    };
+
+    let (name, version) = os_release();
+
+    if let Some(name) = name {
+        os.insert("name", name);
+    }
+
+    if let Some(version) = version {
+        os.insert("version", version);
+    }
+
+    os
+}
+
+// Reads the distribution name and version from `/etc/os-release`, which is only available on
+// Linux.
+fn os_release() -> (Option<String>, Option<String>) {
+    let mut contents = String::new();
+
+    let read = File::open("/etc/os-release").and_then(|mut file| {
+        file.read_to_string(&mut contents)
+    });
+
+    if read.is_err() {
+        return (None, None);
+    }
+
+    let value = |key: &str| {
+        contents.lines().find(|line| line.starts_with(key)).map(|line| {
+            line[key.len()..].trim_matches('"').to_owned()
+        })
+    };
+
+    (value("NAME="), value("VERSION_ID="))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn metadata_includes_application_and_wrapping_driver() {
+        let info = DriverInfo {
+            name: String::from("odm"),
+            version: Some(String::from("1.2.0")),
+            platform: Some(String::from("tokio")),
+        };
+
+        let metadata = client_metadata(Some("billing"), Some(&info)).unwrap();
+
+        let application = metadata.get_document("application").unwrap();
+        assert_eq!("billing", application.get_str("name").unwrap());
+
+        let driver = metadata.get_document("driver").unwrap();
+        assert_eq!(format!("{}|odm", ::DRIVER_NAME), driver.get_str("name").unwrap());
+        assert!(driver.get_str("version").unwrap().ends_with("|1.2.0"));
+        assert!(metadata.get_str("platform").unwrap().ends_with("|tokio"));
+
+        let os = metadata.get_document("os").unwrap();
+        assert_eq!(os_type(), os.get_str("type").unwrap());
+        assert_eq!(consts::ARCH, os.get_str("architecture").unwrap());
+
+        assert!(!client_metadata(None, None).unwrap().contains_key("application"));
+    }
+
+    #[test]
+    fn long_app_names_are_rejected() {
+        let app_name: String = ::std::iter::repeat('a').take(MAX_APP_NAME_SIZE + 1).collect();
+        assert!(client_metadata(Some(&app_name), None).is_err());
+    }
+
+    #[test]
+    fn metadata_is_truncated_to_size_limit() {
+        let long_platform: String = ::std::iter::repeat('p').take(600).collect();
+        let info = DriverInfo {
+            platform: Some(long_platform),
+            ..DriverInfo::new("odm")
+        };
+
+        let metadata = client_metadata(Some("billing"), Some(&info)).unwrap();
+        assert_eq!(MAX_METADATA_SIZE, metadata_size(&metadata));
+        assert_eq!(doc! { "type": os_type() }, *metadata.get_document("os").unwrap());
+        assert!(metadata.get_str("platform").unwrap().starts_with("Rust"));
+
+        let long_name: String = ::std::iter::repeat('n').take(600).collect();
+        assert!(client_metadata(None, Some(&DriverInfo::new(&long_name))).is_err());
+    }
+}
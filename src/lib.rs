@@ -149,6 +149,8 @@ extern crate hmac;
 extern crate pbkdf2;
 extern crate hex;
 
+pub mod admin;
+pub mod aggregation;
 pub mod db;
 pub mod coll;
 pub mod common;
@@ -156,7 +158,9 @@ pub mod connstring;
 pub mod cursor;
 pub mod error;
 pub mod gridfs;
+pub mod handshake;
 pub mod pool;
+pub mod query;
 pub mod stream;
 pub mod topology;
 pub mod wire_protocol;
@@ -178,11 +182,15 @@ use std::ops::DerefMut;
 use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicIsize, Ordering};
 
+use admin::{Operations, ServerStatus};
 use apm::Listener;
-use common::{ReadPreference, ReadMode, WriteConcern};
-use connstring::ConnectionString;
+use common::{merge_options, ReadConcern, ReadPreference, ReadMode, WriteConcern};
+use connstring::{ConnectionString, Host};
 use db::{Database, ThreadedDatabase};
-use error::Error::ResponseError;
+use db::options::ListDatabasesOptions;
+use db::results::ListDatabasesResult;
+use error::Error::{ArgumentError, ResponseError};
+use handshake::DriverInfo;
 use pool::PooledStream;
 use stream::StreamConnector;
 use topology::{Topology, TopologyDescription, TopologyType, DEFAULT_HEARTBEAT_FREQUENCY_MS,
@@ -198,7 +206,12 @@ pub struct ClientInner {
     /// Describes the guarantees provided by MongoDB when reporting the success of a write
     /// operation.
     pub write_concern: WriteConcern,
+    /// Describes the consistency and isolation of data returned by read operations. If not set,
+    /// the server's default read concern is used.
+    pub read_concern: Option<ReadConcern>,
     req_id: Arc<AtomicIsize>,
+    // The `client` document sent in the handshake of each new connection.
+    client_metadata: bson::Document,
     topology: Topology,
     listener: Listener,
     log_file: Option<Mutex<File>>,
@@ -209,7 +222,9 @@ impl fmt::Debug for ClientInner {
         f.debug_struct("ClientInner")
             .field("read_preference", &self.read_preference)
             .field("write_concern", &self.write_concern)
+            .field("read_concern", &self.read_concern)
             .field("req_id", &self.req_id)
+            .field("client_metadata", &self.client_metadata)
             .field("topology", &self.topology)
             .field("listener", &"Listener { .. }")
             .field("log_file", &self.log_file)
@@ -226,12 +241,23 @@ pub struct ClientOptions {
     pub read_preference: Option<ReadPreference>,
     /// Client-level write guarantees when reporting a write success.
     pub write_concern: Option<WriteConcern>,
+    /// Client-level consistency and isolation guarantees for read operations.
+    pub read_concern: Option<ReadConcern>,
     /// Frequency of server monitor updates; default 10000 ms.
     pub heartbeat_frequency_ms: u32,
     /// Timeout for selecting an appropriate server for operations; default 30000 ms.
     pub server_selection_timeout_ms: i64,
     /// The size of the latency window for selecting suitable servers; default 15 ms.
     pub local_threshold_ms: i64,
+    /// Client-side time limit for each operation, covering server selection, connection
+    /// checkout, and waiting for the server's reply; unlimited by default. An operation that
+    /// exceeds it fails with `Error::TimeoutError`.
+    pub timeout_ms: Option<i64>,
+    /// The application name reported to the server in the connection handshake, which appears
+    /// in the server logs and in `current_op`; takes precedence over `appName` in the URI.
+    pub app_name: Option<String>,
+    /// Identifies a library that wraps this driver in the connection handshake.
+    pub driver_info: Option<DriverInfo>,
     /// Options for how to connect to the server.
     pub stream_connector: StreamConnector,
 }
@@ -243,9 +269,13 @@ impl ClientOptions {
             log_file: None,
             read_preference: None,
             write_concern: None,
+            read_concern: None,
             heartbeat_frequency_ms: DEFAULT_HEARTBEAT_FREQUENCY_MS,
             server_selection_timeout_ms: DEFAULT_SERVER_SELECTION_TIMEOUT_MS,
             local_threshold_ms: DEFAULT_LOCAL_THRESHOLD_MS,
+            timeout_ms: None,
+            app_name: None,
+            driver_info: None,
             stream_connector: StreamConnector::default(),
         }
     }
@@ -307,18 +337,44 @@ pub trait ThreadedClient: Sync + Sized {
         read_preference: Option<ReadPreference>,
         write_concern: Option<WriteConcern>,
     ) -> Database;
+    /// Creates a database representation with custom read and write controls and read concern.
+    fn db_with_read_concern(
+        &self,
+        db_name: &str,
+        read_preference: Option<ReadPreference>,
+        write_concern: Option<WriteConcern>,
+        read_concern: Option<ReadConcern>,
+    ) -> Database;
     /// Acquires a connection stream from the pool, along with slave_ok and should_send_read_pref.
     fn acquire_stream(&self, read_pref: ReadPreference) -> Result<(PooledStream, bool, bool)>;
     /// Acquires a connection stream from the pool for write operations.
     fn acquire_write_stream(&self) -> Result<PooledStream>;
+    /// Acquires a connection stream to a specific host, failing if the host has left the
+    /// topology or its connection pool has been cleared since `iteration`.
+    fn acquire_stream_for_host(&self, host: &Host, iteration: usize) -> Result<PooledStream>;
     /// Returns a unique operational request id.
     fn get_req_id(&self) -> i32;
     /// Returns a list of all database names that exist on the server.
     fn database_names(&self) -> Result<Vec<String>>;
+    /// Returns the names of the databases on the server that match the filter.
+    fn database_names_with_filter(&self, filter: Option<bson::Document>) -> Result<Vec<String>>;
+    /// Returns the databases on the server that match the filter, along with their sizes.
+    fn list_databases(
+        &self,
+        filter: Option<bson::Document>,
+        options: Option<ListDatabasesOptions>,
+    ) -> Result<ListDatabasesResult>;
     /// Drops the database defined by `db_name`.
     fn drop_database(&self, db_name: &str) -> Result<()>;
     /// Reports whether this instance is a primary, master, mongos, or standalone mongod instance.
     fn is_master(&self) -> Result<bool>;
+    /// Returns the state of the server, as reported by `serverStatus`.
+    fn server_status(&self) -> Result<ServerStatus>;
+    /// Returns the operations in progress on the primary, optionally restricted to those
+    /// matching `filter`.
+    fn current_op(&self, filter: Option<bson::Document>) -> Result<Operations>;
+    /// Terminates the operation identified by `opid`, as reported by `current_op`.
+    fn kill_op(&self, opid: Bson) -> Result<()>;
     /// Sets a function to be run every time a command starts.
     fn add_start_hook(&mut self, hook: fn(Client, &CommandStarted)) -> Result<()>;
     /// Sets a function to be run every time a command completes.
@@ -361,12 +417,49 @@ impl ThreadedClient for Client {
 
         let client_options = options.unwrap_or_else(ClientOptions::new);
 
+        if let Some(timeout_ms) = client_options.timeout_ms {
+            if timeout_ms <= 0 {
+                return Err(ArgumentError(format!(
+                    "timeout_ms must be positive, but {} was provided.",
+                    timeout_ms
+                )));
+            }
+        }
+
         let rp = client_options.read_preference.unwrap_or_else(|| {
             ReadPreference::new(ReadMode::Primary, None)
         });
-        let wc = client_options.write_concern.unwrap_or_else(
-            WriteConcern::new,
-        );
+        // A write concern set through the options takes precedence over the URI.
+        let wc = match client_options.write_concern {
+            Some(write_concern) => write_concern,
+            None => {
+                match config.options {
+                    Some(ref opts) => opts.write_concern()?,
+                    None => WriteConcern::new(),
+                }
+            }
+        };
+
+        // A read concern set through the options takes precedence over `readConcernLevel`.
+        let rc = match client_options.read_concern {
+            Some(read_concern) => Some(read_concern),
+            None => {
+                match config.options.as_ref().and_then(|opts| opts.get("readConcernLevel")) {
+                    Some(level) => Some(ReadConcern::new(level.parse()?)),
+                    None => None,
+                }
+            }
+        };
+
+        // An application name set through the options takes precedence over `appName`.
+        let app_name = client_options.app_name.clone().or_else(|| {
+            config.options.as_ref().and_then(|opts| opts.get("appName").cloned())
+        });
+
+        let client_metadata = handshake::client_metadata(
+            app_name.as_ref().map(String::as_str),
+            client_options.driver_info.as_ref(),
+        )?;
 
         let listener = Listener::new();
         let file = match client_options.log_file {
@@ -386,6 +479,7 @@ impl ThreadedClient for Client {
 
         let client = Arc::new(ClientInner {
             req_id: Arc::new(AtomicIsize::new(0)),
+            client_metadata: client_metadata,
             topology: Topology::new(
                 config.clone(),
                 description,
@@ -394,6 +488,7 @@ impl ThreadedClient for Client {
             listener: listener,
             read_preference: rp,
             write_concern: wc,
+            read_concern: rc,
             log_file: file,
         });
 
@@ -404,6 +499,7 @@ impl ThreadedClient for Client {
             top.heartbeat_frequency_ms = client_options.heartbeat_frequency_ms;
             top.server_selection_timeout_ms = client_options.server_selection_timeout_ms;
             top.local_threshold_ms = client_options.local_threshold_ms;
+            top.timeout_ms = client_options.timeout_ms;
 
             for host in config.hosts {
                 let server = Server::new(
@@ -434,6 +530,22 @@ impl ThreadedClient for Client {
         Database::open(self.clone(), db_name, read_preference, write_concern)
     }
 
+    fn db_with_read_concern(
+        &self,
+        db_name: &str,
+        read_preference: Option<ReadPreference>,
+        write_concern: Option<WriteConcern>,
+        read_concern: Option<ReadConcern>,
+    ) -> Database {
+        Database::open_with_read_concern(
+            self.clone(),
+            db_name,
+            read_preference,
+            write_concern,
+            read_concern,
+        )
+    }
+
     fn acquire_stream(
         &self,
         read_preference: ReadPreference,
@@ -445,35 +557,42 @@ impl ThreadedClient for Client {
         self.topology.acquire_write_stream(self.clone())
     }
 
+    fn acquire_stream_for_host(&self, host: &Host, iteration: usize) -> Result<PooledStream> {
+        self.topology.acquire_stream_for_host(self.clone(), host, iteration)
+    }
+
     fn get_req_id(&self) -> i32 {
         self.req_id.fetch_add(1, Ordering::SeqCst) as i32
     }
 
     fn database_names(&self) -> Result<Vec<String>> {
-        let doc = doc!{ "listDatabases": 1 };
-        let db = self.db("admin");
-        let res = db.command(doc, CommandType::ListDatabases, None)?;
+        self.database_names_with_filter(None)
+    }
 
-        if let Some(&Bson::Array(ref batch)) = res.get("databases") {
-            // Extract database names
-            let map = batch
-                .iter()
-                .filter_map(|bdoc| {
-                    if let Bson::Document(ref doc) = *bdoc {
-                        if let Some(&Bson::String(ref name)) = doc.get("name") {
-                            return Some(name.to_owned());
-                        }
-                    }
-                    None
-                })
-                .collect();
+    fn database_names_with_filter(&self, filter: Option<bson::Document>) -> Result<Vec<String>> {
+        let options = ListDatabasesOptions { name_only: Some(true), ..ListDatabasesOptions::new() };
+        let result = self.list_databases(filter, Some(options))?;
+        Ok(result.databases.into_iter().map(|database| database.name).collect())
+    }
+
+    fn list_databases(
+        &self,
+        filter: Option<bson::Document>,
+        options: Option<ListDatabasesOptions>,
+    ) -> Result<ListDatabasesResult> {
+        let mut doc = doc!{ "listDatabases": 1 };
+
+        if let Some(filter) = filter {
+            doc.insert("filter", filter);
+        }
 
-            Ok(map)
-        } else {
-            Err(ResponseError(
-                String::from("Server reply does not contain 'databases'."),
-            ))
+        if let Some(options) = options {
+            doc = merge_options(doc, options);
         }
+
+        let db = self.db("admin");
+        let res = db.command(doc, CommandType::ListDatabases, None)?;
+        ListDatabasesResult::new(&res)
     }
 
     fn drop_database(&self, db_name: &str) -> Result<()> {
@@ -493,6 +612,69 @@ impl ThreadedClient for Client {
         }
     }
 
+    fn server_status(&self) -> Result<ServerStatus> {
+        let doc = doc!{ "serverStatus": 1 };
+        let db = self.db("admin");
+        db.command(doc, CommandType::ServerStatus, None).map(ServerStatus::new)
+    }
+
+    fn current_op(&self, filter: Option<bson::Document>) -> Result<Operations> {
+        let db = self.db("admin");
+        let read_preference = ReadPreference::new(ReadMode::Primary, None);
+
+        let max_wire_version = match self.topology.write_server_description() {
+            Some(description) => description.max_wire_version,
+            None => self.acquire_write_stream()?.max_wire_version,
+        };
+
+        // The `$currentOp` stage requires MongoDB 3.6 (wire version 6); older servers report
+        // their operations in the `inprog` array of the `currentOp` command.
+        if max_wire_version < 6 {
+            let doc = merge_options(doc!{ "currentOp": 1 }, filter.unwrap_or_default());
+            let mut reply = db.command(doc, CommandType::CurrentOp, Some(read_preference))?;
+
+            let inprog = match reply.remove("inprog") {
+                Some(Bson::Array(inprog)) => inprog,
+                _ => {
+                    return Err(ResponseError(
+                        String::from("Server reply does not contain 'inprog'."),
+                    ))
+                }
+            };
+
+            let inprog = inprog
+                .into_iter()
+                .filter_map(|op| match op {
+                    Bson::Document(op) => Some(op),
+                    _ => None,
+                })
+                .collect();
+
+            return Ok(Operations::from_inprog(inprog));
+        }
+
+        let mut pipeline = vec![Bson::Document(doc!{ "$currentOp": {} })];
+
+        if let Some(filter) = filter {
+            pipeline.push(Bson::Document(doc!{ "$match": filter }));
+        }
+
+        let doc = doc! {
+            "aggregate": 1,
+            "pipeline": pipeline,
+            "cursor": {},
+        };
+
+        let cursor = db.command_cursor(doc, CommandType::CurrentOp, read_preference)?;
+        Ok(Operations::new(cursor))
+    }
+
+    fn kill_op(&self, opid: Bson) -> Result<()> {
+        let doc = doc!{ "killOp": 1, "op": opid };
+        let db = self.db("admin");
+        db.command(doc, CommandType::KillOp, None).map(|_| ())
+    }
+
     fn add_start_hook(&mut self, hook: fn(Client, &CommandStarted)) -> Result<()> {
         self.listener.add_start_hook(hook)
     }
@@ -1,5 +1,5 @@
 //! Connection pooling for a single MongoDB server.
-use error::Error::{self, ArgumentError, OperationError};
+use error::Error::{self, ArgumentError, OperationError, TimeoutError};
 use error::Result;
 
 use Client;
@@ -9,6 +9,7 @@ use connstring::Host;
 use cursor::Cursor;
 use stream::{Stream, StreamConnector};
 use wire_protocol::flags::OpQueryFlags;
+use wire_protocol::operations::Message;
 
 use bson::{bson, doc};
 use bufstream::BufStream;
@@ -16,6 +17,7 @@ use bufstream::BufStream;
 use std::fmt;
 use std::sync::{Arc, Condvar, Mutex};
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
 
 pub static DEFAULT_POOL_SIZE: usize = 5;
 
@@ -64,8 +66,31 @@ pub struct PooledStream {
     wait_lock: Arc<Condvar>,
     // The pool iteration at the moment of extraction.
     iteration: usize,
+    // The host that the stream is connected to.
+    host: Host,
     // Whether the handshake occurred successfully.
     successful_handshake: bool,
+    // Whether the socket should be closed instead of being returned to the pool.
+    discarded: bool,
+    // The instant after which reads on the socket time out, if the operation is bounded.
+    read_deadline: Option<Instant>,
+    /// The max wire version last reported by the server that the stream is connected to.
+    pub max_wire_version: i64,
+    /// The max BSON object size last reported by the server that the stream is connected to.
+    pub max_bson_object_size: i64,
+    /// The max message size last reported by the server that the stream is connected to.
+    pub max_message_size_bytes: i64,
+    /// The max write batch size last reported by the server that the stream is connected to.
+    pub max_write_batch_size: i64,
+}
+
+impl fmt::Debug for PooledStream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PooledStream")
+            .field("host", &self.host)
+            .field("iteration", &self.iteration)
+            .finish()
+    }
 }
 
 impl PooledStream {
@@ -73,6 +98,56 @@ impl PooledStream {
     pub fn get_socket(&mut self) -> &mut BufStream<Stream> {
         self.socket.as_mut().unwrap()
     }
+
+    /// Returns the host that the stream is connected to.
+    pub fn host(&self) -> &Host {
+        &self.host
+    }
+
+    /// Returns the iteration of the connection pool at the moment the stream was extracted.
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    /// Bounds every subsequent read on the socket by the time remaining until `deadline`,
+    /// returning a TimeoutError if the deadline has already passed.
+    pub fn set_read_deadline(&mut self, deadline: Instant) -> Result<()> {
+        if Instant::now() >= deadline {
+            return Err(TimeoutError);
+        }
+
+        self.read_deadline = Some(deadline);
+        Ok(())
+    }
+
+    /// Reads a reply off the socket. If the read fails, the socket is discarded, since it may
+    /// still hold part of the reply.
+    pub fn read_reply(&mut self) -> Result<Message> {
+        if let Some(deadline) = self.read_deadline {
+            let now = Instant::now();
+            if now >= deadline {
+                self.discard();
+                return Err(TimeoutError);
+            }
+
+            // The remaining time is re-applied before each reply, so that later batches are
+            // still bounded by the original deadline.
+            self.get_socket().get_ref().set_read_timeout(Some(deadline - now))?;
+        }
+
+        let result = Message::read(self.get_socket().get_mut());
+        if result.is_err() {
+            self.discard();
+        }
+
+        result
+    }
+
+    /// Marks the socket to be closed when the stream is dropped, rather than returned to the
+    /// pool. This is used when the socket may still hold unread replies.
+    pub fn discard(&mut self) {
+        self.discarded = true;
+    }
 }
 
 impl Drop for PooledStream {
@@ -86,7 +161,25 @@ impl Drop for PooledStream {
         // or give up if the pool lock has been poisoned.
         if let Ok(mut locked) = self.pool.lock() {
             if self.iteration == locked.iteration {
-                locked.sockets.push(self.socket.take().unwrap());
+                if self.discarded {
+                    // Free the slot of the closed socket for a new connection.
+                    let _ = locked.len.fetch_sub(1, Ordering::SeqCst);
+                    self.wait_lock.notify_one();
+                    return;
+                }
+
+                let socket = self.socket.take().unwrap();
+
+                // Clear the deadline so it doesn't bound the next operation on the socket,
+                // and close the socket instead if it can't be cleared.
+                if self.read_deadline.is_some() && socket.get_ref().set_read_timeout(None).is_err()
+                {
+                    let _ = locked.len.fetch_sub(1, Ordering::SeqCst);
+                    self.wait_lock.notify_one();
+                    return;
+                }
+
+                locked.sockets.push(socket);
                 // Notify waiting threads that the pool has been repopulated.
                 self.wait_lock.notify_one();
             }
@@ -140,7 +233,14 @@ impl ConnectionPool {
     /// Attempts to acquire a connected socket. If none are available and
     /// the pool has not reached its maximum size, a new socket will connect.
     /// Otherwise, the function will block until a socket is returned to the pool.
-    pub fn acquire_stream(&self, client: Client) -> Result<PooledStream> {
+    ///
+    /// If a deadline is provided, waiting for a socket past it results in a TimeoutError,
+    /// and reads on the acquired socket time out at the deadline.
+    pub fn acquire_stream(
+        &self,
+        client: Client,
+        deadline: Option<Instant>,
+    ) -> Result<PooledStream> {
         let mut locked = self.inner.lock()?;
         if locked.size == 0 {
             return Err(OperationError(String::from(
@@ -151,13 +251,26 @@ impl ConnectionPool {
         loop {
             // Acquire available existing socket
             if let Some(stream) = locked.sockets.pop() {
-                return Ok(PooledStream {
+                let mut stream = PooledStream {
                     socket: Some(stream),
                     pool: self.inner.clone(),
                     wait_lock: self.wait_lock.clone(),
                     iteration: locked.iteration,
+                    host: self.host.clone(),
                     successful_handshake: true,
-                });
+                    discarded: false,
+                    read_deadline: None,
+                    max_wire_version: 0,
+                    max_bson_object_size: 0,
+                    max_message_size_bytes: 0,
+                    max_write_batch_size: 0,
+                };
+
+                if let Some(deadline) = deadline {
+                    stream.set_read_deadline(deadline)?;
+                }
+
+                return Ok(stream);
             }
 
             // Attempt to make a new connection
@@ -169,19 +282,66 @@ impl ConnectionPool {
                     pool: self.inner.clone(),
                     wait_lock: self.wait_lock.clone(),
                     iteration: locked.iteration,
+                    host: self.host.clone(),
                     successful_handshake: false,
+                    discarded: false,
+                    read_deadline: None,
+                    max_wire_version: 0,
+                    max_bson_object_size: 0,
+                    max_message_size_bytes: 0,
+                    max_write_batch_size: 0,
                 };
 
+                if let Some(deadline) = deadline {
+                    stream.set_read_deadline(deadline)?;
+                }
+
                 self.handshake(client, &mut stream)?;
                 let _ = locked.len.fetch_add(1, Ordering::SeqCst);
                 return Ok(stream);
             }
 
             // Release lock and wait for pool to be repopulated
-            locked = self.wait_lock.wait(locked)?;
+            locked = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(TimeoutError);
+                    }
+
+                    self.wait_lock.wait_timeout(locked, deadline - now)?.0
+                }
+                None => self.wait_lock.wait(locked)?,
+            };
         }
     }
 
+    /// Takes an idle socket from the pool without connecting or waiting, returning `None` if
+    /// none is available.
+    pub fn try_acquire_idle_stream(&self) -> Option<PooledStream> {
+        let mut locked = match self.inner.try_lock() {
+            Ok(locked) => locked,
+            Err(_) => return None,
+        };
+
+        let socket = locked.sockets.pop()?;
+
+        Some(PooledStream {
+            socket: Some(socket),
+            pool: self.inner.clone(),
+            wait_lock: self.wait_lock.clone(),
+            iteration: locked.iteration,
+            host: self.host.clone(),
+            successful_handshake: true,
+            discarded: false,
+            read_deadline: None,
+            max_wire_version: 0,
+            max_bson_object_size: 0,
+            max_message_size_bytes: 0,
+            max_write_batch_size: 0,
+        })
+    }
+
     // Connects to a MongoDB server as defined by the initial configuration.
     fn connect(&self) -> Result<BufStream<Stream>> {
         match self.stream_connector.connect(
@@ -193,7 +353,9 @@ impl ConnectionPool {
         }
     }
 
-    // This sends the client metadata to the server as described by the handshake spec.
+    // This sends the client metadata to the server as described by the handshake spec. The
+    // legacy isMaster command is used since the server may not support `hello`; `helloOk` asks
+    // servers that do to say so in their reply.
     //
     // See https://github.com/mongodb/specifications/blob/master/source/mongodb-handshake/handshake.rst
     fn handshake(&self, client: Client, stream: &mut PooledStream) -> Result<()> {
@@ -202,29 +364,21 @@ impl ConnectionPool {
         options.batch_size = Some(1);
 
         let flags = OpQueryFlags::with_find_options(&options);
+        let command = doc! {
+            "isMaster": 1i32,
+            "helloOk": true,
+            "client": client.client_metadata.clone(),
+        };
 
         Cursor::query_with_stream(
             stream,
             client,
-            String::from("local.$cmd"),
+            String::from("admin.$cmd"),
             flags,
-            doc! {
-                "isMaster": 1i32,
-                "client": {
-                    "driver": {
-                        "name": ::DRIVER_NAME,
-                        "version": env!("CARGO_PKG_VERSION"),
-                    },
-                    "os": {
-                        "type": ::std::env::consts::OS,
-                        "architecture": ::std::env::consts::ARCH
-                    }
-                },
-            },
+            command,
             options,
             CommandType::IsMaster,
             false,
-            None,
         )?;
 
         stream.successful_handshake = true;
@@ -0,0 +1,671 @@
+//! Typed builders for query filters and update documents.
+//!
+//! Filters are built from field conditions and combined with `and`, `or` and `nor`; both filters
+//! and updates convert into the `bson::Document` values taken by `Collection` methods:
+//!
+//! ```ignore
+//! let filter = field("age").gte(18).and(field("tags").in_(vec!["admin", "staff"]));
+//! let update = Update::new().set("active", true).inc("logins", 1);
+//!
+//! coll.update_many(filter.into(), update, None)?;
+//! ```
+use bson::{self, Bson, bson, doc};
+use coll::options::UpdateModifications;
+
+/// Starts a condition on the named field, which may be a dotted path into embedded documents.
+pub fn field(name: &str) -> Field {
+    Field {
+        name: String::from(name),
+        operators: bson::Document::new(),
+    }
+}
+
+/// Matches documents that satisfy every one of the filters.
+pub fn and<F: Into<Filter>>(filters: Vec<F>) -> Filter {
+    Filter::logical("$and", filters)
+}
+
+/// Matches documents that satisfy at least one of the filters.
+pub fn or<F: Into<Filter>>(filters: Vec<F>) -> Filter {
+    Filter::logical("$or", filters)
+}
+
+/// Matches documents that satisfy none of the filters.
+pub fn nor<F: Into<Filter>>(filters: Vec<F>) -> Filter {
+    Filter::logical("$nor", filters)
+}
+
+/// Matches documents whose text index content matches the search string.
+pub fn text(search: &str) -> Filter {
+    Filter::from(doc! { "$text": { "$search": search } })
+}
+
+/// A BSON type, as matched by the `$type` operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BsonType {
+    Double,
+    String,
+    Object,
+    Array,
+    BinData,
+    ObjectId,
+    Bool,
+    Date,
+    Null,
+    Regex,
+    JavaScript,
+    Int,
+    Timestamp,
+    Long,
+    Decimal,
+    MinKey,
+    MaxKey,
+    /// Any numeric type.
+    Number,
+}
+
+impl BsonType {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            BsonType::Double => "double",
+            BsonType::String => "string",
+            BsonType::Object => "object",
+            BsonType::Array => "array",
+            BsonType::BinData => "binData",
+            BsonType::ObjectId => "objectId",
+            BsonType::Bool => "bool",
+            BsonType::Date => "date",
+            BsonType::Null => "null",
+            BsonType::Regex => "regex",
+            BsonType::JavaScript => "javascript",
+            BsonType::Int => "int",
+            BsonType::Timestamp => "timestamp",
+            BsonType::Long => "long",
+            BsonType::Decimal => "decimal",
+            BsonType::MinKey => "minKey",
+            BsonType::MaxKey => "maxKey",
+            BsonType::Number => "number",
+        }
+    }
+}
+
+/// A GeoJSON geometry with `[longitude, latitude]` coordinates.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Geometry {
+    Point([f64; 2]),
+    LineString(Vec<[f64; 2]>),
+    /// An exterior ring followed by any interior rings, each closed by repeating its first
+    /// position.
+    Polygon(Vec<Vec<[f64; 2]>>),
+    MultiPolygon(Vec<Vec<Vec<[f64; 2]>>>),
+}
+
+impl Geometry {
+    pub fn to_bson(&self) -> bson::Document {
+        let (geometry_type, coordinates) = match *self {
+            Geometry::Point(ref position) => ("Point", position_to_bson(position)),
+            Geometry::LineString(ref positions) => ("LineString", positions_to_bson(positions)),
+            Geometry::Polygon(ref rings) => ("Polygon", rings_to_bson(rings)),
+            Geometry::MultiPolygon(ref polygons) => {
+                let polygons = polygons.iter().map(|rings| rings_to_bson(rings)).collect();
+                ("MultiPolygon", Bson::Array(polygons))
+            }
+        };
+
+        doc! {
+            "type": geometry_type,
+            "coordinates": coordinates,
+        }
+    }
+}
+
+fn position_to_bson(position: &[f64; 2]) -> Bson {
+    Bson::Array(vec![Bson::FloatingPoint(position[0]), Bson::FloatingPoint(position[1])])
+}
+
+fn positions_to_bson(positions: &[[f64; 2]]) -> Bson {
+    Bson::Array(positions.iter().map(position_to_bson).collect())
+}
+
+fn rings_to_bson(rings: &[Vec<[f64; 2]>]) -> Bson {
+    Bson::Array(rings.iter().map(|ring| positions_to_bson(ring)).collect())
+}
+
+fn array<T: Into<Bson>, I: IntoIterator<Item = T>>(values: I) -> Bson {
+    Bson::Array(values.into_iter().map(Into::into).collect())
+}
+
+/// The query operators applied to a single field.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    name: String,
+    operators: bson::Document,
+}
+
+impl Field {
+    fn operator<B: Into<Bson>>(mut self, operator: &str, value: B) -> Field {
+        self.operators.insert(operator, value.into());
+        self
+    }
+
+    pub fn eq<B: Into<Bson>>(self, value: B) -> Field {
+        self.operator("$eq", value)
+    }
+
+    pub fn ne<B: Into<Bson>>(self, value: B) -> Field {
+        self.operator("$ne", value)
+    }
+
+    pub fn gt<B: Into<Bson>>(self, value: B) -> Field {
+        self.operator("$gt", value)
+    }
+
+    pub fn gte<B: Into<Bson>>(self, value: B) -> Field {
+        self.operator("$gte", value)
+    }
+
+    pub fn lt<B: Into<Bson>>(self, value: B) -> Field {
+        self.operator("$lt", value)
+    }
+
+    pub fn lte<B: Into<Bson>>(self, value: B) -> Field {
+        self.operator("$lte", value)
+    }
+
+    pub fn in_<B: Into<Bson>, I: IntoIterator<Item = B>>(self, values: I) -> Field {
+        self.operator("$in", array(values))
+    }
+
+    pub fn nin<B: Into<Bson>, I: IntoIterator<Item = B>>(self, values: I) -> Field {
+        self.operator("$nin", array(values))
+    }
+
+    pub fn exists(self, exists: bool) -> Field {
+        self.operator("$exists", exists)
+    }
+
+    pub fn type_(self, bson_type: BsonType) -> Field {
+        self.operator("$type", bson_type.as_str())
+    }
+
+    /// Matches strings against a regular expression, with options such as `i`, `m`, `x` and
+    /// `s` given as a string.
+    pub fn regex(self, pattern: &str, options: &str) -> Field {
+        let field = self.operator("$regex", pattern);
+        if options.is_empty() {
+            field
+        } else {
+            field.operator("$options", options)
+        }
+    }
+
+    pub fn mod_(self, divisor: i64, remainder: i64) -> Field {
+        self.operator("$mod", Bson::Array(vec![Bson::I64(divisor), Bson::I64(remainder)]))
+    }
+
+    pub fn all<B: Into<Bson>, I: IntoIterator<Item = B>>(self, values: I) -> Field {
+        self.operator("$all", array(values))
+    }
+
+    pub fn size(self, size: i32) -> Field {
+        self.operator("$size", size)
+    }
+
+    /// Matches arrays with at least one embedded document that satisfies the filter.
+    pub fn elem_match<F: Into<Filter>>(self, filter: F) -> Field {
+        self.operator("$elemMatch", filter.into().to_bson())
+    }
+
+    /// Matches arrays with at least one value that satisfies all of the given conditions,
+    /// which are built on a field with an empty name:
+    ///
+    /// ```ignore
+    /// field("scores").elem_match_values(|score| score.gte(80).lt(85))
+    /// ```
+    pub fn elem_match_values<F: FnOnce(Field) -> Field>(self, condition: F) -> Field {
+        let operators = condition(field("")).operators;
+        self.operator("$elemMatch", operators)
+    }
+
+    /// Matches documents where the conditions built by the closure do not hold, including
+    /// documents without the field.
+    pub fn not<F: FnOnce(Field) -> Field>(self, condition: F) -> Field {
+        let operators = condition(field("")).operators;
+        self.operator("$not", operators)
+    }
+
+    /// Matches geometries that are within the given GeoJSON geometry.
+    pub fn geo_within(self, geometry: Geometry) -> Field {
+        self.operator("$geoWithin", doc! { "$geometry": geometry.to_bson() })
+    }
+
+    /// Matches legacy coordinate pairs within a box given by its bottom-left and top-right
+    /// corners.
+    pub fn geo_within_box(self, bottom_left: [f64; 2], top_right: [f64; 2]) -> Field {
+        let corners = Bson::Array(vec![
+            position_to_bson(&bottom_left),
+            position_to_bson(&top_right),
+        ]);
+
+        self.operator("$geoWithin", doc! { "$box": corners })
+    }
+
+    /// Matches points within a circle on a sphere, with the radius given in radians.
+    pub fn geo_within_center_sphere(self, center: [f64; 2], radius: f64) -> Field {
+        let circle = Bson::Array(vec![position_to_bson(&center), Bson::FloatingPoint(radius)]);
+        self.operator("$geoWithin", doc! { "$centerSphere": circle })
+    }
+
+    /// Matches geometries that intersect the given GeoJSON geometry.
+    pub fn geo_intersects(self, geometry: Geometry) -> Field {
+        self.operator("$geoIntersects", doc! { "$geometry": geometry.to_bson() })
+    }
+
+    /// Sorts documents by their distance from a point, optionally bounded in meters. Requires a
+    /// geospatial index.
+    pub fn near(self, point: [f64; 2], min_distance: Option<f64>, max_distance: Option<f64>)
+        -> Field {
+        self.near_operator("$near", point, min_distance, max_distance)
+    }
+
+    /// Like `near`, but calculates distances on a sphere.
+    pub fn near_sphere(
+        self,
+        point: [f64; 2],
+        min_distance: Option<f64>,
+        max_distance: Option<f64>,
+    ) -> Field {
+        self.near_operator("$nearSphere", point, min_distance, max_distance)
+    }
+
+    fn near_operator(
+        self,
+        operator: &str,
+        point: [f64; 2],
+        min_distance: Option<f64>,
+        max_distance: Option<f64>,
+    ) -> Field {
+        let mut near = doc! { "$geometry": Geometry::Point(point).to_bson() };
+
+        if let Some(min_distance) = min_distance {
+            near.insert("$minDistance", min_distance);
+        }
+
+        if let Some(max_distance) = max_distance {
+            near.insert("$maxDistance", max_distance);
+        }
+
+        self.operator(operator, near)
+    }
+
+    /// Combines this condition with another filter.
+    pub fn and<F: Into<Filter>>(self, other: F) -> Filter {
+        Filter::from(self).and(other)
+    }
+
+    /// Matches documents that satisfy either this condition or the other filter.
+    pub fn or<F: Into<Filter>>(self, other: F) -> Filter {
+        Filter::from(self).or(other)
+    }
+
+    pub fn to_bson(&self) -> bson::Document {
+        let mut document = bson::Document::new();
+        document.insert(self.name.clone(), self.operators.clone());
+        document
+    }
+}
+
+/// A query filter.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Filter {
+    document: bson::Document,
+}
+
+impl Filter {
+    /// Creates an empty filter, which matches every document.
+    pub fn new() -> Filter {
+        Default::default()
+    }
+
+    fn logical<F: Into<Filter>>(operator: &str, filters: Vec<F>) -> Filter {
+        let clauses = filters
+            .into_iter()
+            .map(|filter| Bson::Document(filter.into().document))
+            .collect();
+
+        let mut document = bson::Document::new();
+        document.insert(operator, Bson::Array(clauses));
+        Filter { document: document }
+    }
+
+    // Appends a clause to this filter's logical operator if that is all it holds, so that
+    // chained calls produce one flat list. An empty filter is treated as a starting point for
+    // either operator, so `Filter::new().or(a).or(b)` matches `a` or `b`.
+    fn combine<F: Into<Filter>>(self, operator: &str, other: F) -> Filter {
+        let other = other.into();
+
+        if self.document.is_empty() {
+            return other;
+        }
+
+        let mut document = self.document;
+        if document.len() == 1 {
+            if let Some(&mut Bson::Array(ref mut clauses)) = document.get_mut(operator) {
+                clauses.push(Bson::Document(other.document));
+                return Filter { document: document };
+            }
+        }
+
+        Filter::logical(operator, vec![Filter { document: document }, other])
+    }
+
+    pub fn and<F: Into<Filter>>(self, other: F) -> Filter {
+        self.combine("$and", other)
+    }
+
+    pub fn or<F: Into<Filter>>(self, other: F) -> Filter {
+        self.combine("$or", other)
+    }
+
+    pub fn to_bson(&self) -> bson::Document {
+        self.document.clone()
+    }
+}
+
+impl From<Field> for Filter {
+    fn from(field: Field) -> Self {
+        Filter { document: field.to_bson() }
+    }
+}
+
+impl From<bson::Document> for Filter {
+    fn from(document: bson::Document) -> Self {
+        Filter { document: document }
+    }
+}
+
+impl From<Filter> for bson::Document {
+    fn from(filter: Filter) -> Self {
+        filter.document
+    }
+}
+
+impl From<Field> for bson::Document {
+    fn from(field: Field) -> Self {
+        field.to_bson()
+    }
+}
+
+/// A document of update operators.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Update {
+    document: bson::Document,
+}
+
+impl Update {
+    pub fn new() -> Update {
+        Default::default()
+    }
+
+    // Adds a field to the document of the given update operator.
+    fn operator<B: Into<Bson>>(mut self, operator: &str, field: &str, value: B) -> Update {
+        if !self.document.contains_key(operator) {
+            self.document.insert(operator, bson::Document::new());
+        }
+
+        if let Some(&mut Bson::Document(ref mut fields)) = self.document.get_mut(operator) {
+            fields.insert(field, value.into());
+        }
+
+        self
+    }
+
+    pub fn set<B: Into<Bson>>(self, field: &str, value: B) -> Update {
+        self.operator("$set", field, value)
+    }
+
+    /// Sets the field only when an upsert inserts a new document.
+    pub fn set_on_insert<B: Into<Bson>>(self, field: &str, value: B) -> Update {
+        self.operator("$setOnInsert", field, value)
+    }
+
+    pub fn unset(self, field: &str) -> Update {
+        self.operator("$unset", field, "")
+    }
+
+    pub fn inc<B: Into<Bson>>(self, field: &str, amount: B) -> Update {
+        self.operator("$inc", field, amount)
+    }
+
+    pub fn mul<B: Into<Bson>>(self, field: &str, factor: B) -> Update {
+        self.operator("$mul", field, factor)
+    }
+
+    /// Sets the field to the value if the value is less than the current one.
+    pub fn min<B: Into<Bson>>(self, field: &str, value: B) -> Update {
+        self.operator("$min", field, value)
+    }
+
+    /// Sets the field to the value if the value is greater than the current one.
+    pub fn max<B: Into<Bson>>(self, field: &str, value: B) -> Update {
+        self.operator("$max", field, value)
+    }
+
+    pub fn rename(self, field: &str, new_name: &str) -> Update {
+        self.operator("$rename", field, new_name)
+    }
+
+    /// Sets the field to the current date.
+    pub fn current_date(self, field: &str) -> Update {
+        self.operator("$currentDate", field, true)
+    }
+
+    /// Sets the field to the current time as a BSON timestamp.
+    pub fn current_timestamp(self, field: &str) -> Update {
+        self.operator("$currentDate", field, doc! { "$type": "timestamp" })
+    }
+
+    pub fn push<B: Into<Bson>>(self, field: &str, value: B) -> Update {
+        self.operator("$push", field, value)
+    }
+
+    /// Appends each of the values to an array.
+    pub fn push_each<B: Into<Bson>, I: IntoIterator<Item = B>>(self, field: &str, values: I)
+        -> Update {
+        self.operator("$push", field, doc! { "$each": array(values) })
+    }
+
+    /// Adds the value to an array unless it is already present.
+    pub fn add_to_set<B: Into<Bson>>(self, field: &str, value: B) -> Update {
+        self.operator("$addToSet", field, value)
+    }
+
+    /// Adds each of the values to an array unless it is already present.
+    pub fn add_to_set_each<B: Into<Bson>, I: IntoIterator<Item = B>>(
+        self,
+        field: &str,
+        values: I,
+    ) -> Update {
+        self.operator("$addToSet", field, doc! { "$each": array(values) })
+    }
+
+    /// Removes all array elements equal to the value.
+    pub fn pull<B: Into<Bson>>(self, field: &str, value: B) -> Update {
+        self.operator("$pull", field, value)
+    }
+
+    /// Removes all array elements that match the filter, which is built from conditions on the
+    /// elements' own fields or, for scalar elements, on a field with an empty name.
+    pub fn pull_matching<F: Into<Filter>>(self, field: &str, filter: F) -> Update {
+        let mut filter = filter.into().document;
+
+        // A condition on the elements themselves is given without a field name.
+        if let Some(Bson::Document(operators)) = filter.remove("") {
+            if filter.is_empty() {
+                return self.operator("$pull", field, operators);
+            }
+            filter.insert("", operators);
+        }
+
+        self.operator("$pull", field, filter)
+    }
+
+    pub fn pull_all<B: Into<Bson>, I: IntoIterator<Item = B>>(self, field: &str, values: I)
+        -> Update {
+        self.operator("$pullAll", field, array(values))
+    }
+
+    /// Removes the first element of an array.
+    pub fn pop_first(self, field: &str) -> Update {
+        self.operator("$pop", field, -1)
+    }
+
+    /// Removes the last element of an array.
+    pub fn pop_last(self, field: &str) -> Update {
+        self.operator("$pop", field, 1)
+    }
+
+    pub fn to_bson(&self) -> bson::Document {
+        self.document.clone()
+    }
+}
+
+impl From<Update> for bson::Document {
+    fn from(update: Update) -> Self {
+        update.document
+    }
+}
+
+impl From<Update> for UpdateModifications {
+    fn from(update: Update) -> Self {
+        UpdateModifications::Document(update.document)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn field_conditions() {
+        assert_eq!(doc!{"age": {"$gte": 18, "$lt": 65}}, field("age").gte(18).lt(65).to_bson());
+        assert_eq!(doc!{"tags": {"$in": ["a", "b"]}}, field("tags").in_(vec!["a", "b"]).to_bson());
+        assert_eq!(doc!{"x": {"$exists": false}}, field("x").exists(false).to_bson());
+        assert_eq!(doc!{"x": {"$type": "long"}}, field("x").type_(BsonType::Long).to_bson());
+        assert_eq!(doc!{"x": {"$mod": [4i64, 0i64]}}, field("x").mod_(4, 0).to_bson());
+        assert_eq!(
+            doc!{"name": {"$regex": "^ab", "$options": "i"}},
+            field("name").regex("^ab", "i").to_bson()
+        );
+        assert_eq!(doc!{"name": {"$regex": "^ab"}}, field("name").regex("^ab", "").to_bson());
+        assert_eq!(
+            doc!{"price": {"$not": {"$gt": 1.99}}},
+            field("price").not(|price| price.gt(1.99)).to_bson()
+        );
+    }
+
+    #[test]
+    fn elem_match() {
+        assert_eq!(
+            doc!{"results": {"$elemMatch": {"product": "xyz"}}},
+            field("results").elem_match(doc!{"product": "xyz"}).to_bson()
+        );
+        assert_eq!(
+            doc!{"results": {"$elemMatch": {"$and": [
+                {"product": {"$eq": "xyz"}},
+                {"score": {"$gte": 8}},
+            ]}}},
+            field("results")
+                .elem_match(field("product").eq("xyz").and(field("score").gte(8)))
+                .to_bson()
+        );
+        assert_eq!(
+            doc!{"scores": {"$elemMatch": {"$gte": 80, "$lt": 85}}},
+            field("scores").elem_match_values(|score| score.gte(80).lt(85)).to_bson()
+        );
+    }
+
+    #[test]
+    fn logical_operators() {
+        let filter = field("a").eq(1).and(field("b").eq(2)).and(field("c").eq(3));
+        assert_eq!(
+            doc!{"$and": [{"a": {"$eq": 1}}, {"b": {"$eq": 2}}, {"c": {"$eq": 3}}]},
+            bson::Document::from(filter)
+        );
+
+        let filter = or(vec![field("a").eq(1), field("b").eq(2)]);
+        assert_eq!(
+            doc!{"$or": [{"a": {"$eq": 1}}, {"b": {"$eq": 2}}]},
+            bson::Document::from(filter)
+        );
+
+        assert_eq!(
+            doc!{"$nor": [{"a": {"$eq": 1}}]},
+            bson::Document::from(nor(vec![field("a").eq(1)]))
+        );
+        assert_eq!(doc!{"a": {"$eq": 1}}, Filter::new().and(field("a").eq(1)).to_bson());
+    }
+
+    #[test]
+    fn or_on_empty_filter() {
+        assert_eq!(doc!{"a": {"$eq": 1}}, Filter::new().or(field("a").eq(1)).to_bson());
+        assert_eq!(
+            doc!{"$or": [{"a": {"$eq": 1}}, {"b": {"$eq": 2}}]},
+            Filter::new().or(field("a").eq(1)).or(field("b").eq(2)).to_bson()
+        );
+    }
+
+    #[test]
+    fn geo_operators() {
+        let polygon = Geometry::Polygon(vec![
+            vec![[0.0, 0.0], [3.0, 6.0], [6.0, 1.0], [0.0, 0.0]],
+        ]);
+
+        assert_eq!(
+            doc!{"loc": {"$geoWithin": {"$geometry": {
+                "type": "Polygon",
+                "coordinates": [[[0.0, 0.0], [3.0, 6.0], [6.0, 1.0], [0.0, 0.0]]],
+            }}}},
+            field("loc").geo_within(polygon).to_bson()
+        );
+        assert_eq!(
+            doc!{"loc": {"$near": {
+                "$geometry": {"type": "Point", "coordinates": [-73.9, 40.7]},
+                "$maxDistance": 500.0,
+            }}},
+            field("loc").near([-73.9, 40.7], None, Some(500.0)).to_bson()
+        );
+        assert_eq!(
+            doc!{"loc": {"$geoWithin": {"$box": [[0.0, 0.0], [100.0, 100.0]]}}},
+            field("loc").geo_within_box([0.0, 0.0], [100.0, 100.0]).to_bson()
+        );
+    }
+
+    #[test]
+    fn update_operators() {
+        let update = Update::new()
+            .set("a", 1)
+            .set("b", "x")
+            .inc("n", 2)
+            .unset("old")
+            .rename("nmae", "name")
+            .current_date("modified")
+            .push_each("tags", vec!["a", "b"])
+            .add_to_set("set", 5)
+            .pull_matching("scores", field("").lt(50));
+
+        assert_eq!(
+            doc!{
+                "$set": {"a": 1, "b": "x"},
+                "$inc": {"n": 2},
+                "$unset": {"old": ""},
+                "$rename": {"nmae": "name"},
+                "$currentDate": {"modified": true},
+                "$push": {"tags": {"$each": ["a", "b"]}},
+                "$addToSet": {"set": 5},
+                "$pull": {"scores": {"$lt": 50}},
+            },
+            bson::Document::from(update)
+        );
+    }
+}
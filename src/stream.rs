@@ -2,6 +2,7 @@ use std::io::{BufReader, Read, Result, Write};
 #[cfg(feature = "ssl")]
 use std::io::{Error, ErrorKind};
 use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
 
 #[cfg(feature = "ssl")]
 use openssl::ssl::{Ssl, SslContext, SslFiletype, SslMethod, SslOptions, SslStream, SslVerifyMode};
@@ -196,4 +197,13 @@ impl Stream {
             Stream::Ssl(ref stream) => stream.get_ref().peer_addr(),
         }
     }
+
+    /// Sets the read timeout of the underlying socket; `None` blocks indefinitely.
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> Result<()> {
+        match *self {
+            Stream::Tcp { ref write_half, .. } => write_half.set_read_timeout(timeout),
+            #[cfg(feature = "ssl")]
+            Stream::Ssl(ref stream) => stream.get_ref().set_read_timeout(timeout),
+        }
+    }
 }
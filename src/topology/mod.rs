@@ -3,7 +3,7 @@ pub mod server;
 pub mod monitor;
 
 use {Client, Result};
-use Error::{self, ArgumentError, OperationError};
+use Error::{self, ArgumentError, OperationError, TimeoutError};
 
 use bson::oid;
 
@@ -14,13 +14,14 @@ use stream::StreamConnector;
 
 use rand::{thread_rng, Rng};
 
+use std::cmp;
 use std::collections::HashMap;
 use std::fmt;
 use std::i64;
 use std::str::FromStr;
 use std::sync::{Arc, RwLock};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use time;
 
 use self::server::{Server, ServerDescription, ServerType};
@@ -57,6 +58,9 @@ pub struct TopologyDescription {
     /// This defines how long to block for server selection before
     /// returning an error. The default is 30 seconds.
     pub server_selection_timeout_ms: i64,
+    /// The client-side time limit for each operation, bounding server selection, connection
+    /// checkout, and the wait for a reply. There is no limit by default.
+    pub timeout_ms: Option<i64>,
     // The largest election id seen from a server in the topology.
     max_election_id: Option<oid::ObjectId>,
     // If true, all servers in the topology fall within the compatible
@@ -77,6 +81,7 @@ impl fmt::Debug for TopologyDescription {
             .field("heartbeat_frequency_ms", &self.heartbeat_frequency_ms)
             .field("local_threshold_ms", &self.local_threshold_ms)
             .field("server_selection_timeout_ms", &self.server_selection_timeout_ms)
+            .field("timeout_ms", &self.timeout_ms)
             .field("max_election_id", &self.max_election_id)
             .field("compatible", &self.compatible)
             .field("max_set_version", &self.max_set_version)
@@ -116,6 +121,7 @@ impl Default for TopologyDescription {
             set_name: String::new(),
             heartbeat_frequency_ms: DEFAULT_HEARTBEAT_FREQUENCY_MS,
             server_selection_timeout_ms: DEFAULT_SERVER_SELECTION_TIMEOUT_MS,
+            timeout_ms: None,
             local_threshold_ms: DEFAULT_LOCAL_THRESHOLD_MS,
             servers: HashMap::new(),
             max_election_id: None,
@@ -134,7 +140,12 @@ impl TopologyDescription {
     }
 
     /// Returns the nearest server stream, calculated by round trip time.
-    fn get_nearest_from_vec(&self, client: Client, servers: &mut Vec<Host>) -> Result<(PooledStream, ServerType)> {
+    fn get_nearest_from_vec(
+        &self,
+        client: Client,
+        servers: &mut Vec<Host>,
+        deadline: Option<Instant>,
+    ) -> Result<(PooledStream, ServerType)> {
         servers.sort_by(|a, b| {
             let mut a_rtt = i64::MAX;
             let mut b_rtt = i64::MAX;
@@ -158,7 +169,7 @@ impl TopologyDescription {
                 if let Ok(description) = server.description.read() {
                     if description.round_trip_time.is_none() {
                         break;
-                    } else if let Ok(stream) = server.acquire_stream(client.clone()) {
+                    } else if let Ok(stream) = server.acquire_stream(client.clone(), deadline) {
                         return Ok((stream, description.server_type));
                     }
                 }
@@ -170,13 +181,18 @@ impl TopologyDescription {
     }
 
     /// Returns a random server stream from the vector.
-    fn get_rand_from_vec(&self, client: Client, servers: &mut Vec<Host>) -> Result<(PooledStream, ServerType)> {
+    fn get_rand_from_vec(
+        &self,
+        client: Client,
+        servers: &mut Vec<Host>,
+        deadline: Option<Instant>,
+    ) -> Result<(PooledStream, ServerType)> {
         while !servers.is_empty() {
             let len = servers.len();
             let index = thread_rng().gen_range(0, len);
 
             if let Some(server) = self.servers.get(&servers[index]) {
-                if let Ok(stream) = server.acquire_stream(client.clone()) {
+                if let Ok(stream) = server.acquire_stream(client.clone(), deadline) {
                     if let Ok(description) = server.description.read() {
                         return Ok((stream, description.server_type));
                     }
@@ -194,6 +210,7 @@ impl TopologyDescription {
         &self,
         client: Client,
         read_preference: &ReadPreference,
+        deadline: Option<Instant>,
     ) -> Result<(PooledStream, bool, bool)> {
         let (mut hosts, rand) = self.choose_hosts(read_preference)?;
 
@@ -211,7 +228,7 @@ impl TopologyDescription {
                 mode: ReadMode::PrimaryPreferred,
                 ..read_preference.clone()
             };
-            return self.acquire_stream(client, &read_pref);
+            return self.acquire_stream(client, &read_pref, deadline);
         }
 
         // If no servers are available, request an update from all monitors.
@@ -226,9 +243,9 @@ impl TopologyDescription {
 
         // Retrieve a server stream from the list of acceptable hosts.
         let (pooled_stream, server_type) = if rand {
-            self.get_rand_from_vec(client, &mut hosts)?
+            self.get_rand_from_vec(client, &mut hosts, deadline)?
         } else {
-            self.get_nearest_from_vec(client, &mut hosts)?
+            self.get_nearest_from_vec(client, &mut hosts, deadline)?
         };
 
         // Determine how to handle server-side logic based on ReadMode and TopologyType.
@@ -271,8 +288,59 @@ impl TopologyDescription {
         Ok((pooled_stream, slave_ok, send_read_pref))
     }
 
+    /// Returns a stream to a specific server, provided that the server is still part of the
+    /// topology and its connection pool has not been cleared since `iteration`.
+    pub fn acquire_stream_for_host(
+        &self,
+        client: Client,
+        host: &Host,
+        iteration: usize,
+        deadline: Option<Instant>,
+    ) -> Result<PooledStream> {
+        let server = match self.servers.get(host) {
+            Some(server) => server,
+            None => {
+                return Err(OperationError(format!(
+                    "The server {}:{} is no longer part of the topology.",
+                    host.host_name,
+                    host.port
+                )))
+            }
+        };
+
+        let stream = server.acquire_stream(client, deadline)?;
+        if stream.iteration() != iteration {
+            return Err(OperationError(format!(
+                "The connection pool for server {}:{} has been cleared.",
+                host.host_name,
+                host.port
+            )));
+        }
+
+        Ok(stream)
+    }
+
+    /// Returns an idle stream to a specific host without connecting or waiting, if one is
+    /// available and the host's connection pool has not been cleared since `iteration`.
+    pub fn try_acquire_idle_stream_for_host(
+        &self,
+        host: &Host,
+        iteration: usize,
+    ) -> Option<PooledStream> {
+        let stream = self.servers.get(host)?.try_acquire_idle_stream()?;
+        if stream.iteration() != iteration {
+            return None;
+        }
+
+        Some(stream)
+    }
+
     /// Returns a server stream for write operations.
-    pub fn acquire_write_stream(&self, client: Client) -> Result<PooledStream> {
+    pub fn acquire_write_stream(
+        &self,
+        client: Client,
+        deadline: Option<Instant>,
+    ) -> Result<PooledStream> {
         let (mut hosts, rand) = self.choose_write_hosts();
 
         // If no servers are available, request an update from all monitors.
@@ -283,12 +351,25 @@ impl TopologyDescription {
         }
 
         if rand {
-            Ok(self.get_rand_from_vec(client, &mut hosts)?.0)
+            Ok(self.get_rand_from_vec(client, &mut hosts, deadline)?.0)
         } else {
-            Ok(self.get_nearest_from_vec(client, &mut hosts)?.0)
+            Ok(self.get_nearest_from_vec(client, &mut hosts, deadline)?.0)
         }
     }
 
+    /// Returns the description of a discovered server that is suitable for write operations,
+    /// if there is one.
+    pub fn write_server_description(&self) -> Option<ServerDescription> {
+        let (hosts, _) = self.choose_write_hosts();
+
+        hosts
+            .iter()
+            .filter_map(|host| self.servers.get(host))
+            .filter_map(|server| server.description.read().ok())
+            .find(|description| description.server_type != ServerType::Unknown)
+            .map(|description| description.clone())
+    }
+
     /// Filters a given set of hosts based on the provided read preference tag sets.
     pub fn filter_hosts(&self, hosts: &mut Vec<Host>, read_preference: &ReadPreference) {
         let mut tag_filter = None;
@@ -912,10 +993,11 @@ impl Topology {
         // Note start of server selection.
         let time = time::get_time();
         let start_ms = time.sec * 1000 + (time.nsec as i64) / 1000000;
+        let deadline = self.operation_deadline()?;
 
         loop {
             let result = if write {
-                match self.description.read()?.acquire_write_stream(client.clone()) {
+                match self.description.read()?.acquire_write_stream(client.clone(), deadline) {
                     Ok(stream) => Ok((stream, false, false)),
                     Err(err) => Err(err),
                 }
@@ -923,12 +1005,20 @@ impl Topology {
                 self.description.read()?.acquire_stream(
                     client.clone(),
                     read_preference.as_ref().unwrap(),
+                    deadline,
                 )
             };
 
             match result {
                 Ok(stream) => return Ok(stream),
                 Err(err) => {
+                    // The client timeout takes precedence over the server selection timeout.
+                    if let Some(deadline) = deadline {
+                        if Instant::now() >= deadline {
+                            return Err(TimeoutError);
+                        }
+                    }
+
                     // Check duration of current server selection and return an error if
                     // overdue.
                     let end_time = time::get_time();
@@ -939,11 +1029,22 @@ impl Topology {
                 }
             };
 
-            // Otherwise, sleep for a little while.
-            thread::sleep(Duration::from_millis(500));
+            // Otherwise, sleep for a little while, without sleeping past the deadline.
+            let mut pause = Duration::from_millis(500);
+            if let Some(deadline) = deadline {
+                pause = cmp::min(pause, deadline.saturating_duration_since(Instant::now()));
+            }
+
+            thread::sleep(pause);
         }
     }
 
+    // Returns the point in time at which an operation starting now exceeds the client timeout.
+    fn operation_deadline(&self) -> Result<Option<Instant>> {
+        let timeout_ms = self.description.read()?.timeout_ms;
+        Ok(timeout_ms.map(|ms| Instant::now() + Duration::from_millis(ms as u64)))
+    }
+
     /// Returns a server stream for read operations.
     pub fn acquire_stream(
         &self,
@@ -953,6 +1054,34 @@ impl Topology {
         self.acquire_stream_private(client, Some(read_preference), false)
     }
 
+    /// Returns a server stream to a specific host, without performing server selection.
+    pub fn acquire_stream_for_host(
+        &self,
+        client: Client,
+        host: &Host,
+        iteration: usize,
+    ) -> Result<PooledStream> {
+        let deadline = self.operation_deadline()?;
+        self.description.read()?.acquire_stream_for_host(client, host, iteration, deadline)
+    }
+
+    /// Returns an idle stream to a specific host without connecting or waiting, if one is
+    /// available.
+    pub fn try_acquire_idle_stream_for_host(
+        &self,
+        host: &Host,
+        iteration: usize,
+    ) -> Option<PooledStream> {
+        let description = self.description.try_read().ok()?;
+        description.try_acquire_idle_stream_for_host(host, iteration)
+    }
+
+    /// Returns the description of a discovered server that is suitable for write operations,
+    /// without acquiring a stream to it.
+    pub fn write_server_description(&self) -> Option<ServerDescription> {
+        self.description.read().ok()?.write_server_description()
+    }
+
     /// Returns a server stream for write operations.
     pub fn acquire_write_stream(&self, client: Client) -> Result<PooledStream> {
         let (stream, _, _) = self.acquire_stream_private(client, None, true)?;
@@ -1,4 +1,4 @@
-//! Asynchronous server and topology discovery and monitoring using isMaster results.
+//! Asynchronous server and topology discovery and monitoring using hello and isMaster results.
 use {Client, Result};
 use Error::{self, ArgumentError, OperationError};
 
@@ -24,16 +24,23 @@ use time;
 use super::server::{ServerDescription, ServerType};
 use super::{DEFAULT_HEARTBEAT_FREQUENCY_MS, TopologyDescription};
 
-const DEFAULT_MAX_BSON_OBJECT_SIZE: i64 = 16 * 1024 * 1024;
-const DEFAULT_MAX_MESSAGE_SIZE_BYTES: i64 = 48000000;
+pub const DEFAULT_MAX_BSON_OBJECT_SIZE: i64 = 16 * 1024 * 1024;
+pub const DEFAULT_MAX_MESSAGE_SIZE_BYTES: i64 = 48000000;
+pub const DEFAULT_MAX_WRITE_BATCH_SIZE: i64 = 1000;
 
-/// The result of an isMaster operation.
+/// The result of a hello or legacy isMaster operation.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct IsMasterResult {
     pub ok: bool,
+    /// Whether the server is a writable primary, reported as `isWritablePrimary` by `hello` and
+    /// as `ismaster` by `isMaster`.
     pub is_master: bool,
+    /// Whether the server supports the `hello` command, in reply to an isMaster that asked with
+    /// `helloOk`.
+    pub hello_ok: bool,
     pub max_bson_object_size: i64,
     pub max_message_size_bytes: i64,
+    pub max_write_batch_size: i64,
     pub local_time: Option<DateTime<Utc>>,
     pub min_wire_version: i64,
     pub max_wire_version: i64,
@@ -72,6 +79,9 @@ pub struct Monitor {
     client: Client,
     // Owned, single-threaded pool.
     personal_pool: Arc<ConnectionPool>,
+    // Whether the server has reported support for `hello`, which is then used instead of the
+    // legacy isMaster command until the monitor's connection is reset.
+    hello_ok: AtomicBool,
     // Owned copy of the topology's heartbeat frequency.
     heartbeat_frequency_ms: AtomicUsize,
     // Used for condvar functionality.
@@ -108,8 +118,10 @@ impl IsMasterResult {
         let mut result = IsMasterResult {
             ok: ok,
             is_master: false,
+            hello_ok: false,
             max_bson_object_size: DEFAULT_MAX_BSON_OBJECT_SIZE,
             max_message_size_bytes: DEFAULT_MAX_MESSAGE_SIZE_BYTES,
+            max_write_batch_size: DEFAULT_MAX_WRITE_BATCH_SIZE,
             local_time: None,
             min_wire_version: -1,
             max_wire_version: -1,
@@ -129,19 +141,39 @@ impl IsMasterResult {
             set_version: None,
         };
 
+        if let Some(&Bson::Boolean(b)) = doc.get("isWritablePrimary") {
+            result.is_master = b;
+        }
+
         if let Some(&Bson::Boolean(b)) = doc.get("ismaster") {
             result.is_master = b;
         }
 
+        if let Some(&Bson::Boolean(b)) = doc.get("helloOk") {
+            result.hello_ok = b;
+        }
+
         if let Some(&Bson::UtcDatetime(datetime)) = doc.get("localTime") {
             result.local_time = Some(datetime);
         }
 
-        if let Some(&Bson::I64(v)) = doc.get("minWireVersion") {
+        if let Some(v) = get_integer(&doc, "maxBsonObjectSize") {
+            result.max_bson_object_size = v;
+        }
+
+        if let Some(v) = get_integer(&doc, "maxMessageSizeBytes") {
+            result.max_message_size_bytes = v;
+        }
+
+        if let Some(v) = get_integer(&doc, "maxWriteBatchSize") {
+            result.max_write_batch_size = v;
+        }
+
+        if let Some(v) = get_integer(&doc, "minWireVersion") {
             result.min_wire_version = v;
         }
 
-        if let Some(&Bson::I64(v)) = doc.get("maxWireVersion") {
+        if let Some(v) = get_integer(&doc, "maxWireVersion") {
             result.max_wire_version = v;
         }
 
@@ -204,7 +236,7 @@ impl IsMasterResult {
             result.hidden = h;
         }
 
-        if let Some(&Bson::I64(v)) = doc.get("setVersion") {
+        if let Some(v) = get_integer(&doc, "setVersion") {
             result.set_version = Some(v);
         }
 
@@ -230,6 +262,15 @@ impl IsMasterResult {
     }
 }
 
+// Servers may encode the integer fields of an isMaster reply as either 32- or 64-bit integers.
+fn get_integer(doc: &bson::Document, key: &str) -> Option<i64> {
+    match doc.get(key) {
+        Some(&Bson::I32(v)) => Some(v as i64),
+        Some(&Bson::I64(v)) => Some(v),
+        _ => None,
+    }
+}
+
 impl Monitor {
     /// Returns a new monitor connected to the server.
     pub fn new(
@@ -247,6 +288,7 @@ impl Monitor {
             personal_pool: Arc::new(ConnectionPool::with_size(host, connector, 1)),
             top_description: top_description,
             server_description: server_description,
+            hello_ok: AtomicBool::new(false),
             heartbeat_frequency_ms: AtomicUsize::new(DEFAULT_HEARTBEAT_FREQUENCY_MS as usize),
             dummy_lock: Mutex::new(()),
             condvar: Condvar::new(),
@@ -264,26 +306,31 @@ impl Monitor {
         self.update_top_description(self.server_description.clone());
     }
 
-    /// Returns an isMaster server response using an owned monitor socket.
+    /// Returns a hello server response using an owned monitor socket, falling back to the legacy
+    /// isMaster command until the server has reported support for `hello`.
     pub fn is_master(&self) -> Result<(Cursor, i64)> {
         let mut options = FindOptions::new();
         options.limit = Some(1);
         options.batch_size = Some(1);
 
         let flags = OpQueryFlags::with_find_options(&options);
-        let filter = doc!{ "isMaster": 1_i32 };
-        let mut stream = self.personal_pool.acquire_stream(self.client.clone())?;
+        let filter = if self.hello_ok.load(Ordering::SeqCst) {
+            doc!{ "hello": 1_i32 }
+        } else {
+            doc!{ "isMaster": 1_i32, "helloOk": true }
+        };
+
+        let mut stream = self.personal_pool.acquire_stream(self.client.clone(), None)?;
         let time_start = time::get_time();
         let cursor = Cursor::query_with_stream(
             &mut stream,
             self.client.clone(),
-            String::from("local.$cmd"),
+            String::from("admin.$cmd"),
             flags,
             filter,
             options,
             CommandType::IsMaster,
             false,
-            None,
         )?;
         let time_end = time::get_time();
 
@@ -314,7 +361,13 @@ impl Monitor {
         {
             let mut server_description = self.server_description.write().unwrap();
             match ismaster_result {
-                Ok(ismaster) => server_description.update(ismaster, round_trip_time),
+                Ok(ismaster) => {
+                    if ismaster.hello_ok {
+                        self.hello_ok.store(true, Ordering::SeqCst);
+                    }
+
+                    server_description.update(ismaster, round_trip_time)
+                }
                 Err(err) => {
                     server_description.set_err(err);
                     return Err(OperationError(
@@ -365,6 +418,7 @@ impl Monitor {
                 // Refresh all connections
                 self.server_pool.clear();
                 self.personal_pool.clear();
+                self.hello_ok.store(false, Ordering::SeqCst);
 
                 if self.server_description.read().unwrap().server_type == ServerType::Unknown {
                     self.set_err(err);
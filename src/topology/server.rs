@@ -12,6 +12,7 @@ use std::str::FromStr;
 use std::sync::{Arc, RwLock};
 use std::sync::atomic::Ordering;
 use std::thread;
+use std::time::Instant;
 
 use super::monitor::{IsMasterResult, Monitor};
 use super::TopologyDescription;
@@ -62,6 +63,12 @@ pub struct ServerDescription {
     pub min_wire_version: i64,
     /// The maximum wire version supported by this server.
     pub max_wire_version: i64,
+    /// The maximum size of a BSON document accepted by this server.
+    pub max_bson_object_size: i64,
+    /// The maximum size of a wire protocol message accepted by this server.
+    pub max_message_size_bytes: i64,
+    /// The maximum number of statements in a single write command accepted by this server.
+    pub max_write_batch_size: i64,
     /// The server's host information, if it is part of a replica set.
     pub me: Option<Host>,
     /// All hosts in the replica set known by this server.
@@ -129,6 +136,9 @@ impl ServerDescription {
 
         self.min_wire_version = ismaster.min_wire_version;
         self.max_wire_version = ismaster.max_wire_version;
+        self.max_bson_object_size = ismaster.max_bson_object_size;
+        self.max_message_size_bytes = ismaster.max_message_size_bytes;
+        self.max_write_batch_size = ismaster.max_write_batch_size;
         self.me = ismaster.me;
         self.hosts = ismaster.hosts;
         self.passives = ismaster.passives;
@@ -232,9 +242,34 @@ impl Server {
         }
     }
 
-    /// Returns a server stream from the connection pool.
-    pub fn acquire_stream(&self, client: Client) -> Result<PooledStream> {
-        self.pool.acquire_stream(client)
+    /// Returns a server stream from the connection pool, waiting for a socket no later than
+    /// `deadline`.
+    pub fn acquire_stream(
+        &self,
+        client: Client,
+        deadline: Option<Instant>,
+    ) -> Result<PooledStream> {
+        let mut stream = self.pool.acquire_stream(client, deadline)?;
+        self.describe_stream(&mut stream)?;
+        Ok(stream)
+    }
+
+    /// Returns an idle stream from the connection pool without connecting or waiting, if one is
+    /// available.
+    pub fn try_acquire_idle_stream(&self) -> Option<PooledStream> {
+        let mut stream = self.pool.try_acquire_idle_stream()?;
+        self.describe_stream(&mut stream).ok()?;
+        Some(stream)
+    }
+
+    // Copies the limits last reported by the server onto a stream.
+    fn describe_stream(&self, stream: &mut PooledStream) -> Result<()> {
+        let description = self.description.read()?;
+        stream.max_wire_version = description.max_wire_version;
+        stream.max_bson_object_size = description.max_bson_object_size;
+        stream.max_message_size_bytes = description.max_message_size_bytes;
+        stream.max_write_batch_size = description.max_write_batch_size;
+        Ok(())
     }
 
     /// Request an update from the monitor on the server status.
@@ -18,6 +18,22 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Represents the bit vector of options for an OP_DELETE message.
+    pub struct OpDeleteFlags: i32 {
+        const SINGLE_REMOVE = 0b00000001;
+    }
+}
+
+bitflags! {
+    /// Represents the bit vector of flags for an OP_MSG message.
+    pub struct OpMsgFlags: u32 {
+        const CHECKSUM_PRESENT = 0b00000001;
+        const MORE_TO_COME     = 0b00000010;
+        const EXHAUST_ALLOWED  = 1 << 16;
+    }
+}
+
 bitflags! {
     /// Represents the bit vector of flags for an OP_INSERT message.
     pub struct OpInsertFlags: i32 {
@@ -68,6 +84,10 @@ impl OpQueryFlags {
             flags.insert(Self::AWAIT_DATA);
         }
 
+        if options.exhaust {
+            flags.insert(Self::EXHAUST);
+        }
+
         if options.allow_partial_results {
             flags.insert(Self::PARTIAL);
         }
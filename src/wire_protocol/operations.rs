@@ -1,12 +1,13 @@
 //! Wire protocol operational client-server communication logic.
 use bson;
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
-use Error::{ArgumentError, ResponseError};
+use Error::{self, ArgumentError, ResponseError};
 use Result;
 use wire_protocol::header::{Header, OpCode};
-use wire_protocol::flags::{OpInsertFlags, OpQueryFlags, OpReplyFlags, OpUpdateFlags};
+use wire_protocol::flags::{OpDeleteFlags, OpInsertFlags, OpMsgFlags, OpQueryFlags, OpReplyFlags,
+                          OpUpdateFlags};
 
-use std::io::{Read, Write};
+use std::io::{self, Read, Write};
 use std::mem;
 use std::result::Result::{Ok, Err};
 
@@ -103,6 +104,33 @@ pub enum Message {
         /// Uniquely identifies the cursor being returned.
         cursor_id: i64,
     },
+    OpKillCursors {
+        /// The message header.
+        header: Header,
+        // The wire protocol specifies that a 32-bit 0 field goes here
+        /// The cursors to close.
+        cursor_ids: Vec<i64>,
+    },
+    OpDelete {
+        /// The message header.
+        header: Header,
+        // The wire protocol specifies that a 32-bit 0 field goes here
+        /// The full qualified name of the collection, beginning with the
+        /// database name and a dot separator.
+        namespace: String,
+        /// A bit vector of delete options.
+        flags: OpDeleteFlags,
+        /// Identifies the document(s) to be deleted.
+        selector: bson::Document,
+    },
+    OpMsg {
+        /// The message header.
+        header: Header,
+        /// A bit vector of message options.
+        flags: OpMsgFlags,
+        /// The command document, sent as a single body section.
+        body: bson::Document,
+    },
 }
 
 impl Message {
@@ -259,6 +287,72 @@ impl Message {
         }
     }
 
+    /// Constructs a new "kill cursors" request message.
+    pub fn new_kill_cursors(request_id: i32, cursor_ids: Vec<i64>) -> Message {
+        let header_length = mem::size_of::<Header>() as i32;
+
+        // There are two i32 fields because of the reserved "ZERO".
+        let i32_length = 2 * mem::size_of::<i32>() as i32;
+
+        let i64_length = cursor_ids.len() as i32 * mem::size_of::<i64>() as i32;
+        let total_length = header_length + i32_length + i64_length;
+
+        let header = Header::new_kill_cursors(total_length, request_id);
+
+        Message::OpKillCursors {
+            header: header,
+            cursor_ids: cursor_ids,
+        }
+    }
+
+    /// Constructs a new message request for a deletion.
+    pub fn new_delete(
+        request_id: i32,
+        namespace: String,
+        flags: OpDeleteFlags,
+        selector: bson::Document,
+    ) -> Result<Message> {
+        let header_length = mem::size_of::<Header>() as i32;
+
+        // Add an extra byte after the string for null-termination.
+        let string_length = namespace.len() as i32 + 1;
+
+        // There are two i32 fields -- `flags` is represented in the struct as
+        // a bit vector, and the wire protocol-specified ZERO field.
+        let i32_length = mem::size_of::<i32>() as i32 * 2;
+
+        let selector_length = selector.byte_length()?;
+        let total_length = header_length + string_length + i32_length + selector_length;
+
+        let header = Header::new_delete(total_length, request_id);
+
+        Ok(Message::OpDelete {
+            header: header,
+            namespace: namespace,
+            flags: flags,
+            selector: selector,
+        })
+    }
+
+    /// Constructs a new OP_MSG request with the provided command as its body.
+    pub fn new_msg(request_id: i32, flags: OpMsgFlags, body: bson::Document) -> Result<Message> {
+        let header_length = mem::size_of::<Header>() as i32;
+        let flags_length = mem::size_of::<u32>() as i32;
+
+        // Each section begins with a single byte that identifies its kind.
+        let section_length = 1 + body.byte_length()?;
+
+        let total_length = header_length + flags_length + section_length;
+
+        let header = Header::new_msg(total_length, request_id);
+
+        Ok(Message::OpMsg {
+            header: header,
+            flags: flags,
+            body: body,
+        })
+    }
+
     /// Writes a serialized BSON document to a given buffer.
     ///
     /// # Arguments
@@ -459,6 +553,110 @@ impl Message {
         Ok(())
     }
 
+    /// Writes a serialized "kill cursors" request to a given buffer.
+    ///
+    /// # Arguments
+    ///
+    /// `buffer` - The buffer to write to.
+    /// `header` - The header for the given message.
+    /// `cursor_ids` - Specifies which cursors to close.
+    ///
+    /// # Return value
+    ///
+    /// Returns nothing on success, or an Error on failure.
+    pub fn write_kill_cursors<W: Write>(
+        buffer: &mut W,
+        header: &Header,
+        cursor_ids: &[i64],
+    ) -> Result<()> {
+
+        header.write(buffer)?;
+
+        // Write ZERO field
+        buffer.write_i32::<LittleEndian>(0)?;
+
+        buffer.write_i32::<LittleEndian>(cursor_ids.len() as i32)?;
+
+        for cursor_id in cursor_ids {
+            buffer.write_i64::<LittleEndian>(*cursor_id)?;
+        }
+
+        let _ = buffer.flush();
+        Ok(())
+    }
+
+    /// Writes a serialized delete message to a given buffer.
+    ///
+    /// # Arguments
+    ///
+    /// `buffer` - The buffer to write to.
+    /// `header` - The header for the given message.
+    /// `namespace` - The full qualified name of the collection, beginning with
+    ///               the database name and a dot.
+    /// `flags` - Bit vector of delete options.
+    /// `selector` - Identifies the document(s) to be deleted.
+    ///
+    /// # Return value
+    ///
+    /// Returns nothing on success, or an Error on failure.
+    pub fn write_delete<W: Write>(
+        buffer: &mut W,
+        header: &Header,
+        namespace: &str,
+        flags: &OpDeleteFlags,
+        selector: &bson::Document,
+    ) -> Result<()> {
+
+        header.write(buffer)?;
+
+        // Write ZERO field
+        buffer.write_i32::<LittleEndian>(0)?;
+
+        for byte in namespace.bytes() {
+            buffer.write_u8(byte)?;
+        }
+
+        // Writes the null terminator for the collection name string.
+        buffer.write_u8(0)?;
+
+        buffer.write_i32::<LittleEndian>(flags.bits())?;
+
+        Message::write_bson_document(buffer, selector)?;
+
+        let _ = buffer.flush();
+        Ok(())
+    }
+
+    /// Writes a serialized OP_MSG to a given buffer.
+    ///
+    /// # Arguments
+    ///
+    /// `buffer` - The buffer to write to.
+    /// `header` - The header for the given message.
+    /// `flags` - Bit vector of message options.
+    /// `body` - The command document.
+    ///
+    /// # Return value
+    ///
+    /// Returns nothing on success, or an Error on failure.
+    pub fn write_msg<W: Write>(
+        buffer: &mut W,
+        header: &Header,
+        flags: &OpMsgFlags,
+        body: &bson::Document,
+    ) -> Result<()> {
+
+        header.write(buffer)?;
+        buffer.write_u32::<LittleEndian>(flags.bits())?;
+
+        // Section kind 0: a single BSON document.
+        buffer.write_u8(0)?;
+        Message::write_bson_document(buffer, body)?;
+
+        let _ = buffer.flush();
+        Ok(())
+    }
+
     /// Attemps to write the serialized message to a buffer.
     ///
     /// # Arguments
@@ -515,6 +713,21 @@ impl Message {
                 number_to_return,
                 cursor_id,
             } => Message::write_get_more(buffer, header, namespace, number_to_return, cursor_id),
+            Message::OpKillCursors {
+                ref header,
+                ref cursor_ids,
+            } => Message::write_kill_cursors(buffer, header, cursor_ids),
+            Message::OpDelete {
+                ref header,
+                ref namespace,
+                ref flags,
+                ref selector,
+            } => Message::write_delete(buffer, header, namespace, flags, selector),
+            Message::OpMsg {
+                ref header,
+                ref flags,
+                ref body,
+            } => Message::write_msg(buffer, header, flags, body),
         }
     }
 
@@ -557,6 +770,95 @@ impl Message {
         Ok(Message::new_reply(header, flags, cid, sf, nr, v))
     }
 
+    /// Reads a serialized OP_MSG from a buffer. The documents of any document sequence sections
+    /// are added to the body as an array named by the section's identifier.
+    ///
+    /// # Arguments
+    ///
+    /// `buffer` - The buffer to read from.
+    ///
+    /// # Return value
+    ///
+    /// Returns the message on success, or an Error on failure.
+    fn read_msg<R: Read>(buffer: &mut R, header: Header) -> Result<Message> {
+        let mut length = header.message_length - mem::size_of::<Header>() as i32;
+
+        let flags = OpMsgFlags::from_bits_truncate(buffer.read_u32::<LittleEndian>()?);
+        length -= mem::size_of::<u32>() as i32;
+
+        if flags.contains(OpMsgFlags::CHECKSUM_PRESENT) {
+            length -= mem::size_of::<u32>() as i32;
+        }
+
+        let mut body = None;
+        let mut sequences = Vec::new();
+
+        while length > 0 {
+            let kind = buffer.read_u8()?;
+            length -= 1;
+
+            match kind {
+                0 => {
+                    let document = bson::decode_document(buffer)?;
+                    length -= document.byte_length()?;
+                    body = Some(document);
+                }
+                1 => {
+                    let size = buffer.read_i32::<LittleEndian>()?;
+                    length -= size;
+
+                    let mut identifier = Vec::new();
+                    loop {
+                        match buffer.read_u8()? {
+                            0 => break,
+                            byte => identifier.push(byte),
+                        }
+                    }
+
+                    let mut remaining =
+                        size - mem::size_of::<i32>() as i32 - identifier.len() as i32 - 1;
+                    let mut documents = Vec::new();
+
+                    while remaining > 0 {
+                        let document = bson::decode_document(buffer)?;
+                        remaining -= document.byte_length()?;
+                        documents.push(bson::Bson::Document(document));
+                    }
+
+                    let identifier = String::from_utf8(identifier).map_err(|_| {
+                        ResponseError(String::from("Invalid OP_MSG section identifier."))
+                    })?;
+                    sequences.push((identifier, documents));
+                }
+                kind => {
+                    return Err(ResponseError(
+                        format!("Invalid OP_MSG section kind from server: {}.", kind),
+                    ))
+                }
+            }
+        }
+
+        // Checksums are not verified.
+        if flags.contains(OpMsgFlags::CHECKSUM_PRESENT) {
+            buffer.read_u32::<LittleEndian>()?;
+        }
+
+        let mut body = match body {
+            Some(body) => body,
+            None => return Err(ResponseError(String::from("OP_MSG from server has no body."))),
+        };
+
+        for (identifier, documents) in sequences {
+            body.insert(identifier, bson::Bson::Array(documents));
+        }
+
+        Ok(Message::OpMsg {
+            header: header,
+            flags: flags,
+            body: body,
+        })
+    }
+
     /// Attempts to read a serialized reply Message from a buffer.
     ///
     /// # Arguments
@@ -570,16 +872,50 @@ impl Message {
     where
         T: Read + Write,
     {
-        let header = Header::read(buffer)?;
+        let header = Header::read(buffer).map_err(Message::timeout_error)?;
         match header.op_code {
-            OpCode::Reply => Message::read_reply(buffer, header),
+            OpCode::Reply => Message::read_reply(buffer, header).map_err(Message::timeout_error),
+            OpCode::Msg => Message::read_msg(buffer, header).map_err(Message::timeout_error),
             opcode => {
                 Err(ResponseError(format!(
-                    "Expected to read OpCode::Reply but instead found \
+                    "Expected to read OpCode::Reply or OpCode::Msg but instead found \
                                            opcode {}",
                     opcode
                 )))
             }
         }
     }
+
+    // Reports a read that ran past the socket's read timeout as a TimeoutError.
+    fn timeout_error(err: Error) -> Error {
+        let kind = match err {
+            Error::IoError(ref err) |
+            Error::DecoderError(bson::DecoderError::IoError(ref err)) => err.kind(),
+            _ => return err,
+        };
+
+        match kind {
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => Error::TimeoutError,
+            _ => err,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bson::{bson, doc};
+    use std::io::Cursor;
+
+    #[test]
+    fn op_msg_round_trip() {
+        let body = doc! { "getMore": 1i64, "collection": "coll", "$db": "test" };
+        let message = Message::new_msg(7, OpMsgFlags::EXHAUST_ALLOWED, body).unwrap();
+
+        let mut buffer = Cursor::new(Vec::new());
+        message.write(&mut buffer).unwrap();
+        buffer.set_position(0);
+
+        assert_eq!(message, Message::read(&mut buffer).unwrap());
+    }
 }
@@ -115,16 +115,23 @@ fn bulk_ordered_mix() {
             filter: doc! { "_id": 3 },
             replacement: doc! { "x": 37 },
             upsert: Some(true),
+            collation: None,
+            hint: None,
         },
         WriteModel::UpdateMany {
             filter: doc! { "_id": { "$lt": 3 } },
-            update: doc! { "$inc": { "x": 1 } },
+            update: doc! { "$inc": { "x": 1 } }.into(),
             upsert: Some(false),
+            collation: None,
+            array_filters: None,
+            hint: None,
         },
         WriteModel::DeleteOne {
             filter: doc! {
             "_id": 4
         },
+            collation: None,
+            hint: None,
         },
         WriteModel::InsertOne {
             document: doc! {
@@ -134,8 +141,11 @@ fn bulk_ordered_mix() {
         },
         WriteModel::UpdateOne {
             filter: doc! { "_id": 6 },
-            update: doc! { "$set":  { "x": 62 } },
+            update: doc! { "$set":  { "x": 62 } }.into(),
             upsert: Some(true),
+            collation: None,
+            array_filters: None,
+            hint: None,
         },
         WriteModel::InsertOne {
             document: doc! {
@@ -165,6 +175,8 @@ fn bulk_ordered_mix() {
             filter: doc! {
             "_id": { "$gte": 103 }
         },
+            collation: None,
+            hint: None,
         },
     ];
 
@@ -204,3 +216,45 @@ fn bulk_ordered_mix() {
     check_value_in_tree!(result.inserted_ids, 12, 104);
     check_value_in_tree!(result.upserted_ids, 8, 6);
 }
+
+#[test]
+fn bulk_unordered_result_indexes_match_requests() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-bulk");
+    let coll = db.collection("bulk_unordered_result_indexes_match_requests");
+
+    coll.drop().unwrap();
+
+    let models = vec![
+        WriteModel::UpdateOne {
+            filter: doc! { "_id": 10 },
+            update: doc! { "$set": { "x": 1 } }.into(),
+            upsert: Some(true),
+            collation: None,
+            array_filters: None,
+            hint: None,
+        },
+        WriteModel::InsertOne { document: doc! { "_id": 1 } },
+        WriteModel::DeleteOne { filter: doc! { "_id": 100 }, collation: None, hint: None },
+        WriteModel::InsertOne { document: doc! { "_id": 2 } },
+        WriteModel::UpdateOne {
+            filter: doc! { "_id": 20 },
+            update: doc! { "$set": { "x": 2 } }.into(),
+            upsert: Some(true),
+            collation: None,
+            array_filters: None,
+            hint: None,
+        },
+    ];
+
+    let result = coll.bulk_write(models, false);
+    assert!(result.bulk_write_exception.is_none());
+
+    assert_eq!(2, result.inserted_count);
+    assert_eq!(Some(&Bson::I32(1)), result.inserted_ids.get(&1));
+    assert_eq!(Some(&Bson::I32(2)), result.inserted_ids.get(&3));
+
+    assert_eq!(2, result.upserted_count);
+    assert_eq!(Some(&Bson::I32(10)), result.upserted_ids.get(&0));
+    assert_eq!(Some(&Bson::I32(20)), result.upserted_ids.get(&4));
+}
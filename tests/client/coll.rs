@@ -1,9 +1,17 @@
 use bson::Bson;
 
 use mongodb::{Client, ThreadedClient};
+use mongodb::aggregation::{Accumulator, Group, Out, Pipeline};
+use mongodb::coll::explain::ExplainVerbosity;
+use mongodb::common::WriteConcern;
+use mongodb::query::{field, Update};
 use mongodb::db::ThreadedDatabase;
-use mongodb::coll::options::{FindOptions, FindOneAndUpdateOptions, IndexModel, IndexOptions,
-                             ReturnDocument};
+use mongodb::coll::options::{AggregateOptions, Collation, CollationStrength, CommitQuorum,
+                             CountDocumentsOptions, CountOptions, CreateIndexesOptions,
+                             DeleteOptions, EstimatedDocumentCountOptions, FindOptions,
+                             FindOneAndUpdateOptions, Hint, IndexModel, IndexOptions,
+                             IndexSpecifier, InsertManyOptions, ModifyIndexOptions,
+                             ReturnDocument, UpdateOptions};
 
 #[test]
 fn find_sorted() {
@@ -304,6 +312,189 @@ fn aggregate() {
     assert!(vec.contains(&"f".to_owned()));
 }
 
+#[test]
+fn aggregate_with_pipeline_builder() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-coll");
+    let coll = db.collection("aggregate_with_pipeline_builder");
+
+    coll.drop().expect("Failed to drop collection.");
+    coll.insert_many(
+        vec![
+            doc! { "item": "a", "qty": 2 },
+            doc! { "item": "b", "qty": 5 },
+            doc! { "item": "a", "qty": 3 },
+        ],
+        None,
+    ).expect("Failed to insert documents.");
+
+    let pipeline = Pipeline::new()
+        .match_(doc! { "qty": { "$gt": 1 } })
+        .group(Group::new("$item").field("total", Accumulator::Sum(Bson::from("$qty"))))
+        .sort(doc! { "_id": 1 });
+
+    let mut cursor = coll.aggregate(pipeline, None).expect("Failed to execute aggregate.");
+    let results = cursor.next_n(10).expect("Failed to get next 10 from cursor.");
+
+    assert_eq!(
+        vec![doc! { "_id": "a", "total": 5 }, doc! { "_id": "b", "total": 5 }],
+        results
+    );
+}
+
+#[test]
+fn aggregate_with_out_stage() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-coll");
+    let coll = db.collection("aggregate_with_out_stage");
+    let out = db.collection("aggregate_with_out_stage_results");
+
+    coll.drop().expect("Failed to drop collection.");
+    out.drop().expect("Failed to drop collection.");
+    coll.insert_many(vec![doc! { "x": 1 }, doc! { "x": 2 }, doc! { "x": 3 }], None)
+        .expect("Failed to insert documents.");
+
+    let pipeline = Pipeline::new()
+        .match_(doc! { "x": { "$gte": 2 } })
+        .out(Out::new("aggregate_with_out_stage_results"));
+
+    let mut cursor = coll.aggregate(pipeline, None).expect("Failed to execute aggregate.");
+    assert!(cursor.next().is_none());
+
+    assert_eq!(2, out.count(None, None).expect("Failed to execute count."));
+}
+
+#[test]
+fn aggregate_explain() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-coll");
+    let coll = db.collection("aggregate_explain");
+
+    coll.drop().expect("Failed to drop collection.");
+    coll.insert_one(doc! { "x": 1 }, None).expect("Failed to insert document.");
+
+    let mut options = AggregateOptions::new();
+    options.explain = Some(true);
+
+    let mut cursor = coll.aggregate(vec![doc! { "$match": { "x": 1 } }], Some(options))
+        .expect("Failed to execute aggregate.");
+
+    let plan = cursor.next().expect("No explain output was returned.").unwrap();
+    assert!(plan.contains_key("stages") || plan.contains_key("queryPlanner"));
+}
+
+#[test]
+fn database_aggregate_current_op() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("admin");
+    skip_if_db_version_below!(db, 3, 6);
+
+    let pipeline = Pipeline::new()
+        .stage(doc! { "$currentOp": { "allUsers": true, "idleConnections": true } })
+        .limit(1);
+
+    let mut cursor = db.aggregate(pipeline, None).expect("Failed to execute aggregate.");
+    assert!(cursor.next().is_some());
+}
+
+#[test]
+fn explain_find_uses_index() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-coll");
+    let coll = db.collection("explain_find_uses_index");
+
+    coll.drop().expect("Failed to drop collection.");
+    coll.insert_many(vec![doc! { "x": 1 }, doc! { "x": 2 }, doc! { "x": 3 }], None)
+        .expect("Failed to insert documents.");
+    coll.create_index(doc! { "x": 1 }, None).expect("Failed to create index.");
+
+    let explained = coll.explain_find(
+        Some(doc! { "x": 2 }),
+        None,
+        ExplainVerbosity::ExecutionStats,
+    ).expect("Failed to explain find.");
+
+    assert!(explained.winning_plan.is_some());
+    assert_eq!(vec![String::from("x_1")], explained.index_names());
+    assert_eq!(Some(1), explained.n_returned);
+    assert_eq!(Some(1), explained.total_docs_examined);
+    assert!(explained.execution_time_millis.is_some());
+}
+
+#[test]
+fn explain_operations() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-coll");
+    let coll = db.collection("explain_operations");
+
+    coll.drop().expect("Failed to drop collection.");
+    coll.insert_many(vec![doc! { "x": 1 }, doc! { "x": 2 }], None)
+        .expect("Failed to insert documents.");
+
+    let verbosity = ExplainVerbosity::QueryPlanner;
+
+    let explained = coll.explain_count(Some(doc! { "x": 1 }), None, verbosity)
+        .expect("Failed to explain count.");
+    assert!(explained.winning_plan.is_some());
+
+    let explained = coll.explain_distinct("x", None, None, verbosity)
+        .expect("Failed to explain distinct.");
+    assert!(explained.winning_plan.is_some());
+
+    let explained = coll.explain_aggregate(
+        vec![doc! { "$match": { "x": 1 } }, doc! { "$group": { "_id": "$x" } }],
+        None,
+        verbosity,
+    ).expect("Failed to explain aggregate.");
+    assert!(explained.raw.contains_key("stages") || explained.winning_plan.is_some());
+
+    // Explaining writes with execution statistics must not modify any documents.
+    let verbosity = ExplainVerbosity::ExecutionStats;
+
+    coll.explain_update(doc! { "x": 1 }, doc! { "$set": { "x": 5 } }, true, None, verbosity)
+        .expect("Failed to explain update.");
+    coll.explain_delete(doc! {}, true, None, verbosity)
+        .expect("Failed to explain delete.");
+
+    assert_eq!(1, coll.count(Some(doc! { "x": 1 }), None).expect("Failed to execute count."));
+    assert_eq!(2, coll.count(None, None).expect("Failed to execute count."));
+}
+
+#[test]
+fn query_and_update_builders() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-coll");
+    let coll = db.collection("query_and_update_builders");
+
+    coll.drop().expect("Failed to drop collection.");
+    coll.insert_many(
+        vec![
+            doc! { "name": "Ann", "age": 17, "tags": ["new"] },
+            doc! { "name": "bob", "age": 30, "tags": ["admin"] },
+            doc! { "name": "Bea", "age": 45, "tags": ["staff", "admin"] },
+        ],
+        None,
+    ).expect("Failed to insert documents.");
+
+    let filter = field("age").gte(18).and(field("tags").in_(vec!["admin"]));
+    assert_eq!(2, coll.count(Some(filter.into()), None).expect("Failed to execute count."));
+
+    let filter = field("name").regex("^b", "i");
+    let update = Update::new().set("active", true).inc("age", 1).push_each("tags", vec!["x", "y"]);
+
+    let result = coll.update_many(filter.into(), update, None)
+        .expect("Failed to update documents.");
+    assert_eq!(2, result.modified_count);
+
+    let doc = coll.find_one(Some(doc! { "name": "Bea" }), None)
+        .expect("Failed to execute find.")
+        .expect("No document was found.");
+
+    assert_eq!(Some(&Bson::Boolean(true)), doc.get("active"));
+    assert_eq!(Some(&Bson::I32(46)), doc.get("age"));
+    assert_eq!(4, doc.get_array("tags").unwrap().len());
+}
+
 #[test]
 fn count() {
     let client = Client::connect("localhost", 27017).unwrap();
@@ -344,6 +535,120 @@ fn count() {
     assert_eq!(0, count_none);
 }
 
+#[test]
+fn estimated_document_count() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-coll");
+    let coll = db.collection("estimated_document_count");
+
+    coll.drop().expect("Failed to drop collection.");
+    assert_eq!(0, coll.estimated_document_count(None).expect("Failed to estimate count."));
+
+    coll.insert_many(vec![doc! { "x": 1 }, doc! { "x": 2 }, doc! { "x": 3 }], None)
+        .expect("Failed to insert documents.");
+
+    let mut options = EstimatedDocumentCountOptions::new();
+    options.max_time_ms = Some(1000);
+    let count = coll.estimated_document_count(Some(options))
+        .expect("Failed to estimate count.");
+    assert_eq!(3, count);
+}
+
+#[test]
+fn count_documents() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-coll");
+    let coll = db.collection("count_documents");
+
+    coll.drop().expect("Failed to drop collection.");
+    assert_eq!(0, coll.count_documents(None, None).expect("Failed to count documents."));
+
+    let docs = (0..10).map(|i| doc! { "x": i, "title": if i % 2 == 0 { "Jaws" } else { "JAWS" } });
+    coll.insert_many(docs.collect(), None).expect("Failed to insert documents.");
+
+    let count = coll.count_documents(Some(doc! { "x": { "$gte": 5 } }), None)
+        .expect("Failed to count documents.");
+    assert_eq!(5, count);
+
+    let mut options = CountDocumentsOptions::new();
+    options.skip = Some(2);
+    options.limit = Some(6);
+    options.max_time_ms = Some(1000);
+    let count = coll.count_documents(None, Some(options)).expect("Failed to count documents.");
+    assert_eq!(6, count);
+
+    coll.create_index(doc! { "x": 1 }, None).expect("Failed to create index.");
+
+    let mut options = CountDocumentsOptions::new();
+    options.hint = Some(Hint::Keys(doc! { "x": 1 }));
+    let count = coll.count_documents(Some(doc! { "x": { "$lt": 3 } }), Some(options))
+        .expect("Failed to count documents.");
+    assert_eq!(3, count);
+}
+
+#[test]
+fn count_documents_with_collation() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-coll");
+    skip_if_db_version_below!(db, 3, 4);
+    let coll = db.collection("count_documents_with_collation");
+
+    coll.drop().expect("Failed to drop collection.");
+    coll.insert_many(vec![doc! { "title": "Jaws" }, doc! { "title": "JAWS" }], None)
+        .expect("Failed to insert documents.");
+
+    let mut options = CountDocumentsOptions::new();
+    options.collation = Some(Collation {
+        strength: Some(CollationStrength::Secondary),
+        ..Collation::new("en")
+    });
+
+    let count = coll.count_documents(Some(doc! { "title": "jaws" }), Some(options))
+        .expect("Failed to count documents.");
+    assert_eq!(2, count);
+}
+
+#[test]
+fn count_and_delete_with_collation() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-coll");
+    skip_if_db_version_below!(db, 3, 4);
+    let coll = db.collection("count_and_delete_with_collation");
+
+    coll.drop().expect("Failed to drop database");
+
+    coll.insert_many(
+        vec![
+            doc! { "title": "Jaws" },
+            doc! { "title": "JAWS" },
+            doc! { "title": "Back to the Future" },
+        ],
+        None,
+    ).expect("Failed to insert documents.");
+
+    let collation = Collation {
+        strength: Some(CollationStrength::Secondary),
+        ..Collation::new("en")
+    };
+
+    let count = coll.count(Some(doc! { "title": "jaws" }), None).expect(
+        "Failed to execute count.",
+    );
+    assert_eq!(0, count);
+
+    let mut options = CountOptions::new();
+    options.collation = Some(collation.clone());
+    let count = coll.count(Some(doc! { "title": "jaws" }), Some(options))
+        .expect("Failed to execute count.");
+    assert_eq!(2, count);
+
+    let mut options = DeleteOptions::new();
+    options.collation = Some(collation);
+    let result = coll.delete_many_with_options(doc! { "title": "jaws" }, Some(options))
+        .expect("Failed to execute delete_many.");
+    assert_eq!(2, result.deleted_count);
+}
+
 #[test]
 fn distinct_none() {
     let client = Client::connect("localhost", 27017).unwrap();
@@ -484,6 +789,50 @@ fn insert_many() {
     }
 }
 
+#[test]
+fn insert_one_unacknowledged() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-coll");
+    let coll = db.collection("insert_one_unacknowledged");
+
+    coll.drop().expect("Failed to drop database");
+
+    let result = coll.insert_one(doc! { "title": "Jaws" }, Some(WriteConcern::unacknowledged()))
+        .expect("Failed to insert document.");
+    assert!(!result.acknowledged);
+
+    let result = coll.insert_one(doc! { "title": "Back to the Future" }, None)
+        .expect("Failed to insert document.");
+    assert!(result.acknowledged);
+}
+
+#[test]
+fn unacknowledged_writes() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-coll");
+    let coll = db.collection("unacknowledged_writes");
+
+    coll.drop().expect("Failed to drop collection.");
+
+    let mut options = InsertManyOptions::new();
+    options.ordered = Some(false);
+    options.write_concern = Some(WriteConcern::unacknowledged());
+
+    let result = coll.insert_many(vec![doc! { "x": 1 }, doc! { "x": 2 }], Some(options))
+        .expect("Failed to insert documents.");
+    assert!(!result.acknowledged);
+
+    let mut update_options = UpdateOptions::new();
+    update_options.write_concern = Some(WriteConcern::unacknowledged());
+    let result = coll.update_many(doc!{}, doc! { "$inc": { "x": 10 } }, Some(update_options))
+        .expect("Failed to update documents.");
+    assert!(!result.acknowledged);
+
+    let result = coll.delete_one(doc! { "x": 11 }, Some(WriteConcern::unacknowledged()))
+        .expect("Failed to delete document.");
+    assert!(!result.acknowledged);
+}
+
 #[test]
 fn delete_one() {
     let client = Client::connect("localhost", 27017).unwrap();
@@ -910,3 +1259,155 @@ fn drop_all_indexes() {
 
     assert_eq!(1, results.len());
 }
+
+#[test]
+fn create_modify_and_drop_indexes_with_options() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-coll");
+    skip_if_db_version_below!(db, 4, 4);
+    let coll = db.collection("create_modify_and_drop_indexes_with_options");
+
+    coll.drop().expect("Failed to drop collection.");
+    coll.insert_one(doc! { "x": 1 }, None).expect("Failed to insert document.");
+
+    let mut partial = IndexOptions::new();
+    partial.partial_filter_expression = Some(doc! { "x": { "$gt": 5 } });
+    partial.hidden = Some(true);
+
+    let mut wildcard = IndexOptions::new();
+    wildcard.wildcard_projection = Some(doc! { "a": 1 });
+
+    let mut ttl = IndexOptions::new();
+    ttl.expire_after_seconds = Some(60);
+
+    let models = vec![
+        IndexModel::new(doc! { "x": 1 }, Some(partial.clone())),
+        IndexModel::new(doc! { "$**": 1 }, Some(wildcard.clone())),
+        IndexModel::new(doc! { "created": 1 }, Some(ttl)),
+    ];
+
+    let mut options = CreateIndexesOptions::new();
+    options.commit_quorum = Some(CommitQuorum::VotingMembers);
+
+    let names = coll.create_indexes_with_options(models, Some(options))
+        .expect("Failed to create indexes.");
+    assert_eq!(vec!["x_1", "$**_1", "created_1"], names);
+
+    let mut modify = ModifyIndexOptions::new();
+    modify.hidden = Some(false);
+    coll.modify_index("x_1", modify)
+        .expect("Failed to unhide index.");
+
+    let mut modify = ModifyIndexOptions::new();
+    modify.expire_after_seconds = Some(120);
+    coll.modify_index(IndexSpecifier::KeyPattern(doc! { "created": 1 }), modify)
+        .expect("Failed to change index expiry.");
+
+    let models: Vec<_> = coll.list_index_models()
+        .expect("Failed to list indexes.")
+        .collect::<Result<_, _>>()
+        .expect("Failed to deserialize index models.");
+
+    let find = |name: &str| {
+        models.iter().find(|model| model.options.name.as_ref().map(String::as_str) == Some(name))
+    };
+
+    let x = find("x_1").expect("Index x_1 was not listed.");
+    assert_eq!(partial.partial_filter_expression, x.options.partial_filter_expression);
+    assert_ne!(Some(true), x.options.hidden);
+
+    let wildcard_index = find("$**_1").expect("Wildcard index was not listed.");
+    assert_eq!(wildcard.wildcard_projection, wildcard_index.options.wildcard_projection);
+
+    let created = find("created_1").expect("Index created_1 was not listed.");
+    assert_eq!(Some(120), created.options.expire_after_seconds);
+
+    coll.drop_index_string(vec!["x_1", "created_1"]).expect("Failed to drop indexes.");
+
+    let remaining = coll.list_indexes().unwrap().next_n(10).unwrap();
+    assert_eq!(2, remaining.len());
+}
+
+#[test]
+fn insert_many_splits_by_object_size() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-coll");
+    let coll = db.collection("insert_many_splits_by_object_size");
+
+    coll.drop().expect("Failed to drop collection.");
+
+    // Three 7 MB documents can't fit into a single 16 MB insert command.
+    let payload: String = ::std::iter::repeat('x').take(7 * 1024 * 1024).collect();
+    let docs = (0..3).map(|i| doc! { "_id": i, "payload": payload.clone() }).collect();
+
+    let result = coll.insert_many(docs, None).expect("Failed to insert documents.");
+    assert_eq!(3, result.inserted_ids.expect("No ids were returned.").len());
+    assert_eq!(3, coll.count(None, None).expect("Failed to count documents."));
+}
+
+#[test]
+fn update_many_with_pipeline() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-coll");
+    skip_if_db_version_below!(db, 4, 2);
+    let coll = db.collection("update_many_with_pipeline");
+
+    coll.drop().expect("Failed to drop collection.");
+    coll.insert_many(vec![doc! { "a": 1, "b": 2 }, doc! { "a": 3, "b": 4 }], None)
+        .expect("Failed to insert documents.");
+
+    let pipeline = vec![doc! { "$set": { "total": { "$add": ["$a", "$b"] } } }];
+    let result = coll.update_many(doc! {}, pipeline, None).expect("Failed to update documents.");
+    assert_eq!(2, result.modified_count);
+
+    let doc = coll.find_one(Some(doc! { "a": 3 }), None)
+        .expect("Failed to execute find.")
+        .expect("No document was found.");
+    assert_eq!(Some(&Bson::I32(7)), doc.get("total"));
+}
+
+#[test]
+fn update_one_with_array_filters() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-coll");
+    skip_if_db_version_below!(db, 3, 6);
+    let coll = db.collection("update_one_with_array_filters");
+
+    coll.drop().expect("Failed to drop collection.");
+    coll.insert_one(doc! { "_id": 1, "grades": [95, 80, 90] }, None)
+        .expect("Failed to insert document.");
+
+    let mut options = UpdateOptions::new();
+    options.array_filters = Some(vec![doc! { "grade": { "$gte": 90 } }]);
+
+    coll.update_one(doc! { "_id": 1 }, doc! { "$set": { "grades.$[grade]": 100 } }, Some(options))
+        .expect("Failed to update document.");
+
+    let doc = coll.find_one(None, None)
+        .expect("Failed to execute find.")
+        .expect("No document was found.");
+    assert_eq!(
+        Some(&Bson::Array(vec![Bson::I32(100), Bson::I32(80), Bson::I32(100)])),
+        doc.get("grades")
+    );
+}
+
+#[test]
+fn collection_stats() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-coll-collection_stats");
+    db.drop_database().unwrap();
+
+    let coll = db.collection("stats");
+    coll.insert_many(vec![doc! { "x": 1 }, doc! { "x": 2 }], None)
+        .expect("Failed to insert documents.");
+    coll.create_index(doc! { "x": 1 }, None).expect("Failed to create index.");
+
+    let stats = coll.stats(None).expect("Failed to get collection stats.");
+    assert_eq!("test-client-coll-collection_stats.stats", stats.ns);
+    assert_eq!(2, stats.count);
+    assert_eq!(2, stats.nindexes);
+    assert!(stats.index_sizes.contains_key("_id_"));
+    assert!(stats.index_sizes.contains_key("x_1"));
+    assert!(!stats.capped);
+}
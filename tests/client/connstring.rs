@@ -1,3 +1,4 @@
+use mongodb::common::{Acknowledgment, WriteConcern};
 use mongodb::connstring;
 
 #[test]
@@ -184,6 +185,35 @@ fn read_pref_tags() {
     }
 }
 
+#[test]
+fn write_concern() {
+    let uri = "mongodb://localhost/?w=majority&wtimeoutMS=100&journal=true&fsync=true";
+    let options = connstring::parse(uri).unwrap().options.unwrap();
+    let write_concern = options.write_concern().unwrap();
+    assert_eq!(Acknowledgment::Majority, write_concern.w);
+    assert_eq!(Some(100), write_concern.w_timeout);
+    assert_eq!(Some(true), write_concern.j);
+    assert!(write_concern.fsync);
+    assert_eq!(
+        doc! { "w": "majority", "wtimeout": 100, "j": true, "fsync": true },
+        write_concern.to_bson()
+    );
+
+    let options = connstring::parse("mongodb://localhost/?w=dc").unwrap().options.unwrap();
+    let write_concern = options.write_concern().unwrap();
+    assert_eq!(Acknowledgment::Tag(String::from("dc")), write_concern.w);
+    assert_eq!(None, write_concern.j);
+
+    let options = connstring::parse("mongodb://localhost/?w=0").unwrap().options.unwrap();
+    let write_concern = options.write_concern().unwrap();
+    assert_eq!(WriteConcern::unacknowledged(), write_concern);
+    assert!(!write_concern.is_acknowledged());
+    assert_eq!(doc! { "w": 0 }, write_concern.to_bson());
+
+    let options = connstring::parse("mongodb://localhost/?journal=yes").unwrap().options.unwrap();
+    assert!(options.write_concern().is_err());
+}
+
 #[test]
 fn unix_domain_socket_single() {
     let uri = "mongodb:///tmp/mongodb-27017.sock/?safe=false";
@@ -1,12 +1,17 @@
 use bson::{Bson, Document};
 
-use mongodb::{Client, CommandType, ThreadedClient};
+use mongodb::{Client, CommandType, Error, ThreadedClient};
 use mongodb::common::{ReadMode, ReadPreference};
-use mongodb::coll::options::FindOptions;
-use mongodb::db::ThreadedDatabase;
+use mongodb::connstring::Host;
+use mongodb::coll::Collection;
+use mongodb::coll::options::{CursorType, FindOptions, Hint};
+use mongodb::db::{Database, ThreadedDatabase};
+use mongodb::db::options::CreateCollectionOptions;
 use mongodb::cursor::Cursor;
 use mongodb::wire_protocol::flags::OpQueryFlags;
 
+use std::time::{Duration, Instant};
+
 #[test]
 fn cursor_features() {
     let client = Client::connect("localhost", 27017).unwrap();
@@ -82,3 +87,383 @@ fn cursor_features() {
         };
     }
 }
+
+// Creates a collection of ten documents with an index on `foo`.
+fn indexed_collection(db: &Database, name: &str) -> Collection {
+    let coll = db.collection(name);
+    coll.drop().expect("Failed to drop collection.");
+
+    let docs = (0..10).map(|i| doc! { "foo": i as i64, "bar": "baz" }).collect();
+    coll.insert_many(docs, None).expect("Failed to insert documents.");
+    coll.create_index(doc! { "foo": 1 }, None).expect("Failed to create index.");
+    coll
+}
+
+#[test]
+fn find_with_hint() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-cursor");
+    skip_if_db_version_below!(db, 3, 2);
+    let coll = indexed_collection(&db, "find_with_hint");
+
+    let mut options = FindOptions::new();
+    options.hint = Some(Hint::Name(String::from("foo_1")));
+
+    let cursor = coll.find(None, Some(options)).expect("Failed to find with an index name hint.");
+    assert_eq!(10, cursor.count());
+
+    let mut options = FindOptions::new();
+    options.hint = Some(Hint::Keys(doc! { "bar": 1 }));
+
+    assert!(coll.find(None, Some(options)).is_err(), "Expected an error for a missing index.");
+}
+
+#[test]
+fn find_with_min_and_max() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-cursor");
+    skip_if_db_version_below!(db, 3, 2);
+    let coll = indexed_collection(&db, "find_with_min_and_max");
+
+    let mut options = FindOptions::new();
+    options.hint = Some(Hint::Keys(doc! { "foo": 1 }));
+    options.min = Some(doc! { "foo": 3 });
+    options.max = Some(doc! { "foo": 7 });
+
+    let results = coll.find(None, Some(options))
+        .expect("Failed to execute find.")
+        .next_n(10)
+        .expect("Failed to read from cursor.");
+
+    let values: Vec<_> = results.iter().map(|doc| doc.get("foo").cloned()).collect();
+    assert_eq!((3..7).map(|i| Some(Bson::I64(i))).collect::<Vec<_>>(), values);
+}
+
+#[test]
+fn find_with_return_key() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-cursor");
+    skip_if_db_version_below!(db, 3, 2);
+    let coll = indexed_collection(&db, "find_with_return_key");
+
+    let mut options = FindOptions::new();
+    options.hint = Some(Hint::Keys(doc! { "foo": 1 }));
+    options.return_key = Some(true);
+
+    let doc = coll.find_one(Some(doc! { "foo": 4 }), Some(options))
+        .expect("Failed to execute find.")
+        .expect("Expected a document.");
+
+    assert_eq!(doc! { "foo": 4i64 }, doc);
+}
+
+#[test]
+fn find_with_show_record_id() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-cursor");
+    skip_if_db_version_below!(db, 3, 2);
+    let coll = indexed_collection(&db, "find_with_show_record_id");
+
+    let mut options = FindOptions::new();
+    options.show_record_id = Some(true);
+
+    let results = coll.find(None, Some(options))
+        .expect("Failed to execute find.")
+        .next_n(10)
+        .expect("Failed to read from cursor.");
+
+    assert_eq!(10, results.len());
+    for doc in results {
+        assert!(doc.contains_key("$recordId"));
+        assert_eq!(Some(&Bson::String(String::from("baz"))), doc.get("bar"));
+    }
+}
+
+#[test]
+fn find_command_errors_keep_code_and_message() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-cursor");
+    skip_if_db_version_below!(db, 3, 2);
+    let coll = db.collection("find_command_errors_keep_code_and_message");
+
+    coll.drop().expect("Failed to drop collection.");
+    coll.insert_one(doc! { "foo": 1 }, None).expect("Failed to insert document.");
+
+    match coll.find(Some(doc! { "$notAnOperator": 1 }), None) {
+        Err(Error::OperationError(ref msg)) => {
+            assert!(msg.starts_with("BadValue (2): "));
+            assert!(msg.contains("$notAnOperator"));
+        }
+        Err(e) => panic!("Expected OperationError, but got {:?}", e),
+        Ok(_) => panic!("Expected an error for an invalid filter."),
+    }
+}
+
+#[test]
+fn unknown_cursor_command_is_an_error() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-cursor");
+    skip_if_db_version_below!(db, 3, 2);
+
+    let primary = ReadPreference::new(ReadMode::Primary, None);
+    match db.command_cursor(doc! { "notACommand": 1 }, CommandType::Suppressed, primary) {
+        Err(Error::OperationError(ref msg)) => assert!(msg.starts_with("CommandNotFound (59)")),
+        Err(e) => panic!("Expected OperationError, but got {:?}", e),
+        Ok(_) => panic!("Expected an error for an unknown command."),
+    }
+}
+
+#[test]
+fn kill_closes_cursor() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-cursor");
+    let coll = db.collection("kill_closes_cursor");
+
+    coll.drop().expect("Failed to drop collection.");
+
+    let docs = (0..10).map(|i| doc! { "foo": i as i64 }).collect();
+    coll.insert_many(docs, None).expect("Failed to insert documents.");
+
+    let mut options = FindOptions::new();
+    options.batch_size = Some(2);
+
+    let mut cursor = coll.find(None, Some(options)).expect("Failed to execute find.");
+    assert!(cursor.next().is_some());
+
+    cursor.kill().expect("Failed to kill cursor.");
+    cursor.kill().expect("Killing a closed cursor should succeed.");
+
+    // Only the rest of the batch that was already received can be returned.
+    assert_eq!(1, cursor.count());
+}
+
+#[test]
+fn query_on_invalid_namespace_is_an_error() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    skip_if_db_version_below!(client.db("test-client-cursor"), 3, 2);
+
+    let result = Cursor::query(
+        client.clone(),
+        String::from("no-collection-separator"),
+        OpQueryFlags::empty(),
+        Document::new(),
+        FindOptions::new(),
+        CommandType::Find,
+        false,
+        ReadPreference::new(ReadMode::Primary, None),
+    );
+
+    match result {
+        Err(Error::ArgumentError(_)) => (),
+        other => panic!("Expected an ArgumentError, but got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn missing_namespace_is_an_empty_cursor() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-cursor");
+    skip_if_db_version_below!(db, 3, 0);
+    let coll = db.collection("missing_namespace_is_an_empty_cursor");
+
+    coll.drop().expect("Failed to drop collection.");
+
+    let cursor = coll.list_indexes().expect("Failed to list indexes of a missing collection.");
+    assert_eq!(0, cursor.count());
+}
+
+#[test]
+fn find_with_negative_limit_returns_single_batch() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-cursor");
+    skip_if_db_version_below!(db, 3, 2);
+    let coll = db.collection("find_with_negative_limit_returns_single_batch");
+
+    coll.drop().expect("Failed to drop collection.");
+
+    let docs = (0..10).map(|i| doc! { "foo": i as i64 }).collect();
+    coll.insert_many(docs, None).expect("Failed to insert documents.");
+
+    let mut options = FindOptions::new();
+    options.batch_size = Some(2);
+    options.limit = Some(-5);
+
+    let mut cursor = coll.find(None, Some(options)).expect("Failed to execute find.");
+    let results = cursor.next_n(10).expect("Failed to get next 10 results.");
+    assert_eq!(5, results.len());
+}
+
+#[test]
+fn find_with_allow_disk_use() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-cursor");
+    skip_if_db_version_below!(db, 4, 4);
+    let coll = indexed_collection(&db, "find_with_allow_disk_use");
+
+    let mut options = FindOptions::new();
+    options.allow_disk_use = Some(true);
+    options.sort = Some(doc! { "bar": 1, "foo": -1 });
+
+    let results = coll.find(None, Some(options))
+        .expect("Failed to execute find.")
+        .next_n(10)
+        .expect("Failed to read from cursor.");
+
+    let values: Vec<_> = results.iter().map(|doc| doc.get("foo").cloned()).collect();
+    assert_eq!((0..10).rev().map(|i| Some(Bson::I64(i))).collect::<Vec<_>>(), values);
+}
+
+#[test]
+fn find_with_let_vars() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-cursor");
+    skip_if_db_version_below!(db, 5, 0);
+    let coll = indexed_collection(&db, "find_with_let_vars");
+
+    let mut options = FindOptions::new();
+    options.let_vars = Some(doc! { "target": 6i64 });
+
+    let filter = doc! { "$expr": { "$eq": ["$foo", "$$target"] } };
+    let results = coll.find(Some(filter), Some(options))
+        .expect("Failed to execute find.")
+        .next_n(10)
+        .expect("Failed to read from cursor.");
+
+    assert_eq!(1, results.len());
+    assert_eq!(Some(&Bson::I64(6)), results[0].get("foo"));
+}
+
+#[test]
+fn stream_for_host_outside_topology_is_an_error() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let host = Host {
+        host_name: String::from("removed.example.com"),
+        ipc: String::new(),
+        port: 27017,
+    };
+
+    match client.acquire_stream_for_host(&host, 0) {
+        Err(Error::OperationError(ref msg)) => {
+            assert!(msg.contains("removed.example.com:27017"));
+            assert!(msg.contains("no longer part of the topology"));
+        }
+        Err(e) => panic!("Expected OperationError, but got {:?}", e),
+        Ok(_) => panic!("Expected an error for a host outside the topology."),
+    }
+}
+
+#[test]
+fn tailable_cursor_waits_for_new_documents() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-cursor");
+    skip_if_db_version_below!(db, 3, 2);
+
+    let name = "tailable_cursor_waits_for_new_documents";
+    db.drop_collection(name).ok();
+
+    let mut create_options = CreateCollectionOptions::new();
+    create_options.capped = Some(true);
+    create_options.size = Some(100000);
+    db.create_collection(name, Some(create_options)).expect("Failed to create capped collection.");
+
+    let coll = db.collection(name);
+    coll.insert_many(vec![doc! { "foo": 1 }, doc! { "foo": 2 }], None)
+        .expect("Failed to insert documents.");
+
+    let mut options = FindOptions::new();
+    options.cursor_type = CursorType::TailableAwait;
+    options.max_await_time_ms = Some(100);
+
+    let mut cursor = coll.find_tailable(None, Some(options)).expect("Failed to tail collection.");
+
+    for i in 1..3 {
+        let doc = cursor.try_next().expect("Failed to read from cursor.").unwrap();
+        assert_eq!(Some(&Bson::I32(i)), doc.get("foo"));
+    }
+
+    assert_eq!(None, cursor.try_next().expect("Failed to poll cursor."));
+    assert!(cursor.is_alive());
+
+    coll.insert_one(doc! { "foo": 3 }, None).expect("Failed to insert document.");
+
+    let doc = cursor.try_next().expect("Failed to read from cursor.").unwrap();
+    assert_eq!(Some(&Bson::I32(3)), doc.get("foo"));
+}
+
+#[test]
+fn find_tailable_rejects_non_tailable_cursor_type() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let coll = client.db("test-client-cursor").collection("find_tailable_rejects");
+
+    match coll.find_tailable(None, Some(FindOptions::new())) {
+        Err(Error::ArgumentError(_)) => (),
+        Err(e) => panic!("Expected ArgumentError, but got {:?}", e),
+        Ok(_) => panic!("Expected an error for a non-tailable cursor type."),
+    }
+}
+
+#[test]
+fn find_with_max_await_time_ms() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-cursor");
+    skip_if_db_version_below!(db, 3, 2);
+
+    let name = "find_with_max_await_time_ms";
+    db.drop_collection(name).ok();
+
+    let mut create_options = CreateCollectionOptions::new();
+    create_options.capped = Some(true);
+    create_options.size = Some(100000);
+    db.create_collection(name, Some(create_options)).expect("Failed to create capped collection.");
+
+    let coll = db.collection(name);
+    coll.insert_one(doc! { "foo": 1 }, None).expect("Failed to insert document.");
+
+    let mut options = FindOptions::new();
+    options.cursor_type = CursorType::TailableAwait;
+    options.max_await_time_ms = Some(1000);
+
+    let mut cursor = coll.find_tailable(None, Some(options)).expect("Failed to tail collection.");
+    cursor.try_next().expect("Failed to read from cursor.").unwrap();
+
+    // With no new documents, the getMore waits for max_await_time_ms before returning.
+    let start = Instant::now();
+    assert_eq!(None, cursor.try_next().expect("Failed to poll cursor."));
+    assert!(start.elapsed() >= Duration::from_millis(800));
+    assert!(cursor.is_alive());
+}
+
+#[test]
+fn exhaust_cursor_streams_every_batch() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-cursor");
+    let coll = db.collection("exhaust_cursor_streams_every_batch");
+
+    coll.drop().expect("Failed to drop collection.");
+
+    let docs = (0..50).map(|i| doc! { "foo": i as i64 }).collect();
+    coll.insert_many(docs, None).expect("Failed to insert documents.");
+
+    let mut options = FindOptions::new();
+    options.exhaust = true;
+    options.batch_size = Some(7);
+    options.sort = Some(doc! { "foo": 1 });
+
+    let results = coll.find(None, Some(options.clone()))
+        .expect("Failed to execute find.")
+        .next_n(100)
+        .expect("Failed to read from exhaust cursor.");
+
+    assert_eq!(50, results.len());
+    for (i, doc) in results.iter().enumerate() {
+        assert_eq!(Some(&Bson::I64(i as i64)), doc.get("foo"));
+    }
+
+    // Dropping a partially read exhaust cursor must not leave its socket in the pool.
+    {
+        let mut cursor = coll.find(None, Some(options)).expect("Failed to execute find.");
+        cursor.next_n(10).expect("Failed to read from exhaust cursor.");
+    }
+
+    assert_eq!(50, coll.count(None, None).expect("Failed to count documents."));
+}
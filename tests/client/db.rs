@@ -1,8 +1,14 @@
 use bson::{self, Bson};
+use chrono::Utc;
 use mongodb::{Client, ThreadedClient};
 use mongodb::db::ThreadedDatabase;
-use mongodb::db::options::CreateUserOptions;
-use mongodb::db::roles::{AllDatabaseRole, SingleDatabaseRole, Role};
+use mongodb::coll::options::CollModOptions;
+use mongodb::db::options::{ClusteredIndex, CreateCollectionOptions, CreateRoleOptions,
+                           CreateUserOptions, ProfilingLevel, RoleInfoOptions,
+                           TimeseriesGranularity, TimeseriesOptions, UpdateRoleOptions,
+                           UpdateUserOptions, ValidationAction, ValidationLevel};
+use mongodb::db::results::CollectionType;
+use mongodb::db::roles::{AllDatabaseRole, Privilege, Resource, SingleDatabaseRole, Role};
 
 #[test]
 fn create_collection() {
@@ -96,6 +102,202 @@ fn list_collections() {
     }
 }
 
+#[test]
+fn create_collection_with_validator() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-db-create_collection_with_validator");
+    skip_if_db_version_below!(db, 3, 6);
+    db.drop_database().unwrap();
+
+    let mut options = CreateCollectionOptions::new();
+    options.validator = Some(doc! {
+        "$jsonSchema": {
+            "bsonType": "object",
+            "required": ["name"],
+        },
+    });
+    options.validation_level = Some(ValidationLevel::Strict);
+    options.validation_action = Some(ValidationAction::Error);
+
+    db.create_collection("people", Some(options)).expect("Failed to create collection.");
+
+    let coll = db.collection("people");
+    coll.insert_one(doc! { "name": "Ann" }, None).expect("Failed to insert valid document.");
+    assert!(coll.insert_one(doc! { "age": 3 }, None).is_err());
+
+    let mut modify = CollModOptions::new();
+    modify.validation_action = Some(ValidationAction::Warn);
+    coll.modify(modify).expect("Failed to modify collection.");
+
+    coll.insert_one(doc! { "age": 3 }, None).expect("Failed to insert invalid document.");
+    assert!(coll.modify(CollModOptions::new()).is_err());
+}
+
+#[test]
+fn create_view_and_list_specifications() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-db-create_view");
+    skip_if_db_version_below!(db, 3, 4);
+    db.drop_database().unwrap();
+
+    db.collection("orders")
+        .insert_many(vec![doc! { "x": 1 }, doc! { "x": 2 }], None)
+        .expect("Failed to insert documents.");
+
+    db.create_view("big_orders", "orders", vec![doc! { "$match": { "x": { "$gt": 1 } } }], None)
+        .expect("Failed to create view.");
+
+    let view = db.collection("big_orders");
+    assert_eq!(1, view.count(None, None).expect("Failed to count view."));
+
+    let specs = db.list_collection_specifications(None).expect("Failed to list collections.");
+    let view_spec = specs.iter().find(|spec| spec.name == "big_orders").unwrap();
+    assert!(view_spec.is_view());
+    assert_eq!(Some("orders"), view_spec.view_on());
+    assert!(view_spec.id_index.is_none());
+
+    let coll_spec = specs.iter().find(|spec| spec.name == "orders").unwrap();
+    assert_eq!(CollectionType::Collection, coll_spec.collection_type);
+    assert!(!coll_spec.read_only);
+}
+
+#[test]
+fn create_timeseries_collection() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-db-create_timeseries_collection");
+    skip_if_db_version_below!(db, 5, 0);
+    db.drop_database().unwrap();
+
+    let mut timeseries = TimeseriesOptions::new("ts");
+    timeseries.meta_field = Some(String::from("sensor"));
+    timeseries.granularity = Some(TimeseriesGranularity::Minutes);
+
+    let mut options = CreateCollectionOptions::new();
+    options.timeseries = Some(timeseries.clone());
+    options.expire_after_seconds = Some(86400);
+
+    db.create_collection("readings", Some(options)).expect("Failed to create collection.");
+
+    let coll = db.collection("readings");
+    let docs = vec![
+        doc! { "ts": Utc::now(), "sensor": "a", "temp": 20 },
+        doc! { "_id": 7, "ts": Utc::now(), "sensor": "b", "temp": 21 },
+    ];
+
+    let result = coll.insert_many(docs, None).expect("Failed to insert readings.");
+    let ids = result.inserted_ids.expect("No ids were returned.");
+    assert_eq!(Some(&Bson::I32(7)), ids.get(&1));
+    assert_eq!(2, coll.count(None, None).expect("Failed to count readings."));
+
+    let spec = coll.specification()
+        .expect("Failed to list collections.")
+        .expect("The collection was not listed.");
+
+    assert_eq!(CollectionType::Timeseries, spec.collection_type);
+    assert_eq!(Some(86400), spec.expire_after_seconds());
+
+    let listed = spec.timeseries().expect("No time-series options were listed.");
+    assert_eq!(timeseries.time_field, listed.time_field);
+    assert_eq!(timeseries.meta_field, listed.meta_field);
+    assert_eq!(timeseries.granularity, listed.granularity);
+}
+
+#[test]
+fn create_clustered_collection() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-db-create_clustered_collection");
+    skip_if_db_version_below!(db, 5, 3);
+    db.drop_database().unwrap();
+
+    let mut options = CreateCollectionOptions::new();
+    options.clustered_index = Some(ClusteredIndex::new());
+
+    db.create_collection("events", Some(options)).expect("Failed to create collection.");
+
+    let coll = db.collection("events");
+    coll.insert_many(vec![doc! { "_id": 2 }, doc! { "x": 1 }], None)
+        .expect("Failed to insert documents.");
+
+    let spec = coll.specification().unwrap().unwrap();
+    let clustered_index = spec.clustered_index().expect("No clustered index was listed.");
+    assert_eq!(doc! { "_id": 1 }, clustered_index.key);
+    assert!(clustered_index.unique);
+}
+
+#[test]
+fn rename_collection() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-db-rename_collection");
+    db.drop_database().unwrap();
+
+    let coll = db.collection("before");
+    coll.insert_one(doc! { "x": 1 }, None).expect("Failed to insert document.");
+    db.collection("after").insert_one(doc! { "x": 2 }, None).expect("Failed to insert document.");
+
+    assert!(coll.rename("after", false).is_err());
+    coll.rename("after", true).expect("Failed to rename collection.");
+
+    let names = db.collection_names(None).expect("Failed to list collection names.");
+    assert!(!names.contains(&String::from("before")));
+
+    let doc = db.collection("after").find_one(None, None).unwrap().unwrap();
+    assert_eq!(Some(&Bson::I32(1)), doc.get("x"));
+}
+
+#[test]
+fn database_stats() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-db-database_stats");
+    db.drop_database().unwrap();
+
+    db.collection("a").insert_many(vec![doc! { "x": 1 }, doc! { "x": 2 }], None)
+        .expect("Failed to insert documents.");
+    db.collection("b").insert_one(doc! { "x": 3 }, None).expect("Failed to insert document.");
+
+    let stats = db.stats(None).expect("Failed to get database stats.");
+    assert_eq!("test-client-db-database_stats", stats.db);
+    assert_eq!(2, stats.collections);
+    assert_eq!(3, stats.objects);
+    assert_eq!(2, stats.indexes);
+    assert_eq!(1, stats.scale_factor);
+
+    let scaled = db.stats(Some(1024)).expect("Failed to get scaled database stats.");
+    assert_eq!(1024, scaled.scale_factor);
+}
+
+#[test]
+fn profiler_records_operations() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-db-profiler_records_operations");
+    db.drop_database().unwrap();
+
+    db.set_profiling_level(ProfilingLevel::All, Some(50), None, None)
+        .expect("Failed to enable the profiler.");
+
+    let status = db.get_profiling_status().expect("Failed to get profiling status.");
+    assert_eq!(ProfilingLevel::All, status.level);
+    assert_eq!(50, status.slow_ms);
+
+    let coll = db.collection("orders");
+    coll.insert_one(doc! { "x": 1 }, None).expect("Failed to insert document.");
+    coll.find_one(Some(doc! { "x": 1 }), None).expect("Failed to find document.");
+
+    let previous = db.set_profiling_level(ProfilingLevel::Off, Some(100), None, None)
+        .expect("Failed to disable the profiler.");
+    assert_eq!(ProfilingLevel::All, previous.level);
+
+    let filter = doc! { "ns": "test-client-db-profiler_records_operations.orders" };
+    let entries: Vec<_> = db.profile_entries(Some(filter))
+        .expect("Failed to read system.profile.")
+        .collect::<Result<_, _>>()
+        .expect("Failed to read profile entry.");
+
+    let find = entries.iter().find(|entry| entry.op == "query").expect("No find was profiled.");
+    assert_eq!(Some(String::from("COLLSCAN")), find.plan_summary);
+    assert_eq!(Some(1), find.docs_examined);
+    assert!(entries.iter().any(|entry| entry.op == "insert"));
+}
+
 #[test]
 fn create_and_get_users() {
     let client = Client::connect("localhost", 27017).unwrap();
@@ -187,6 +389,80 @@ fn create_and_get_users() {
     };
 }
 
+#[test]
+fn manage_users_and_roles() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-db-manage_users_and_roles");
+    db.drop_database().unwrap();
+    db.drop_all_users(None).unwrap();
+    let _ = db.drop_role("orderReader", None);
+
+    let mut role_options = CreateRoleOptions::new();
+    role_options.privileges.push(Privilege::new(
+        Resource::collection("test-client-db-manage_users_and_roles", "orders"),
+        vec!["find"],
+    ));
+    db.create_role("orderReader", Some(role_options)).expect("Failed to create role.");
+
+    let insert = Privilege::new(
+        Resource::database("test-client-db-manage_users_and_roles"),
+        vec!["insert"],
+    );
+    db.grant_privileges_to_role("orderReader", vec![insert.clone()], None)
+        .expect("Failed to grant privileges.");
+
+    let info_options = RoleInfoOptions { show_privileges: Some(true), ..RoleInfoOptions::new() };
+    let role = db.get_role("orderReader", Some(info_options)).unwrap();
+    match role.get("privileges") {
+        Some(&Bson::Array(ref privileges)) => assert_eq!(2, privileges.len()),
+        _ => panic!("Role was listed without privileges."),
+    }
+
+    db.revoke_privileges_from_role("orderReader", vec![insert], None)
+        .expect("Failed to revoke privileges.");
+
+    let mut update_role = UpdateRoleOptions::new();
+    update_role.roles = Some(vec![Role::All(AllDatabaseRole::Read)]);
+    db.update_role("orderReader", update_role).expect("Failed to update role.");
+
+    let roles = db.get_all_roles(None).unwrap();
+    assert_eq!(1, roles.len());
+
+    let custom = Role::Custom {
+        role: String::from("orderReader"),
+        db: String::from("test-client-db-manage_users_and_roles"),
+    };
+
+    db.create_user("reporting", "1234", None).unwrap();
+    db.grant_roles_to_user("reporting", vec![custom.clone()], None)
+        .expect("Failed to grant roles.");
+
+    let user = db.get_user("reporting", None).unwrap();
+    match user.get("roles") {
+        Some(&Bson::Array(ref roles)) => assert_eq!(vec![custom.to_bson()], *roles),
+        _ => panic!("User was listed without roles."),
+    }
+
+    db.revoke_roles_from_user("reporting", vec![custom], None)
+        .expect("Failed to revoke roles.");
+
+    let mut update_user = UpdateUserOptions::new();
+    update_user.custom_data = Some(doc! { "team": "analytics" });
+    db.update_user("reporting", update_user).expect("Failed to update user.");
+    db.change_user_password("reporting", "5678", None).expect("Failed to change password.");
+
+    let user = db.get_user("reporting", None).unwrap();
+    assert_eq!(Some(&Bson::Document(doc! { "team": "analytics" })), user.get("customData"));
+    match user.get("roles") {
+        Some(&Bson::Array(ref roles)) => assert!(roles.is_empty()),
+        _ => panic!("User was listed without roles."),
+    }
+
+    db.drop_user("reporting", None).unwrap();
+    db.drop_role("orderReader", None).expect("Failed to drop role.");
+    assert!(db.get_role("orderReader", None).is_err());
+}
+
 #[test]
 fn get_version() {
     let client = Client::connect("localhost", 27017).unwrap();
@@ -1,5 +1,6 @@
 use bson::{self, Bson};
-use mongodb::{DRIVER_NAME, Client, ThreadedClient};
+use mongodb::{DRIVER_NAME, Client, ClientOptions, ThreadedClient};
+use mongodb::handshake::DriverInfo;
 use mongodb::db::ThreadedDatabase;
 use mongodb::CommandType;
 
@@ -11,10 +12,16 @@ struct Metadata {
 
 #[derive(Debug, Deserialize)]
 struct ClientMetadata {
+    pub application: Option<ApplicationMetadata>,
     pub driver: DriverMetadata,
     pub os: OsMetadata,
 }
 
+#[derive(Debug, Deserialize)]
+struct ApplicationMetadata {
+    pub name: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct DriverMetadata {
     pub name: String,
@@ -44,3 +51,40 @@ fn metadata_sent_in_handshake() {
     assert_eq!(metadata.client.driver.name, DRIVER_NAME);
 }
 
+#[test]
+fn app_name_and_driver_info_sent_in_handshake() {
+    let mut options = ClientOptions::new();
+    options.app_name = Some(String::from("test-client-handshake"));
+    options.driver_info = Some(DriverInfo::new("wrapper"));
+
+    let client = Client::connect_with_options("localhost", 27017, options).unwrap();
+    skip_if_db_version_below!(client.db("admin"), 3, 6);
+
+    let filter = doc! { "appName": "test-client-handshake" };
+    let operation = client
+        .current_op(Some(filter))
+        .unwrap()
+        .next()
+        .expect("The aggregation reporting current operations was not listed.")
+        .unwrap();
+
+    let metadata = operation.raw.get("clientMetadata").expect("No client metadata reported.");
+    let metadata: ClientMetadata = bson::from_bson(metadata.clone()).unwrap();
+
+    let application = metadata.application.expect("No application name was sent.");
+    assert_eq!("test-client-handshake", application.name);
+    assert_eq!(format!("{}|wrapper", DRIVER_NAME), metadata.driver.name);
+}
+
+#[test]
+fn app_name_from_uri() {
+    let client = Client::with_uri("mongodb://localhost:27017/?appName=uri-app").unwrap();
+    skip_if_db_version_below!(client.db("admin"), 3, 6);
+
+    let mut operations = client.current_op(Some(doc! { "appName": "uri-app" })).unwrap();
+    assert!(operations.next().is_some());
+
+    let long_name: String = ::std::iter::repeat('a').take(129).collect();
+    let uri = format!("mongodb://localhost:27017/?appName={}", long_name);
+    assert!(Client::with_uri(&uri).is_err());
+}
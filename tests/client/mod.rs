@@ -11,9 +11,12 @@ mod handshake;
 mod wire_protocol;
 
 use bson;
-use mongodb::{Client, ThreadedClient};
+use mongodb::{Client, ClientOptions, CommandType, Error, ThreadedClient};
+use mongodb::common::{ReadConcern, ReadConcernLevel};
 use mongodb::db::ThreadedDatabase;
+use mongodb::db::options::ListDatabasesOptions;
 use std::thread;
+use std::time::{Duration, Instant};
 
 #[test]
 fn is_master() {
@@ -69,6 +72,40 @@ fn database_names() {
     ));
 }
 
+#[test]
+fn list_databases() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let db = client.db("test-client-mod-list_databases");
+    db.drop_database().expect("Failed to drop database");
+    db.collection("test")
+        .insert_one(doc! { "x": 1 }, None)
+        .expect("Failed to insert placeholder document into collection");
+
+    let filter = doc! { "name": { "$regex": "^test-client-mod-list_databases" } };
+    let names = client.database_names_with_filter(Some(filter.clone())).expect(
+        "Failed to execute database_names.",
+    );
+    assert_eq!(vec![String::from("test-client-mod-list_databases")], names);
+
+    let result = client.list_databases(Some(filter.clone()), None).expect(
+        "Failed to execute list_databases.",
+    );
+    assert_eq!(1, result.databases.len());
+    assert!(result.total_size.is_some());
+
+    let database = &result.databases[0];
+    assert_eq!("test-client-mod-list_databases", database.name);
+    assert_eq!(Some(false), database.empty);
+    assert!(database.size_on_disk.unwrap() > 0);
+
+    let options = ListDatabasesOptions { name_only: Some(true), ..ListDatabasesOptions::new() };
+    let result = client.list_databases(Some(filter), Some(options)).expect(
+        "Failed to execute list_databases.",
+    );
+    assert_eq!(None, result.total_size);
+    assert_eq!(None, result.databases[0].size_on_disk);
+}
+
 #[test]
 fn is_sync() {
     let client = Client::connect("localhost", 27017).unwrap();
@@ -130,3 +167,103 @@ fn is_sync() {
     assert!(results.contains(&"test-client-mod-is_sync".to_owned()));
     assert!(results.contains(&"test-client-mod-is_sync_2".to_owned()));
 }
+
+#[test]
+fn read_concern_from_uri() {
+    let client = Client::with_uri("mongodb://localhost:27017/?readConcernLevel=majority").unwrap();
+    assert_eq!(Some(ReadConcern::new(ReadConcernLevel::Majority)), client.read_concern);
+
+    let db = client.db("test-client-mod-read_concern_from_uri");
+    assert_eq!(Some(ReadConcern::new(ReadConcernLevel::Majority)), db.read_concern);
+
+    let db = client.db_with_read_concern(
+        "test-client-mod-read_concern_from_uri",
+        None,
+        None,
+        Some(ReadConcern::new(ReadConcernLevel::Local)),
+    );
+    assert_eq!(Some(ReadConcern::new(ReadConcernLevel::Local)), db.read_concern);
+
+    assert!(Client::with_uri("mongodb://localhost:27017/?readConcernLevel=bogus").is_err());
+}
+
+#[test]
+fn timeout_ms_bounds_server_selection() {
+    let mut options = ClientOptions::new();
+    options.timeout_ms = Some(200);
+
+    // Nothing listens on this port, so server selection can never succeed.
+    let client = Client::connect_with_options("localhost", 1, options).unwrap();
+    let db = client.db("test-client-mod-timeout_ms_bounds_server_selection");
+
+    let start = Instant::now();
+    match db.command(doc! { "ping": 1 }, CommandType::Suppressed, None) {
+        Err(Error::TimeoutError) => (),
+        other => panic!("Expected a TimeoutError, but got {:?}", other),
+    }
+
+    // The server selection timeout defaults to 30 seconds.
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn timeout_ms_must_be_positive() {
+    let mut options = ClientOptions::new();
+    options.timeout_ms = Some(0);
+
+    match Client::connect_with_options("localhost", 27017, options) {
+        Err(Error::ArgumentError(_)) => (),
+        other => panic!("Expected an ArgumentError, but got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn server_status() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let status = client.server_status().expect("Failed to get server status.");
+
+    assert!(!status.version.is_empty());
+    assert!(status.process.starts_with("mongo"));
+    assert!(status.connections.current > 0);
+}
+
+#[test]
+fn current_op_and_kill_op() {
+    let client = Client::connect("localhost", 27017).unwrap();
+    let coll = client.db("test-client-mod-current_op_and_kill_op").collection("blocked");
+    coll.drop().unwrap();
+    let documents = (0..20).map(|x| doc! { "x": x }).collect();
+    coll.insert_many(documents, None).unwrap();
+
+    // Run a query that sleeps for a second per document on another thread, so that it is still
+    // in progress long after it shows up in the operation list.
+    let slow = {
+        let client = client.clone();
+        thread::spawn(move || {
+            let db = client.db("test-client-mod-current_op_and_kill_op");
+            let filter = doc! { "$where": "sleep(1000) || true" };
+            db.collection("blocked").find(Some(filter), None).map(|cursor| cursor.count())
+        })
+    };
+
+    let mut opid = None;
+    for _ in 0..50 {
+        let filter = doc! { "ns": "test-client-mod-current_op_and_kill_op.blocked" };
+        let ops = client.current_op(Some(filter)).expect("Failed to list operations.");
+        opid = ops.filter_map(|op| op.ok()).filter_map(|op| op.opid).next();
+
+        if opid.is_some() {
+            break;
+        }
+
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    let opid = opid.expect("The slow query was never reported as in progress.");
+    client.kill_op(opid).expect("Failed to kill operation.");
+
+    match slow.join().unwrap() {
+        Err(Error::OperationError(ref msg)) if msg.starts_with("Interrupted (11601)") => (),
+        result => panic!("Expected the killed query to be interrupted, got {:?}", result),
+    }
+}
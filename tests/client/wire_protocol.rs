@@ -1,7 +1,8 @@
 use bson::{Bson, Document};
 use mongodb::{Client, ThreadedClient};
 use mongodb::db::ThreadedDatabase;
-use mongodb::wire_protocol::flags::{OpInsertFlags, OpQueryFlags, OpUpdateFlags};
+use mongodb::wire_protocol::flags::{OpDeleteFlags, OpInsertFlags, OpMsgFlags, OpQueryFlags,
+                                    OpUpdateFlags};
 use mongodb::wire_protocol::operations::Message;
 use std::net::TcpStream;
 
@@ -296,3 +297,28 @@ fn insert_update_then_query() {
         Err(_) => panic!("Could not connect to server"),
     }
 }
+
+#[test]
+fn encoded_delete_and_msg_lengths_match_headers() {
+    let name = "test-client-wire_protocol-encoded_lengths.delete".to_owned();
+    let delete = Message::new_delete(1, name, OpDeleteFlags::SINGLE_REMOVE, doc! { "foo": "bar" })
+        .expect("Could not create delete message!");
+
+    let msg = Message::new_msg(
+        2,
+        OpMsgFlags::MORE_TO_COME,
+        doc! { "insert": "delete", "$db": "test-client-wire_protocol-encoded_lengths" },
+    ).expect("Could not create OP_MSG!");
+
+    for message in vec![delete, msg] {
+        let length = match message {
+            Message::OpDelete { ref header, .. } |
+            Message::OpMsg { ref header, .. } => header.message_length,
+            _ => panic!("Unexpected message type!"),
+        };
+
+        let mut buffer = Vec::new();
+        message.write(&mut buffer).expect("Could not write message!");
+        assert_eq!(length as usize, buffer.len());
+    }
+}
@@ -42,6 +42,7 @@
 extern crate approx;
 #[macro_use(bson, doc)]
 extern crate bson;
+extern crate chrono;
 extern crate mongodb;
 extern crate rand;
 extern crate semver;